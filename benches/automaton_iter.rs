@@ -1,8 +1,9 @@
 // 2025 Steven Chiacchira
 use std::hint::black_box;
+use std::mem;
 
 use criterion::{criterion_group, criterion_main, Criterion};
-use talos::automata::ToroidalAutomaton;
+use talos::automata::{self, ToroidalAutomaton};
 use talos::encrypt::{AUTOMATA_RULE, N_COLS, N_ROWS};
 use talos::matrix::{ToroidalBinaryMatrix, ToroidalBitMatrix, ToroidalBoolMatrix};
 
@@ -18,7 +19,7 @@ pub fn generate_bool_table(rows: usize, cols: usize) -> Vec<Vec<bool>> {
     result
 }
 
-criterion_group!(benches, automata_black_box);
+criterion_group!(benches, automata_black_box, lookup_table);
 criterion_main!(benches);
 
 fn automata_black_box(c: &mut Criterion) {
@@ -48,4 +49,79 @@ fn automata_black_box(c: &mut Criterion) {
     group.bench_function("Automaton<ToroidalBitMatrix<u32>>.iter_rule(1_000)", |b| {
         b.iter(|| automaton_u32.iter_rule(N_ITERS))
     });
+
+    let mut state_u8 = black_box(ToroidalBitMatrix::<u8>::new(table.clone()).unwrap());
+    let mut next_u8 = state_u8.clone();
+    let mut state_u32 = black_box(ToroidalBitMatrix::<u32>::new(table.clone()).unwrap());
+    let mut next_u32 = state_u32.clone();
+
+    group.bench_function("ToroidalBitMatrix<u8>.next_generation x 1_000", |b| {
+        b.iter(|| {
+            for _ in 0..N_ITERS {
+                state_u8
+                    .next_generation(&AUTOMATA_RULE, &mut next_u8)
+                    .unwrap();
+                mem::swap(&mut state_u8, &mut next_u8);
+            }
+        })
+    });
+    group.bench_function("ToroidalBitMatrix<u32>.next_generation x 1_000", |b| {
+        b.iter(|| {
+            for _ in 0..N_ITERS {
+                state_u32
+                    .next_generation(&AUTOMATA_RULE, &mut next_u32)
+                    .unwrap();
+                mem::swap(&mut state_u32, &mut next_u32);
+            }
+        })
+    });
+}
+
+fn lookup_table(c: &mut Criterion) {
+    const N_ITERS: u32 = 1_000;
+
+    let mut group = c.benchmark_group("Lookup Table");
+
+    let table = generate_bool_table(N_ROWS, N_COLS);
+
+    let mut state_bool = black_box(ToroidalBoolMatrix::new(table.clone()).unwrap());
+    let mut next_bool = state_bool.clone();
+    let mut state_u8 = black_box(ToroidalBitMatrix::<u8>::new(table.clone()).unwrap());
+    let mut next_u8 = state_u8.clone();
+
+    let mut automaton_bool = ToroidalAutomaton::<ToroidalBoolMatrix>::new(
+        ToroidalBoolMatrix::new(table.clone()).unwrap(),
+        AUTOMATA_RULE.clone(),
+    )
+    .with_lookup_table();
+    let mut automaton_u8 = ToroidalAutomaton::<ToroidalBitMatrix<u8>>::new(
+        ToroidalBitMatrix::<u8>::new(table.clone()).unwrap(),
+        AUTOMATA_RULE.clone(),
+    )
+    .with_lookup_table();
+
+    group.bench_function("step_cells<ToroidalBoolMatrix> x 1_000", |b| {
+        b.iter(|| {
+            for _ in 0..N_ITERS {
+                automata::step_cells(&state_bool, &AUTOMATA_RULE, &mut next_bool).unwrap();
+                mem::swap(&mut state_bool, &mut next_bool);
+            }
+        })
+    });
+    group.bench_function("step_cells<ToroidalBitMatrix<u8>> x 1_000", |b| {
+        b.iter(|| {
+            for _ in 0..N_ITERS {
+                automata::step_cells(&state_u8, &AUTOMATA_RULE, &mut next_u8).unwrap();
+                mem::swap(&mut state_u8, &mut next_u8);
+            }
+        })
+    });
+    group.bench_function(
+        "Automaton<ToroidalBoolMatrix>.with_lookup_table().iter_rule(1_000)",
+        |b| b.iter(|| automaton_bool.iter_rule(N_ITERS)),
+    );
+    group.bench_function(
+        "Automaton<ToroidalBitMatrix<u8>>.with_lookup_table().iter_rule(1_000)",
+        |b| b.iter(|| automaton_u8.iter_rule(N_ITERS)),
+    );
 }
@@ -3,7 +3,22 @@ use std::hint::black_box;
 use std::time::Duration;
 
 use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
-use talos::encrypt::{decrypt_message_256, encrypt_message_256, get_shift_transpose_automata};
+use talos::encrypt::{
+    decrypt_message_256, encrypt_message_256, get_shift_transpose_automata_with_params,
+    CipherParams, TalosAutomaton, GEOMETRY_256,
+};
+use talos::key::TalosKey;
+
+/// Prepares the automata of RFC-1 for the 32 bit key `seed`.
+fn get_automata(seed: u32) -> (TalosAutomaton, TalosAutomaton) {
+    get_shift_transpose_automata_with_params(
+        &CipherParams::rfc1(),
+        &GEOMETRY_256,
+        &TalosKey::Bits32(seed),
+        None,
+    )
+    .unwrap()
+}
 
 #[must_use]
 pub fn generate_message(n_elements: usize) -> Vec<u8> {
@@ -32,7 +47,7 @@ fn encryption_decryption_in_memory(c: &mut Criterion) {
         b.iter_batched(
             || {
                 let message_clone = message.clone();
-                let (s_automaton, t_automaton) = get_shift_transpose_automata(key);
+                let (s_automaton, t_automaton) = get_automata(key);
                 (message_clone, s_automaton, t_automaton)
             },
             |(message_clone, mut s_automaton, mut t_automaton)| {
@@ -48,7 +63,7 @@ fn encryption_decryption_in_memory(c: &mut Criterion) {
         b.iter_batched(
             || {
                 let message_clone = message.clone();
-                let (s_automaton, t_automaton) = get_shift_transpose_automata(key);
+                let (s_automaton, t_automaton) = get_automata(key);
                 (message_clone, s_automaton, t_automaton)
             },
             |(message_clone, mut s_automaton, mut t_automaton)| {
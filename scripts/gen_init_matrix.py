@@ -0,0 +1,70 @@
+# 2025 Steven Chiacchira
+"""Generates the initialization matrices in data/init_matrix which are not given in the RFCs.
+
+Variable cells reference key bits with the digits of `talos::parse::key_digit`: base-32 digits
+for bits 0 through 31, and the braille pattern U+2800 + i for bit i otherwise.
+
+* `*_init_matrix_128.txt` keep the fixed cells of the RFC-0 matrices, but give each of their 128
+  variable cells a distinct key bit.
+* `*_init_matrix_{d}x{d}.txt` are d x d matrices with the checkerboard layout of the RFC-0
+  matrices, whose fixed cells are drawn at random and whose variable cells reference each of 256
+  key bits equally often.
+
+Run from the project root with `python scripts/gen_init_matrix.py`.
+"""
+import random
+
+BASE_32_DIGITS = "ABCDEFGHIJKLMNOPQRSTUVWXYZ234567"
+INIT_MATRIX_DIR = "data/init_matrix"
+# odd multipliers, so that each permutation is a bijection modulo any power of two
+SLOT_PERMUTATIONS = {
+    "S": lambda k, n_slots: (k * 37) % n_slots,
+    "T": lambda k, n_slots: (k * 53 + 17) % n_slots,
+}
+
+
+def key_digit(bit: int) -> str:
+    return BASE_32_DIGITS[bit] if bit < 32 else chr(0x2800 + bit)
+
+
+def gen_wide_matrix(name: str) -> str:
+    with open(f"{INIT_MATRIX_DIR}/{name}_init_matrix.txt") as f:
+        template = f.read()
+
+    result = []
+    n_variable = 0
+    for c in template:
+        if c in BASE_32_DIGITS:
+            result.append(key_digit(SLOT_PERMUTATIONS[name](n_variable, 128)))
+            n_variable += 1
+        else:
+            result.append(c)
+    assert n_variable == 128
+
+    return "".join(result)
+
+
+def gen_square_matrix(name: str, dim: int, n_slots: int = 256) -> str:
+    rng = random.Random(f"{name}{dim}")
+    rows = []
+    n_variable = 0
+    for row in range(dim):
+        line = []
+        for col in range(dim):
+            if (row + col) % 2 == 1:
+                line.append(key_digit(SLOT_PERMUTATIONS[name](n_variable, n_slots)))
+                n_variable += 1
+            else:
+                line.append(rng.choice("#."))
+        rows.append("".join(line))
+
+    return "\n".join(rows) + "\n"
+
+
+if __name__ == "__main__":
+    for name in SLOT_PERMUTATIONS:
+        with open(f"{INIT_MATRIX_DIR}/{name}_init_matrix_128.txt", "w") as f:
+            f.write(gen_wide_matrix(name))
+        for dim in [32, 64]:
+            with open(f"{INIT_MATRIX_DIR}/{name}_init_matrix_{dim}x{dim}.txt", "w") as f:
+                f.write(gen_square_matrix(name, dim))
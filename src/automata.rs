@@ -1,7 +1,10 @@
 // 2025 Steven Chiacchira
-use crate::matrix::{ToroidalBinaryMatrix, ToroidalMatrixIndex};
+use crate::matrix::{MatrixOpError, ToroidalBinaryMatrix, ToroidalMatrixIndex};
+use std::borrow::Cow;
+use std::error::Error;
 use std::fmt;
 use std::mem;
+use std::str::FromStr;
 
 /// The character used to represent a [`ToroidalAutomaton`]'s `true` state in files and `String`
 /// representations.
@@ -9,28 +12,588 @@ const TRUE_CHAR: char = '#';
 /// The character used to represent a [`ToroidalAutomaton`]'s `false` state in files and String
 /// representations.
 const FALSE_CHAR: char = '.';
+/// The largest radius of a [`Neighborhood::ExtendedMoore`] neighborhood. A radius of 32 already
+/// reaches every cell of a 64x64 block, the largest block of the Talos protocol.
+pub const MAX_RADIUS: usize = 32;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// The cells whose states decide the next state of a cell, given as offsets from the cell.
+///
+/// Offsets are `(row, column)` pairs, and wrap around the torus like any other
+/// [`ToroidalMatrixIndex`].
+pub enum Neighborhood {
+    /// The [Moore neighborhood](https://en.wikipedia.org/wiki/Moore_neighborhood) of the 8 cells
+    /// surrounding a cell.
+    Moore,
+    /// The [von Neumann neighborhood](https://en.wikipedia.org/wiki/Von_Neumann_neighborhood) of
+    /// the 4 cells sharing an edge with a cell.
+    VonNeumann,
+    /// The 6 neighbors of a cell on a hexagonal grid, laid out on the torus by shifting each row
+    /// half a cell left of the row above. These are the Moore neighbors of the cell other than
+    /// its north-east and south-west neighbors.
+    Hexagonal,
+    /// The `(2r + 1)^2 - 1` cells at most `r` rows and `r` columns away from a cell. A radius of
+    /// 1 gives the Moore neighborhood. Rules only accept radii of at most [`MAX_RADIUS`].
+    ExtendedMoore(usize),
+    /// An arbitrary, nonempty list of offsets. A cell is counted once for every offset reaching
+    /// it.
+    Offsets(Cow<'static, [ToroidalMatrixIndex]>),
+}
+
+impl Neighborhood {
+    /// Lists the offsets of the `Neighborhood`.
+    ///
+    /// # Returns
+    /// The `(row, column)` offset of each neighbor from the cell whose neighbors are counted.
+    #[must_use]
+    pub fn offsets(&self) -> Vec<ToroidalMatrixIndex> {
+        match self {
+            Self::Moore => Self::ExtendedMoore(1).offsets(),
+            Self::VonNeumann => vec![(-1, 0), (0, -1), (0, 1), (1, 0)],
+            Self::Hexagonal => vec![(-1, -1), (-1, 0), (0, -1), (0, 1), (1, 0), (1, 1)],
+            Self::ExtendedMoore(radius) => {
+                let radius = *radius as isize;
+                (-radius..=radius)
+                    .flat_map(|row| (-radius..=radius).map(move |col| (row, col)))
+                    .filter(|offset| *offset != (0, 0))
+                    .collect()
+            }
+            Self::Offsets(offsets) => offsets.to_vec(),
+        }
+    }
+
+    /// Returns the number of neighbors in the `Neighborhood`, which is the largest neighbor count
+    /// a cell can have.
+    ///
+    /// # Returns
+    /// The number of offsets of the `Neighborhood`, or `None` if it does not fit in a `usize`.
+    #[must_use]
+    pub fn size(&self) -> Option<usize> {
+        match self {
+            Self::Moore => Some(8),
+            Self::VonNeumann => Some(4),
+            Self::Hexagonal => Some(6),
+            Self::ExtendedMoore(radius) => radius
+                .checked_mul(2)
+                .and_then(|width| width.checked_add(1))
+                .and_then(|width| width.checked_mul(width))
+                .map(|n_cells| n_cells - 1),
+            Self::Offsets(offsets) => Some(offsets.len()),
+        }
+    }
+
+    /// Returns the greatest number of rows or columns separating a cell from one of its neighbors.
+    ///
+    /// # Returns
+    /// The radius of the `Neighborhood`, or 0 if it has no neighbors.
+    #[must_use]
+    pub fn radius(&self) -> usize {
+        if let Self::ExtendedMoore(radius) = self {
+            return *radius;
+        }
+        self.offsets()
+            .iter()
+            .map(|(row, col)| row.unsigned_abs().max(col.unsigned_abs()))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Returns the suffix naming the `Neighborhood` in B/S notation. See [`AutomatonRule`].
+    fn notation_suffix(&self) -> String {
+        match self {
+            Self::Moore => String::new(),
+            Self::VonNeumann => "V".to_string(),
+            Self::Hexagonal => "H".to_string(),
+            Self::ExtendedMoore(radius) => format!("R{}", radius),
+            Self::Offsets(offsets) => {
+                let offsets: String = offsets
+                    .iter()
+                    .map(|(row, col)| format!("({},{})", row, col))
+                    .collect();
+                format!("N{}", offsets)
+            }
+        }
+    }
+
+    /// Parses the suffix naming a `Neighborhood` in B/S notation. See [`AutomatonRule`].
+    fn from_notation_suffix(suffix: &str) -> Result<Self, RuleParseError> {
+        match suffix {
+            "" => Ok(Self::Moore),
+            "V" | "v" => Ok(Self::VonNeumann),
+            "H" | "h" => Ok(Self::Hexagonal),
+            _ => {
+                if let Some(radius) = suffix.strip_prefix(['R', 'r']) {
+                    return match radius.parse::<usize>() {
+                        Ok(radius) if (1..=MAX_RADIUS).contains(&radius) => {
+                            Ok(Self::ExtendedMoore(radius))
+                        }
+                        _ => Err(RuleParseError::InvalidNeighborhood()),
+                    };
+                }
+
+                let offsets = suffix
+                    .strip_prefix(['N', 'n'])
+                    .and_then(|offsets| offsets.strip_prefix('('))
+                    .and_then(|offsets| offsets.strip_suffix(')'))
+                    .ok_or(RuleParseError::InvalidNeighborhood())?;
+                let offsets = offsets
+                    .split(")(")
+                    .map(|offset| {
+                        let (row, col) = offset.split_once(',')?;
+                        Some((row.trim().parse().ok()?, col.trim().parse().ok()?))
+                    })
+                    .collect::<Option<Vec<ToroidalMatrixIndex>>>()
+                    .ok_or(RuleParseError::InvalidNeighborhood())?;
+
+                Ok(Self::Offsets(Cow::Owned(offsets)))
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
 /// Defines how a [`ToroidalAutomaton`] will change from one state to the next.
 ///
-/// This struct is limited to symmetrical cellular automaton rules defined over a (Moore Neighborhood)[<https://en.wikipedia.org/wiki/Moore_neighborhood>].
+/// This struct is limited to symmetrical cellular automaton rules defined over a [`Neighborhood`].
 /// Roughly speaking, a cellular automaton rule is symmetric if it only considers the *number* of
 /// alive and dead neighbors for a given cell.
+///
+/// Rules are parsed from and displayed in the B/S notation of life-like automata, which lists the
+/// neighbor counts at which a dead cell is born, then those at which a living cell survives. A
+/// living cell dies at every count it does not survive at. Rules over a neighborhood other than
+/// the Moore neighborhood end with a suffix naming it: `V` for the von Neumann neighborhood, `H`
+/// for the hexagonal neighborhood, `R` and the radius for an extended Moore neighborhood, or `N`
+/// and a list of `(row,column)` offsets. Neighborhoods of 10 or more cells separate their counts
+/// with commas.
+///
+/// # Examples
+/// Conway's Game of Life is `B3/S23`, and the rule of the Talos protocol is `B23456/S234`. The
+/// rule `B2/S013V` is over the von Neumann neighborhood, `B3,4,5/S2,3,4,5,6R2` over the 5x5
+/// extended Moore neighborhood, and `B1/S1N(-1,0)(0,1)` over the neighbors above and to the right
+/// of a cell.
+///
+/// Rules are created with [`AutomatonRule::new`] or parsed from B/S notation, both of which check
+/// that the `born` and `dies` tables hold one element for each neighbor count.
 pub struct AutomatonRule {
-    /// A 9-element array of booleans. If the ith element is `true`, then a dead cell with `i`
-    /// alive neighbors will become alive.
+    /// One boolean for each neighbor count from 0 to the size of `neighborhood`. If the ith
+    /// element is `true`, then a dead cell with `i` alive neighbors will become alive.
     ///
     /// # Examples
-    /// The `born` array `[true, true, false, false, false, false, false, false, false]`
-    /// specifies that only cells with 0 or 1 neighboring alive cells will become alive.
-    pub born: [bool; 9],
-    /// A 9-element array of booleans. If the ith element is `true`, then a living cell with `i`
-    /// alive neighbors will die.
+    /// The Moore neighborhood `born` table `[true, true, false, false, false, false, false, false,
+    /// false]` specifies that only cells with 0 or 1 neighboring alive cells will become alive.
+    pub(crate) born: Cow<'static, [bool]>,
+    /// One boolean for each neighbor count from 0 to the size of `neighborhood`. If the ith
+    /// element is `true`, then a living cell with `i` alive neighbors will die.
     ///
     /// # Examples
-    /// The `dies` array `[true, true, false, false, false, false, false, false, false]`
-    /// specifies that only cells with 0 or 1 neighboring alive cells will die.
-    pub dies: [bool; 9],
+    /// The Moore neighborhood `dies` table `[true, true, false, false, false, false, false, false,
+    /// false]` specifies that only cells with 0 or 1 neighboring alive cells will die.
+    pub(crate) dies: Cow<'static, [bool]>,
+    /// The cells whose alive neighbors are counted.
+    pub(crate) neighborhood: Neighborhood,
+}
+
+impl AutomatonRule {
+    /// Creates a new `AutomatonRule`, checking that its tables fit its neighborhood.
+    ///
+    /// # Arguments
+    /// * `neighborhood` - the cells whose alive neighbors are counted
+    /// * `born` - the `born` table of the rule. See [`AutomatonRule::born()`]
+    /// * `dies` - the `dies` table of the rule. See [`AutomatonRule::dies()`]
+    ///
+    /// # Returns
+    /// The created `AutomatonRule`, or `None` if `neighborhood` has no neighbors or a radius
+    /// greater than [`MAX_RADIUS`], or `born` or `dies` do not have one element more than the
+    /// size of `neighborhood`.
+    #[must_use]
+    pub fn new(neighborhood: Neighborhood, born: Vec<bool>, dies: Vec<bool>) -> Option<Self> {
+        if matches!(neighborhood, Neighborhood::ExtendedMoore(radius) if radius > MAX_RADIUS) {
+            return None;
+        }
+        let n_neighbors = neighborhood.size()?;
+        // rules without neighbors have no B/S notation
+        if n_neighbors == 0 {
+            return None;
+        }
+        let n_counts = n_neighbors.checked_add(1)?;
+        if born.len() != n_counts || dies.len() != n_counts {
+            return None;
+        }
+
+        Some(Self {
+            born: Cow::Owned(born),
+            dies: Cow::Owned(dies),
+            neighborhood,
+        })
+    }
+
+    /// Returns the `born` table of the `AutomatonRule`.
+    ///
+    /// # Returns
+    /// One boolean for each neighbor count from 0 to the size of the neighborhood. If the ith
+    /// element is `true`, then a dead cell with `i` alive neighbors will become alive.
+    #[must_use]
+    pub fn born(&self) -> &[bool] {
+        &self.born
+    }
+
+    /// Returns the `dies` table of the `AutomatonRule`.
+    ///
+    /// # Returns
+    /// One boolean for each neighbor count from 0 to the size of the neighborhood. If the ith
+    /// element is `true`, then a living cell with `i` alive neighbors will die.
+    #[must_use]
+    pub fn dies(&self) -> &[bool] {
+        &self.dies
+    }
+
+    /// Returns the neighborhood of the `AutomatonRule`.
+    ///
+    /// # Returns
+    /// The cells whose alive neighbors are counted.
+    #[must_use]
+    pub fn neighborhood(&self) -> &Neighborhood {
+        &self.neighborhood
+    }
+}
+
+/// Error occurring while parsing an [`AutomatonRule`] from B/S notation.
+#[derive(Debug, PartialEq, Eq)]
+pub enum RuleParseError {
+    /// The rule is not of the form `B<counts>/S<counts>`, optionally followed by a neighborhood.
+    InvalidFormat(),
+    /// The neighborhood following the rule is not one of `V`, `H`, `R<radius>` with a radius of
+    /// at most [`MAX_RADIUS`], or `N(<row>,<column>)...`.
+    InvalidNeighborhood(),
+    /// A neighbor count is not a number.
+    InvalidCount(String),
+    /// A neighbor count is greater than the size of the neighborhood.
+    CountOutOfRange(usize),
+    /// A neighbor count is listed more than once in the same part of the rule.
+    RepeatedCount(usize),
+}
+
+impl Error for RuleParseError {}
+impl fmt::Display for RuleParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidFormat() => {
+                write!(
+                    f,
+                    "Rules must be written as B<counts>/S<counts>, such as B3/S23"
+                )
+            }
+            Self::InvalidNeighborhood() => {
+                write!(
+                    f,
+                    "Neighborhoods must be written as V, H, R<1-{}> or N(<row>,<column>)...",
+                    MAX_RADIUS
+                )
+            }
+            Self::InvalidCount(count) => {
+                write!(f, "Invalid neighbor count '{}'", count)
+            }
+            Self::CountOutOfRange(n) => {
+                write!(
+                    f,
+                    "Neighbor count {} is larger than the neighborhood of the rule",
+                    n
+                )
+            }
+            Self::RepeatedCount(n) => {
+                write!(f, "Neighbor count {} is listed more than once", n)
+            }
+        }
+    }
+}
+
+impl FromStr for AutomatonRule {
+    type Err = RuleParseError;
+
+    /// Parses a rule in B/S notation, such as `B23456/S234` or `B2/S013V`. The `B` and `S`
+    /// prefixes and the neighborhood suffix may be lowercase, and either part may list no counts.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (born, survives) = s
+            .trim()
+            .split_once('/')
+            .ok_or(RuleParseError::InvalidFormat())?;
+        let born = born
+            .strip_prefix(['B', 'b'])
+            .ok_or(RuleParseError::InvalidFormat())?;
+        let survives = survives
+            .strip_prefix(['S', 's'])
+            .ok_or(RuleParseError::InvalidFormat())?;
+
+        // the neighborhood begins at the first character which cannot be part of a count
+        let (survives, suffix) = survives.split_at(
+            survives
+                .find(|c: char| !c.is_ascii_digit() && c != ',')
+                .unwrap_or(survives.len()),
+        );
+        let neighborhood = Neighborhood::from_notation_suffix(suffix)?;
+
+        Ok(Self {
+            born: Cow::Owned(parse_counts(born, &neighborhood)?),
+            dies: Cow::Owned(
+                parse_counts(survives, &neighborhood)?
+                    .into_iter()
+                    .map(|survive| !survive)
+                    .collect(),
+            ),
+            neighborhood,
+        })
+    }
+}
+
+impl fmt::Display for AutomatonRule {
+    /// Writes the rule in B/S notation, such as `B23456/S234`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let separator = if self.neighborhood.size().is_some_and(|size| size >= 10) {
+            ","
+        } else {
+            ""
+        };
+        let born = (0..self.born.len())
+            .filter(|n| self.born[*n])
+            .map(|n| n.to_string())
+            .collect::<Vec<String>>()
+            .join(separator);
+        let survives = (0..self.dies.len())
+            .filter(|n| !self.dies[*n])
+            .map(|n| n.to_string())
+            .collect::<Vec<String>>()
+            .join(separator);
+
+        write!(
+            f,
+            "B{}/S{}{}",
+            born,
+            survives,
+            self.neighborhood.notation_suffix()
+        )
+    }
+}
+
+/// Parses the neighbor counts listed in one part of a rule in B/S notation over `neighborhood`.
+fn parse_counts(counts: &str, neighborhood: &Neighborhood) -> Result<Vec<bool>, RuleParseError> {
+    let size = neighborhood
+        .size()
+        .ok_or(RuleParseError::InvalidNeighborhood())?;
+    let mut result = vec![false; size + 1];
+    if counts.is_empty() {
+        return Ok(result);
+    }
+
+    // counts of neighborhoods with 10 or more cells may have several digits
+    let counts: Vec<&str> = if size >= 10 {
+        counts.split(',').collect()
+    } else {
+        counts.matches(|_| true).collect()
+    };
+    for count in counts {
+        let n = match count.parse::<usize>() {
+            Ok(n) => n,
+            Err(_) => {
+                return Err(RuleParseError::InvalidCount(count.to_string()));
+            }
+        };
+        if n >= result.len() {
+            return Err(RuleParseError::CountOutOfRange(n));
+        }
+        if result[n] {
+            return Err(RuleParseError::RepeatedCount(n));
+        }
+        result[n] = true;
+    }
+
+    Ok(result)
+}
+
+/// Computes the generation following a state under an [`AutomatonRule`].
+///
+/// [`ToroidalAutomaton`] steps its state through this trait, so that matrix backends can replace
+/// the cell-by-cell default of [`step_cells`] with kernels suited to their layout. Every
+/// implementation must produce the same states as [`step_cells`].
+pub trait AutomatonStep: ToroidalBinaryMatrix {
+    /// Writes the generation following `self` under `rule` to `next`.
+    ///
+    /// # Arguments
+    /// * `rule` - the rule to apply
+    /// * `next` - the matrix to write the next generation to. Must have the same shape as `self`
+    ///
+    /// # Returns
+    /// `()` once `next` has been written, or [`MatrixOpError::DifferentShapes`] if `next` does
+    /// not have the same shape as `self`.
+    fn step(&self, rule: &AutomatonRule, next: &mut Self) -> Result<(), MatrixOpError> {
+        step_cells(self, rule, next)
+    }
+}
+
+/// Writes the generation following `state` under `rule` to `next`, visiting one cell at a time.
+///
+/// This is the default implementation of [`AutomatonStep::step`], and works with any
+/// [`ToroidalBinaryMatrix`] and [`Neighborhood`].
+///
+/// # Arguments
+/// * `state` - the current generation
+/// * `rule` - the rule to apply
+/// * `next` - the matrix to write the next generation to. Must have the same shape as `state`
+///
+/// # Returns
+/// `()` once `next` has been written, or [`MatrixOpError::DifferentShapes`] if `next` does not
+/// have the same shape as `state`.
+pub fn step_cells<T: ToroidalBinaryMatrix>(
+    state: &T,
+    rule: &AutomatonRule,
+    next: &mut T,
+) -> Result<(), MatrixOpError> {
+    let (rows, cols) = (state.get_n_rows(), state.get_n_cols());
+    if rows != next.get_n_rows() || cols != next.get_n_cols() {
+        return Err(MatrixOpError::DifferentShapes());
+    }
+
+    let offsets = rule.neighborhood.offsets();
+    for row in 0..rows {
+        for col in 0..cols {
+            let idx = (row as isize, col as isize);
+            let n_alive_neighbors = count_neighbors(state, idx, &offsets);
+
+            if state.at(&idx) {
+                next.set(&idx, !rule.dies[n_alive_neighbors as usize]);
+            } else {
+                next.set(&idx, rule.born[n_alive_neighbors as usize]);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Counts the living cells of `state` at `offsets` from `idx`. See
+/// [`ToroidalAutomaton::alive_neighbors`].
+fn count_neighbors<T: ToroidalBinaryMatrix>(
+    state: &T,
+    idx: ToroidalMatrixIndex,
+    offsets: &[ToroidalMatrixIndex],
+) -> u32 {
+    offsets
+        .iter()
+        .map(|(row, col)| state.at(&(idx.0 + row, idx.1 + col)) as u32)
+        .sum()
+}
+
+/// Number of entries in a [`RuleLookupTable`], one for each state of a 4x4 block of cells.
+pub const LOOKUP_TABLE_SIZE: usize = 1 << 16;
+
+#[derive(Clone)]
+/// A precomputed table giving the next state of a 2x2 tile of cells under an [`AutomatonRule`],
+/// indexed by the state of the 4x4 block of cells centered on the tile.
+///
+/// Only rules whose [`Neighborhood`] has a radius of at most 1 can be tabulated, since the next
+/// state of the tile then depends on nothing outside the block. Stepping a matrix tile by tile
+/// takes one table lookup for every four cells instead of a neighbor count for every cell. See
+/// [`ToroidalAutomaton::with_lookup_table`].
+pub struct RuleLookupTable {
+    /// Entry `i` holds the next state of the tile when the block is in state `i`. Bit `4 * c + r`
+    /// of `i` is the cell in row `r` and column `c` of the block, and bit `2 * c + r` of the entry
+    /// is the cell in row `r` and column `c` of the tile.
+    entries: Vec<u8>,
+}
+
+impl RuleLookupTable {
+    /// Builds the lookup table of `rule`.
+    ///
+    /// # Arguments
+    /// * `rule` - the rule to tabulate
+    ///
+    /// # Returns
+    /// The created `RuleLookupTable`, or `None` if the neighborhood of `rule` reaches further than
+    /// the cells adjacent to a cell.
+    #[must_use]
+    pub fn new(rule: &AutomatonRule) -> Option<Self> {
+        if rule.neighborhood.radius() > 1 {
+            return None;
+        }
+        let offsets = rule.neighborhood.offsets();
+        let cell = |block: usize, row: isize, col: isize| (block >> (4 * col + row)) & 1;
+
+        let entries = (0..LOOKUP_TABLE_SIZE)
+            .map(|block| {
+                let mut tile = 0_u8;
+                for (row, col) in [(1, 1), (2, 1), (1, 2), (2, 2)] {
+                    let n_alive_neighbors: usize = offsets
+                        .iter()
+                        .map(|(r, c)| cell(block, row + r, col + c))
+                        .sum();
+
+                    let alive = if cell(block, row, col) == 1 {
+                        !rule.dies[n_alive_neighbors]
+                    } else {
+                        rule.born[n_alive_neighbors]
+                    };
+                    tile |= (alive as u8) << (2 * (col - 1) + (row - 1));
+                }
+                tile
+            })
+            .collect();
+
+        Some(Self { entries })
+    }
+
+    /// Writes the generation following `state` to `next`, one 2x2 tile at a time.
+    ///
+    /// The result is the same as that of [`step_cells`] with the tabulated rule. Tiles which
+    /// extend past the last row or column of a matrix with an odd number of rows or columns wrap
+    /// around, rewriting cells of the first row or column with the same values.
+    ///
+    /// # Arguments
+    /// * `state` - the current generation
+    /// * `next` - the matrix to write the next generation to. Must have the same shape as `state`
+    ///
+    /// # Returns
+    /// `()` once `next` has been written, or [`MatrixOpError::DifferentShapes`] if `next` does not
+    /// have the same shape as `state`.
+    pub fn step<T: ToroidalBinaryMatrix>(
+        &self,
+        state: &T,
+        next: &mut T,
+    ) -> Result<(), MatrixOpError> {
+        let (rows, cols) = (state.get_n_rows(), state.get_n_cols());
+        if rows != next.get_n_rows() || cols != next.get_n_cols() {
+            return Err(MatrixOpError::DifferentShapes());
+        }
+
+        // the 4 cells of each column of the band of rows holding a row of tiles
+        let mut column_bits = vec![0_usize; cols];
+        for tile_row in (0..rows as isize).step_by(2) {
+            for (col, bits) in column_bits.iter_mut().enumerate() {
+                *bits = (0..4).fold(0, |bits, r| {
+                    bits | ((state.at(&(tile_row - 1 + r, col as isize)) as usize) << r)
+                });
+            }
+
+            for tile_col in (0..cols as isize).step_by(2) {
+                let block = (0..4).fold(0, |block, c| {
+                    let col = state.canonize_col_index(tile_col - 1 + c);
+                    block | (column_bits[col] << (4 * c))
+                });
+                let tile = self.entries[block];
+
+                for (row, col) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                    let alive = (tile >> (2 * col + row)) & 1 == 1;
+                    next.set(&(tile_row + row, tile_col + col), alive);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for RuleLookupTable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RuleLookupTable").finish_non_exhaustive()
+    }
 }
 
 #[derive(Debug)]
@@ -40,10 +603,16 @@ pub struct AutomatonRule {
 pub struct ToroidalAutomaton<T: ToroidalBinaryMatrix> {
     /// The automaton rule the `ToroidalAutomaton` will follow.
     rule: AutomatonRule,
+    /// The offsets of the neighborhood of `rule`, counted by
+    /// [`alive_neighbors`](ToroidalAutomaton::alive_neighbors).
+    offsets: Vec<ToroidalMatrixIndex>,
     /// The initial state of the `ToroidalAutomaton`.
     state: T,
     /// A state used for iteration optimization.
     state_copy: T,
+    /// The lookup table of `rule`, if iteration should use it instead of the [`AutomatonStep`]
+    /// implementation of `T`.
+    lookup_table: Option<RuleLookupTable>,
 }
 
 impl<T: ToroidalBinaryMatrix + Clone> ToroidalAutomaton<T> {
@@ -58,38 +627,60 @@ impl<T: ToroidalBinaryMatrix + Clone> ToroidalAutomaton<T> {
     #[must_use]
     pub fn new(state: T, rule: AutomatonRule) -> Self {
         ToroidalAutomaton {
+            offsets: rule.neighborhood.offsets(),
             rule,
             state: state.clone(),
             state_copy: state,
+            lookup_table: None,
         }
     }
+
+    /// Makes the `ToroidalAutomaton` iterate its rule with a [`RuleLookupTable`] rather than the
+    /// [`AutomatonStep`] implementation of `T`. The states reached are the same either way.
+    ///
+    /// Rules which cannot be tabulated, because their neighborhood reaches further than the cells
+    /// adjacent to a cell, keep iterating with the [`AutomatonStep`] implementation of `T`.
+    ///
+    /// # Returns
+    /// The `ToroidalAutomaton`, with the lookup table of its rule built if possible.
+    #[must_use]
+    pub fn with_lookup_table(mut self) -> Self {
+        self.lookup_table = RuleLookupTable::new(&self.rule);
+        self
+    }
     /// Iterates the `ToroidalAutomaton`'s rule `iterations` times.
     ///
+    /// Each generation is computed by the [`AutomatonStep`] implementation of `T`, or by the
+    /// lookup table of the rule if one was built with
+    /// [`with_lookup_table`](ToroidalAutomaton::with_lookup_table).
+    ///
     /// # Arguments
     /// * `iterations` - the number of times to apply the `ToroidalAutomaton`'s rule
-    pub fn iter_rule(&mut self, iterations: u32) {
-        let (rows, cols) = (self.state.get_n_rows(), self.state.get_n_cols());
-
+    pub fn iter_rule(&mut self, iterations: u32)
+    where
+        T: AutomatonStep,
+    {
         for _ in 0..iterations {
-            for row in 0..rows {
-                for col in 0..cols {
-                    let idx = (row as isize, col as isize);
-                    let n_alive_neighbors = self.alive_neighbors(idx);
-
-                    if self.state.at(&idx) {
-                        self.state_copy
-                            .set(&idx, !self.rule.dies[n_alive_neighbors as usize]);
-                    } else {
-                        self.state_copy
-                            .set(&idx, self.rule.born[n_alive_neighbors as usize]);
-                    }
-                }
-            }
+            // both states keep the shape given to `new`
+            let _ = match &self.lookup_table {
+                Some(table) => table.step(&self.state, &mut self.state_copy),
+                None => self.state.step(&self.rule, &mut self.state_copy),
+            };
 
             mem::swap(&mut self.state_copy, &mut self.state);
         }
     }
 
+    /// Kills every cell of the `ToroidalAutomaton`, in a way which is not optimized away. See
+    /// [`ToroidalBinaryMatrix::wipe`].
+    ///
+    /// The states of Talos automata are the keystream, so they are also wiped when the
+    /// `ToroidalAutomaton` is dropped.
+    pub fn wipe(&mut self) {
+        self.state.wipe();
+        self.state_copy.wipe();
+    }
+
     /// Returns a reference to the `ToroidalAutomaton` state.
     ///
     /// # Returns
@@ -112,36 +703,33 @@ impl<T: ToroidalBinaryMatrix + Clone> ToroidalAutomaton<T> {
         self.state.set(idx, value)
     }
 
-    /// Counts the number of alive [Moore
-    /// neighbors](https://en.wikipedia.org/wiki/Moore_neighborhood) at `idx`.
+    /// Counts the number of alive neighbors at `idx` in the [`Neighborhood`] of the
+    /// `ToroidalAutomaton`'s rule.
     ///
-    /// Note that because the Moore neighborhood is a 3x3 area centered at `idx`, and because the
-    /// cell at `idx` is not generally included in the count, the result will range in [0, 8].
+    /// The cell at `idx` is not generally included in the count, so the result will range in
+    /// [0, n], where n is the size of the neighborhood.
     ///
     /// <div class="warning">
-    /// The cell at `idx` will be included in the count if the width or height of the `ToroidalAutomaton` is 1.
-    /// In this case, the result will still range in [0, 8].
+    /// Neighbors are counted once for every offset reaching them, so cells are counted more than
+    /// once, and the cell at `idx` may be included, if the neighborhood wraps around the width or
+    /// height of the `ToroidalAutomaton`. In this case, the result will still range in [0, n].
     /// </div>
     ///
     /// # Arguments
-    /// * `idx` - the cell to count living moore neighbors of.
+    /// * `idx` - the cell to count living neighbors of.
     ///
     /// # Returns
-    /// The number of living Moore neighbors of the cell at idx.
+    /// The number of living neighbors of the cell at idx.
     #[must_use]
     pub fn alive_neighbors(&self, idx: ToroidalMatrixIndex) -> u32 {
-        let (row, col) = (idx.0, idx.1);
-        let mut sum_neighbors = 0;
-
-        for r in (row - 1)..=(row + 1) {
-            for c in (col - 1)..=(col + 1) {
-                sum_neighbors += self.state.at(&(r, c)) as u32
-            }
-        }
-
-        sum_neighbors -= self.state.at(&(row, col)) as u32;
+        count_neighbors(&self.state, idx, &self.offsets)
+    }
+}
 
-        sum_neighbors
+impl<T: ToroidalBinaryMatrix> Drop for ToroidalAutomaton<T> {
+    fn drop(&mut self) {
+        self.state.wipe();
+        self.state_copy.wipe();
     }
 }
 
@@ -183,9 +771,30 @@ impl<T: ToroidalBinaryMatrix + Clone> fmt::Display for ToroidalAutomaton<T> {
 #[cfg(test)]
 mod tests {
     use crate::{
-        automata::{AutomatonRule, ToroidalAutomaton},
+        automata::{
+            self, AutomatonRule, AutomatonStep, Neighborhood, RuleLookupTable, RuleParseError,
+            ToroidalAutomaton,
+        },
+        encrypt::AUTOMATA_RULE,
         matrix::{ToroidalBinaryMatrix, ToroidalBitMatrix, ToroidalBoolMatrix},
     };
+    use std::borrow::Cow;
+
+    /// Checks that the [`AutomatonStep`] implementation of `T` agrees with
+    /// [`automata::step_cells`] on every generation reached from `table`.
+    fn check_step<T: AutomatonStep + Clone>(table: &[Vec<bool>], rule: &AutomatonRule) {
+        let mut state = T::new(table.to_vec()).unwrap();
+        let mut next = state.clone();
+        let mut expected = state.clone();
+
+        for _ in 0..16 {
+            state.step(rule, &mut next).unwrap();
+            automata::step_cells(&state, rule, &mut expected).unwrap();
+            assert_eq!(next.to_table(), expected.to_table());
+
+            std::mem::swap(&mut state, &mut next);
+        }
+    }
 
     #[test]
     fn test_toroidal_matrix_eqivalence() {
@@ -198,10 +807,7 @@ mod tests {
             vec![true, true, false, true, false, true],
         ];
 
-        let rule = AutomatonRule {
-            born: [false, false, true, true, true, true, true, false, false],
-            dies: [true, true, false, false, false, false, true, true, true],
-        };
+        let rule = "B23456/S2345".parse::<AutomatonRule>().unwrap();
 
         let mat_1 = ToroidalBitMatrix::<u8>::new(table_1.clone()).unwrap();
         let mat_2 = ToroidalBitMatrix::<u32>::new(table_1.clone()).unwrap();
@@ -219,4 +825,241 @@ mod tests {
         assert_eq!(automata_1.state.to_table(), automata_3.state.to_table());
         assert_eq!(automata_2.state.to_table(), automata_3.state.to_table());
     }
+
+    #[test]
+    fn test_step_matches_step_cells() {
+        let rules: Vec<AutomatonRule> = [
+            "B23456/S2345",
+            "B2/S013V",
+            "B246/S1H",
+            "B3,4,5,6,7/S2,3,4,5,6R2",
+            "B13/S024N(-1,0)(0,0)(0,2)(1,-1)",
+        ]
+        .iter()
+        .map(|rule| rule.parse().unwrap())
+        .collect();
+        let tables = [
+            vec![
+                vec![false, true, false, false, false, true, true],
+                vec![false, false, false, true, true, true, false],
+                vec![false, true, false, false, false, false, true],
+                vec![true, true, false, true, false, true, true],
+                vec![true, false, false, true, true, false, false],
+            ],
+            vec![vec![
+                true, false, true, true, false, true, false, false, true,
+            ]],
+            vec![vec![true], vec![false], vec![true], vec![true]],
+            vec![vec![true, false], vec![true, true]],
+        ];
+
+        for rule in &rules {
+            for table in &tables {
+                check_step::<ToroidalBoolMatrix>(table, rule);
+                check_step::<ToroidalBitMatrix<u8>>(table, rule);
+                check_step::<ToroidalBitMatrix<u16>>(table, rule);
+                check_step::<ToroidalBitMatrix<u64>>(table, rule);
+            }
+        }
+    }
+
+    #[test]
+    fn test_lookup_table_step() {
+        let rules: Vec<AutomatonRule> = [
+            "B23456/S2345",
+            "B02468/S02468",
+            "B2/S013V",
+            "B246/S1H",
+            "B13/S02N(-1,0)(0,0)(1,-1)",
+        ]
+        .iter()
+        .map(|rule| rule.parse().unwrap())
+        .collect();
+        let tables = [
+            vec![
+                vec![false, true, false, false, false, true, true],
+                vec![false, false, false, true, true, true, false],
+                vec![false, true, false, false, false, false, true],
+                vec![true, true, false, true, false, true, true],
+                vec![true, false, false, true, true, false, false],
+            ],
+            vec![
+                vec![false, true, false, false],
+                vec![true, true, false, true],
+                vec![false, false, true, true],
+                vec![true, false, false, false],
+            ],
+            vec![vec![
+                true, false, true, true, false, true, false, false, true,
+            ]],
+            vec![vec![true], vec![false], vec![true]],
+            vec![vec![true]],
+        ];
+
+        for rule in &rules {
+            for table in &tables {
+                let state = ToroidalBoolMatrix::new(table.clone()).unwrap();
+                let mut expected = ToroidalAutomaton::new(state.clone(), rule.clone());
+                let mut automaton = ToroidalAutomaton::new(state, rule.clone()).with_lookup_table();
+
+                assert!(automaton.lookup_table.is_some());
+                for _ in 0..16 {
+                    expected.iter_rule(1);
+                    automaton.iter_rule(1);
+                    assert_eq!(automaton.state.to_table(), expected.state.to_table());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_lookup_table_radius() {
+        let rule: AutomatonRule = "B3,4,5/S2,3,4R2".parse().unwrap();
+        assert!(RuleLookupTable::new(&rule).is_none());
+
+        let state = ToroidalBoolMatrix::new(vec![vec![true, false, true]; 5]).unwrap();
+        let mut expected = ToroidalAutomaton::new(state.clone(), rule.clone());
+        let mut automaton = ToroidalAutomaton::new(state, rule).with_lookup_table();
+        assert!(automaton.lookup_table.is_none());
+        expected.iter_rule(4);
+        automaton.iter_rule(4);
+        assert_eq!(automaton.state.to_table(), expected.state.to_table());
+    }
+
+    #[test]
+    fn test_rule_notation() {
+        assert_eq!(AUTOMATA_RULE.to_string(), "B23456/S234");
+        assert_eq!(
+            "B23456/S234".parse::<AutomatonRule>().unwrap(),
+            AUTOMATA_RULE
+        );
+        assert_eq!(
+            " b3/s23 ".parse::<AutomatonRule>().unwrap().to_string(),
+            "B3/S23"
+        );
+
+        let empty: AutomatonRule = "B/S".parse().unwrap();
+        assert_eq!(*empty.born, [false; 9]);
+        assert_eq!(*empty.dies, [true; 9]);
+        assert_eq!(empty.to_string(), "B/S");
+
+        let full: AutomatonRule = "B876543210/S012345678".parse().unwrap();
+        assert_eq!(*full.born, [true; 9]);
+        assert_eq!(*full.dies, [false; 9]);
+        assert_eq!(full.to_string(), "B012345678/S012345678");
+
+        let von_neumann: AutomatonRule = "B2/S013v".parse().unwrap();
+        assert_eq!(*von_neumann.neighborhood(), Neighborhood::VonNeumann);
+        assert_eq!(von_neumann.born(), [false, false, true, false, false]);
+        assert_eq!(von_neumann.to_string(), "B2/S013V");
+
+        let extended: AutomatonRule = "B3,10/S2,3,24R2".parse().unwrap();
+        assert_eq!(extended.neighborhood, Neighborhood::ExtendedMoore(2));
+        assert_eq!(extended.born.len(), 25);
+        assert!(extended.born[10] && !extended.born[1]);
+        assert_eq!(extended.to_string(), "B3,10/S2,3,24R2");
+
+        for rule in ["B246/S1H", "B1/S1N(-1,0)(0,1)", "B/SN(2,-3)", "B0/S8R1"] {
+            assert_eq!(rule.parse::<AutomatonRule>().unwrap().to_string(), rule);
+        }
+    }
+
+    #[test]
+    fn test_neighborhoods() {
+        assert_eq!(
+            Neighborhood::Moore.offsets(),
+            Neighborhood::ExtendedMoore(1).offsets()
+        );
+        for (neighborhood, size, radius) in [
+            (Neighborhood::Moore, 8, 1),
+            (Neighborhood::VonNeumann, 4, 1),
+            (Neighborhood::Hexagonal, 6, 1),
+            (Neighborhood::ExtendedMoore(3), 48, 3),
+            (
+                Neighborhood::Offsets(Cow::Borrowed(&[(0, 2), (0, 2), (-1, 0)])),
+                3,
+                2,
+            ),
+        ] {
+            assert_eq!(neighborhood.offsets().len(), size);
+            assert_eq!(neighborhood.size(), Some(size));
+            assert_eq!(neighborhood.radius(), radius);
+        }
+
+        assert!(
+            AutomatonRule::new(Neighborhood::VonNeumann, vec![false; 5], vec![true; 5]).is_some()
+        );
+        assert!(
+            AutomatonRule::new(Neighborhood::VonNeumann, vec![false; 9], vec![true; 9]).is_none()
+        );
+        // neighborhoods without neighbors have no B/S notation to display
+        assert!(AutomatonRule::new(
+            Neighborhood::Offsets(Cow::Borrowed(&[])),
+            vec![false],
+            vec![true]
+        )
+        .is_none());
+        assert!(
+            AutomatonRule::new(Neighborhood::ExtendedMoore(0), vec![false], vec![true]).is_none()
+        );
+        assert_eq!(Neighborhood::ExtendedMoore(usize::MAX / 2).size(), None);
+        assert!(
+            AutomatonRule::new(Neighborhood::ExtendedMoore(usize::MAX), vec![], vec![]).is_none()
+        );
+
+        // the cell at (1, 1) has living von Neumann neighbors above and to its left
+        let table = vec![
+            vec![true, true, false],
+            vec![true, false, false],
+            vec![false, false, true],
+        ];
+        let rule: AutomatonRule = "B2/S2V".parse().unwrap();
+        let automaton = ToroidalAutomaton::new(ToroidalBoolMatrix::new(table).unwrap(), rule);
+        assert_eq!(automaton.alive_neighbors((1, 1)), 2);
+        assert_eq!(automaton.alive_neighbors((0, 0)), 2);
+    }
+
+    #[test]
+    fn test_rule_notation_errors() {
+        for (rule, err) in [
+            ("B3S23", RuleParseError::InvalidFormat()),
+            ("S23/B3", RuleParseError::InvalidFormat()),
+            ("3/23", RuleParseError::InvalidFormat()),
+            ("", RuleParseError::InvalidFormat()),
+            ("B39/S23", RuleParseError::CountOutOfRange(9)),
+            ("B5/S1V", RuleParseError::CountOutOfRange(5)),
+            ("B3x/S23", RuleParseError::InvalidCount("x".to_string())),
+            ("B3,,4/S2R2", RuleParseError::InvalidCount("".to_string())),
+            ("B3/S2x", RuleParseError::InvalidNeighborhood()),
+            ("B3/S2/3", RuleParseError::InvalidNeighborhood()),
+            ("B3/S23R0", RuleParseError::InvalidNeighborhood()),
+            ("B3/S23R33", RuleParseError::InvalidNeighborhood()),
+            ("B3/S23R100000", RuleParseError::InvalidNeighborhood()),
+            (
+                "B3/S23R9999999999999999",
+                RuleParseError::InvalidNeighborhood(),
+            ),
+            ("B3/S23N", RuleParseError::InvalidNeighborhood()),
+            ("B3/S23N(1)", RuleParseError::InvalidNeighborhood()),
+            ("B33/S23", RuleParseError::RepeatedCount(3)),
+        ] {
+            assert_eq!(rule.parse::<AutomatonRule>().unwrap_err(), err);
+        }
+    }
+
+    #[test]
+    fn test_wipe() {
+        let table = vec![vec![true, false, true], vec![true, true, false]];
+        let rule = "B23456/S2345".parse::<AutomatonRule>().unwrap();
+        let mut automaton =
+            ToroidalAutomaton::new(ToroidalBitMatrix::<u8>::new(table).unwrap(), rule);
+        automaton.iter_rule(1);
+        automaton.wipe();
+
+        assert_eq!(automaton.state.popcount(), 0);
+        assert_eq!(automaton.state_copy.popcount(), 0);
+        // a wiped automaton keeps its shape
+        assert_eq!(automaton.state.get_n_rows(), 2);
+        assert_eq!(automaton.state.get_n_cols(), 3);
+    }
 }
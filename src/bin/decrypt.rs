@@ -1,78 +1,9 @@
 // 2025 Steven Chiacchira
 use clap::Parser;
-use std::error::Error;
-use std::fmt;
-use std::fs;
-use talos::{encrypt, key, parse};
+use talos::cli::decrypt::{self, DecryptArgs};
+use talos::cli::CliError;
 
-#[derive(Parser, Debug)]
-#[command(version, about, long_about=None)]
-/// CLI tool for decryption using the Talos encryption protocol.
-struct DecryptArgs {
-    /// The file to be decrypted
-    input: String,
-    /// The file path to save the decrypted plaintext to
-    out: String,
-    #[arg(short, long)]
-    /// The encryption key to be used. If a numerical input is given, it will be used as the
-    /// encryption key. If a string is given, it will be used to deterministically generate a key
-    /// using SHA2567.
-    key: key::KeyArgument,
-}
-
-#[derive(Debug)]
-/// Possible error states for CLI decryption.
-enum DecryptError {
-    /// An error occurred reading the specified ciphertext file.
-    FileReadError(),
-    /// An error occurred writing to the specified output file.
-    FileWriteError(),
-}
-
-impl Error for DecryptError {}
-impl fmt::Display for DecryptError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            Self::FileReadError() => {
-                write!(f, "Error reading file")
-            }
-            Self::FileWriteError() => {
-                write!(f, "Error writing output")
-            }
-        }
-    }
-}
-
-fn main() -> Result<(), DecryptError> {
-    let args = DecryptArgs::parse();
-    let seed = args.key.get();
-
-    let mut char_map = parse::gen_char_map(seed);
-    char_map.insert('#', true);
-    char_map.insert('.', false);
-
-    let (mut s_automaton, mut t_automaton) = encrypt::get_shift_transpose_automata(seed);
-
-    let input_buffer = match fs::read(args.input) {
-        Ok(buffer) => buffer,
-        Err(_) => {
-            return Err(DecryptError::FileReadError());
-        }
-    };
-
-    eprintln!("Using key {}", seed);
-    let now = std::time::Instant::now();
-    let output_bytes =
-        encrypt::decrypt_message_256(input_buffer, &mut s_automaton, &mut t_automaton);
-
-    eprintln!(
-        "Finished decrypting in {} miliseconds",
-        now.elapsed().as_millis()
-    );
-
-    let result = fs::write(args.out, output_bytes);
-    match result {
-        Ok(_) => Ok(()),
-        Err(_) => Err(DecryptError::FileWriteError()),
-    }
+/// Equivalent to `talos decrypt`.
+fn main() -> Result<(), CliError> {
+    decrypt::run(DecryptArgs::parse())
 }
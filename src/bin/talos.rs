@@ -0,0 +1,46 @@
+// 2025 Steven Chiacchira
+use clap::{Parser, Subcommand};
+use talos::cli::analyze::{self, AnalyzeCommand};
+use talos::cli::bench::{self, BenchArgs};
+use talos::cli::decrypt::{self, DecryptArgs};
+use talos::cli::encrypt::{self, EncryptArgs};
+use talos::cli::inspect::{self, InspectArgs};
+use talos::cli::keygen::{self, KeygenArgs};
+use talos::cli::CliError;
+
+#[derive(Parser, Debug)]
+#[command(version, about, long_about=None)]
+/// CLI tool for encryption with the Talos encryption protocol and analysis of its automata.
+struct TalosArgs {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+/// The subcommands of the `talos` tool.
+enum Command {
+    /// Encrypt a file into a Talos container.
+    Encrypt(EncryptArgs),
+    /// Decrypt a Talos container.
+    Decrypt(DecryptArgs),
+    /// Generate a random encryption key.
+    Keygen(KeygenArgs),
+    /// Describe the header of a Talos container.
+    Inspect(InspectArgs),
+    /// Analyze the Talos automata.
+    #[command(subcommand)]
+    Analyze(AnalyzeCommand),
+    /// Measure the throughput of encryption and decryption.
+    Bench(BenchArgs),
+}
+
+fn main() -> Result<(), CliError> {
+    match TalosArgs::parse().command {
+        Command::Encrypt(args) => encrypt::run(args),
+        Command::Decrypt(args) => decrypt::run(args),
+        Command::Keygen(args) => keygen::run(args),
+        Command::Inspect(args) => inspect::run(args),
+        Command::Analyze(command) => analyze::run(command),
+        Command::Bench(args) => bench::run(args),
+    }
+}
@@ -0,0 +1,361 @@
+// 2025 Steven Chiacchira
+use super::{BlockDim, CliError};
+use crate::automata::{AutomatonRule, AutomatonStep, ToroidalAutomaton};
+use crate::container::ContainerError;
+use crate::encrypt::{self, TalosMatrix, AUTOMATA_RULE};
+use crate::key::{self, KeyWidth, TalosKey};
+use crate::matrix::{self, ToroidalBinaryMatrix};
+use crate::parse;
+use crate::seeding::SeedingSchedule;
+use clap::{Parser, Subcommand};
+use std::collections::{hash_map::HashMap, HashSet};
+use std::fs::read_to_string;
+use std::num::NonZeroUsize;
+
+/// Statistical analyses of Talos automata, printed to stdout as tab separated values.
+#[derive(Subcommand, Debug)]
+pub enum AnalyzeCommand {
+    /// Count the living cells of an automaton in each generation.
+    Count(CountArgs),
+    /// Measure how long the shift automaton runs before repeating a state.
+    Shift(ShiftArgs),
+    /// Sample the swap indices read from the transpose automaton.
+    Transpose(TransposeArgs),
+}
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+/// CLI for testing Talos CA generation.
+pub struct CountArgs {
+    /// Flag for testing only contiguous seeds.
+    #[arg(short, long, action)]
+    use_contiguous_seeds: bool,
+
+    /// The number of seeds to test.
+    #[arg(short, long, default_value_t = 1)]
+    seeds: u32,
+
+    /// The number of generations to run the [`ToroidalAutomaton`] for.
+    #[arg(short, long, default_value_t = 32_000)]
+    generations: u32,
+
+    /// File to use for initializing the [`ToroidalAutomaton`] state.
+    #[arg(short, long)]
+    init_file: String,
+
+    /// The rule followed by the automaton, in B/S notation. A suffix of `V`, `H` or `R<radius>`
+    /// selects the von Neumann, hexagonal or extended Moore neighborhood.
+    #[arg(long, default_value_t = AUTOMATA_RULE)]
+    rule: AutomatonRule,
+}
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+/// CLI for testing Talos CA generation.
+pub struct ShiftArgs {
+    /// Flag for testing only contiguous seeds.
+    #[arg(short, long, action)]
+    use_contiguous_seeds: bool,
+
+    /// The number of seeds to test.
+    #[arg(short, long, default_value_t = 1)]
+    seeds: u32,
+
+    /// The number of generations to run the [`ToroidalAutomaton`] for.
+    #[arg(short, long, default_value_t = 32_000)]
+    generations: u32,
+
+    /// File to use for initializing the [`ToroidalAutomaton`] state.
+    #[arg(short, long)]
+    init_file: String,
+
+    #[arg(long, action)]
+    no_temporal_seed: bool,
+
+    /// Smear temporal seeding across this many injections instead of following RFC-1. See
+    /// [`SeedingSchedule::smeared`].
+    #[arg(long)]
+    smear_steps: Option<NonZeroUsize>,
+
+    /// The number of generations between smeared injections.
+    #[arg(long, default_value_t = 8, requires = "smear_steps")]
+    smear_generations: u32,
+
+    /// The rule followed by the automaton, in B/S notation. A suffix of `V`, `H` or `R<radius>`
+    /// selects the von Neumann, hexagonal or extended Moore neighborhood.
+    #[arg(long, default_value_t = AUTOMATA_RULE)]
+    rule: AutomatonRule,
+
+    /// The number of rows and columns in an encryption block. The initialization matrix must have
+    /// this shape.
+    #[arg(long, value_enum, default_value_t = BlockDim::Dim16)]
+    block_dim: BlockDim,
+}
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+/// CLI for testing Talos CA generation.
+pub struct TransposeArgs {
+    /// Flag for testing only contiguous seeds. If false random seeds will be used.
+    #[arg(short, long, action)]
+    use_contiguous_seeds: bool,
+
+    /// The number of seeds to test.
+    #[arg(long, default_value_t = 1)]
+    seeds: u32,
+
+    /// The number of generations to run between sampling.
+    #[arg(long, default_value_t = 11)]
+    inter_generations: u32,
+
+    /// How many matrices to sample per seed
+    #[arg(long, default_value_t = 1)]
+    seed_samples: u32,
+
+    /// File to use for initializing the [Automaton](ToroidalAutomaton) state.
+    #[arg(long)]
+    init_file: String,
+
+    /// The rule followed by the automaton, in B/S notation. A suffix of `V`, `H` or `R<radius>`
+    /// selects the von Neumann, hexagonal or extended Moore neighborhood.
+    #[arg(long, default_value_t = AUTOMATA_RULE)]
+    rule: AutomatonRule,
+
+    /// The number of rows and columns in an encryption block. The initialization matrix must have
+    /// this shape.
+    #[arg(long, value_enum, default_value_t = BlockDim::Dim16)]
+    block_dim: BlockDim,
+}
+
+/// Runs the analysis selected by `command`.
+///
+/// # Arguments
+/// * `command` - the parsed command line arguments
+///
+/// # Returns
+/// `()` once the analysis has been printed, or the [`CliError`] which occurred.
+pub fn run(command: AnalyzeCommand) -> Result<(), CliError> {
+    match command {
+        AnalyzeCommand::Count(args) => run_count(args),
+        AnalyzeCommand::Shift(args) => run_shift(args),
+        AnalyzeCommand::Transpose(args) => run_transpose(args),
+    }
+}
+
+/// Returns an iterator over `n_seeds` seeds, which are `0..n_seeds` if `contiguous` and random
+/// 32 bit keys otherwise.
+fn seeds(n_seeds: u32, contiguous: bool) -> impl Iterator<Item = u32> {
+    (0..n_seeds).map(move |i| {
+        if contiguous {
+            return i;
+        }
+        match key::generate_key(KeyWidth::Bits32) {
+            TalosKey::Bits32(seed) => seed,
+            _ => unreachable!(),
+        }
+    })
+}
+
+/// Creates an automaton following `rule` whose state is read from `matrix_config`, with the key
+/// digits replaced by the bits of `seed`. Matrices referencing more than 32 key digits repeat the
+/// bits of `seed`; see [`parse::gen_key_char_map`].
+fn seeded_automaton<T: AutomatonStep + Clone>(
+    matrix_config: &str,
+    seed: u32,
+    rule: &AutomatonRule,
+) -> Result<ToroidalAutomaton<T>, CliError> {
+    let mut char_map: HashMap<char, bool> = parse::gen_key_char_map(
+        &TalosKey::Bits32(seed),
+        parse::count_key_digits(matrix_config),
+    );
+    char_map.insert('#', true);
+    char_map.insert('.', false);
+
+    let table =
+        parse::parse_bool_table(matrix_config, &char_map).map_err(CliError::InvalidTable)?;
+    let state = T::new(table).map_err(CliError::InvalidMatrix)?;
+
+    Ok(ToroidalAutomaton::new(state, rule.clone()))
+}
+
+/// Prints the number of living cells of each generation of an automaton.
+///
+/// # Arguments
+/// * `args` - the parsed command line arguments
+///
+/// # Returns
+/// `()` once the analysis has been printed, or the [`CliError`] which occurred.
+pub fn run_count(args: CountArgs) -> Result<(), CliError> {
+    let matrix_config = read_to_string(&args.init_file).map_err(|_| CliError::FileReadError())?;
+
+    println!("# Using contiguous seeds: {}", args.use_contiguous_seeds);
+    println!("# Number of seeds: {}", args.seeds);
+    println!("# Number of generations: {}", args.generations);
+    println!("# Initial File: {}", args.init_file);
+    println!("# Rule: {}", args.rule);
+    println!("test\ttseed\tgeneration\tn_alive");
+
+    for (test, seed) in seeds(args.seeds, args.use_contiguous_seeds).enumerate() {
+        let mut automaton =
+            seeded_automaton::<matrix::ToroidalBoolMatrix>(&matrix_config, seed, &args.rule)?;
+
+        for generation in 0..args.generations {
+            automaton.iter_rule(1);
+            let n_alive = automaton.get_state().popcount();
+            println!("{}\t{}\t{}\t{}", test, seed, generation, n_alive,);
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints the number of generations the temporally seeded shift automaton runs for before
+/// repeating a state.
+///
+/// # Arguments
+/// * `args` - the parsed command line arguments
+///
+/// # Returns
+/// `()` once the analysis has been printed, or the [`CliError`] which occurred.
+pub fn run_shift(args: ShiftArgs) -> Result<(), CliError> {
+    let geometry = args.block_dim.geometry();
+    let mut global_used_states: HashSet<Vec<u8>, _> = HashSet::new();
+    let mut global_duplicates: Vec<Vec<u8>> = Vec::new();
+
+    let matrix_config = read_to_string(&args.init_file).map_err(|_| CliError::FileReadError())?;
+    let temporal_seed_map = parse::get_temporal_seed_map(&matrix_config);
+    let schedule = match args.smear_steps {
+        Some(n_steps) => SeedingSchedule::smeared(n_steps, args.smear_generations),
+        None => SeedingSchedule::rfc1(),
+    };
+    let n_seeding_generations = schedule.n_generations().ok_or(CliError::InvalidOptions(
+        ContainerError::UnsupportedParameters(),
+    ))?;
+
+    println!("# Using contiguous seeds: {}", args.use_contiguous_seeds);
+    println!("# Number of seeds: {}", args.seeds);
+    println!("# Number of generations: {}", args.generations);
+    println!("# Initial File: {}", args.init_file);
+    println!("# Seeding generations: {}", n_seeding_generations);
+    println!("# Rule: {}", args.rule);
+    println!("# Block dimension: {}", geometry.n_rows());
+    println!("test\tn_generations\tseed\tavg_alive\tcontains_global_duplicate");
+
+    for (test, seed) in seeds(args.seeds, args.use_contiguous_seeds).enumerate() {
+        let mut local_used_states: HashSet<Vec<u8>, _> = HashSet::new();
+        let mut n_local_alive_total = 0;
+
+        let mut automaton = seeded_automaton::<TalosMatrix>(&matrix_config, seed, &args.rule)?;
+        let state = automaton.get_state();
+        if state.get_n_rows() != geometry.n_rows() || state.get_n_cols() != geometry.n_cols() {
+            return Err(CliError::InvalidOptions(
+                ContainerError::UnsupportedParameters(),
+            ));
+        }
+        if !args.no_temporal_seed {
+            encrypt::temporal_seed_automaton_with_key(
+                &mut automaton,
+                &TalosKey::Bits32(seed),
+                &temporal_seed_map,
+                &schedule,
+            );
+        }
+
+        let mut final_generation = args.generations;
+        let mut contains_global_duplicate = false;
+
+        for generation in 0..args.generations {
+            let n_alive = automaton.get_state().popcount();
+            n_local_alive_total += n_alive;
+
+            let curr_state = automaton.get_state().get_storage();
+
+            if global_used_states.contains(curr_state) {
+                global_duplicates.push(curr_state.to_vec());
+                contains_global_duplicate = true;
+                final_generation = generation;
+                break;
+            } else if local_used_states.contains(curr_state) {
+                final_generation = generation;
+                break;
+            }
+            local_used_states.insert(curr_state.clone());
+            global_used_states.insert(curr_state.to_vec());
+            automaton.iter_rule(1);
+        }
+
+        let n_cells = geometry.n_rows() * geometry.n_cols();
+        let avg_alive: f64 =
+            (n_local_alive_total as f64) / (n_cells as f64 * (final_generation as f64 + 1.0));
+
+        println!(
+            "{}\t{}\t{}\t{}\t{}",
+            test, final_generation, seed, avg_alive, contains_global_duplicate
+        );
+    }
+
+    Ok(())
+}
+
+/// Prints the row and column swap indices read from the transpose automaton.
+///
+/// # Arguments
+/// * `args` - the parsed command line arguments
+///
+/// # Returns
+/// `()` once the analysis has been printed, or the [`CliError`] which occurred.
+pub fn run_transpose(args: TransposeArgs) -> Result<(), CliError> {
+    let geometry = args.block_dim.geometry();
+    println!("# Using contiguous seeds: {}", args.use_contiguous_seeds);
+    println!("# Number of samples: {}", args.seed_samples);
+    println!(
+        "# Number of generations between samples: {}",
+        args.inter_generations
+    );
+    println!("# Initial File: {}", args.init_file);
+    println!("# Rule: {}", args.rule);
+    println!("# Block dimension: {}", geometry.n_rows());
+    println!("test\tseed\tgeneration\tcol_row\tgenerated_idx");
+
+    let seed_matrix = read_to_string(&args.init_file).map_err(|_| CliError::FileReadError())?;
+    for (test, seed) in seeds(args.seeds, args.use_contiguous_seeds).enumerate() {
+        let mut automaton =
+            seeded_automaton::<matrix::ToroidalBoolMatrix>(&seed_matrix, seed, &args.rule)?;
+        let state = automaton.get_state();
+        if state.get_n_rows() != geometry.n_rows() || state.get_n_cols() != geometry.n_cols() {
+            return Err(CliError::InvalidOptions(
+                ContainerError::UnsupportedParameters(),
+            ));
+        }
+
+        for iteration in 0..(args.seed_samples) {
+            automaton.iter_rule(args.inter_generations);
+            for row in 0..geometry.n_rows() {
+                let (_, row_swap_idx) =
+                    encrypt::scramble_row_index(automaton.get_state(), &geometry, row);
+                println!(
+                    "{}\t{}\t{}\tR{}\t{}",
+                    test,
+                    seed,
+                    iteration * args.inter_generations,
+                    row,
+                    row_swap_idx
+                )
+            }
+            for col in 0..geometry.n_cols() {
+                let (_, col_swap_idx) =
+                    encrypt::scramble_col_index(automaton.get_state(), &geometry, col);
+                println!(
+                    "{}\t{}\t{}\tC{}\t{}",
+                    test,
+                    seed,
+                    iteration * args.inter_generations,
+                    col,
+                    col_swap_idx
+                )
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,93 @@
+// 2025 Steven Chiacchira
+use super::{BlockDim, CliError, KeyBits, Variant};
+use crate::container::ContainerError;
+use crate::encrypt::{self, CipherParams};
+use crate::key;
+use clap::Parser;
+use std::time::{Duration, Instant};
+
+#[derive(Parser, Debug)]
+#[command(version, about, long_about=None)]
+/// CLI tool measuring the throughput of the Talos encryption protocol.
+pub struct BenchArgs {
+    #[arg(long, default_value_t = 1 << 20)]
+    /// The number of random plaintext bytes to encrypt and decrypt.
+    bytes: usize,
+    #[arg(long, value_enum, default_value_t = KeyBits::Bits32)]
+    /// The width of the random encryption key in bits.
+    key_bits: KeyBits,
+    #[arg(long, value_enum, default_value_t = BlockDim::Dim16)]
+    /// The number of rows and columns in an encryption block.
+    block_dim: BlockDim,
+    #[arg(long, value_enum, default_value_t = Variant::Rfc1)]
+    /// The variant of the Talos protocol to measure.
+    variant: Variant,
+}
+
+/// Measures automaton initialization, encryption and decryption as described by `args`, and
+/// prints the results to stdout as tab separated values.
+///
+/// # Arguments
+/// * `args` - the parsed command line arguments
+///
+/// # Returns
+/// `()` once the results have been printed, or the [`CliError`] which occurred.
+pub fn run(args: BenchArgs) -> Result<(), CliError> {
+    let key = key::generate_key(args.key_bits.key_width());
+    let geometry = args.block_dim.geometry();
+    let variant = args.variant.protocol_variant();
+    let mut message = vec![0_u8; args.bytes];
+    rand::fill(&mut message[..]);
+
+    let get_automata = || {
+        CipherParams::for_geometry(&geometry, key.width(), variant)
+            .and_then(|params| {
+                encrypt::get_shift_transpose_automata_with_params(&params, &geometry, &key, None)
+            })
+            .map_err(|_| CliError::InvalidOptions(ContainerError::UnsupportedParameters()))
+    };
+
+    let now = Instant::now();
+    let (mut s_automaton, mut t_automaton) = get_automata()?;
+    let setup_time = now.elapsed();
+
+    let now = Instant::now();
+    let ciphertext = encrypt::encrypt_message(
+        message.clone(),
+        &geometry,
+        variant,
+        &mut s_automaton,
+        &mut t_automaton,
+    );
+    let encrypt_time = now.elapsed();
+
+    let (mut s_automaton, mut t_automaton) = get_automata()?;
+    let now = Instant::now();
+    let plaintext = encrypt::decrypt_message(
+        ciphertext,
+        &geometry,
+        variant,
+        &mut s_automaton,
+        &mut t_automaton,
+    );
+    let decrypt_time = now.elapsed();
+    assert_eq!(plaintext[..message.len()], message[..]);
+
+    println!("# Plaintext bytes: {}", args.bytes);
+    println!("# Key bits: {}", key.n_bits());
+    println!("# Block shape: {}x{}", geometry.n_rows(), geometry.n_cols());
+    println!("# Protocol variant: {:?}", variant);
+    println!("operation\tmilliseconds\tmib_per_second");
+    println!("setup\t{:.3}\t", millis(setup_time));
+    for (operation, time) in [("encrypt", encrypt_time), ("decrypt", decrypt_time)] {
+        let mib_per_second = args.bytes as f64 / (1024.0 * 1024.0) / time.as_secs_f64();
+        println!("{}\t{:.3}\t{:.3}", operation, millis(time), mib_per_second);
+    }
+
+    Ok(())
+}
+
+/// Returns `duration` in fractional milliseconds.
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
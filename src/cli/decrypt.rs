@@ -0,0 +1,156 @@
+// 2025 Steven Chiacchira
+use super::{CliError, KeyArgs, Output, StagedFile, Variant, STDIO_PATH};
+use crate::container::{self, ContainerError, DecryptOptions, Header, FLAG_AUTHENTICATED};
+use crate::encrypt::{self, CipherParams, GEOMETRY_256};
+use crate::key::{KeyWidth, TalosKey};
+use crate::stream::TalosDecryptor;
+use clap::Parser;
+use std::fs;
+use std::io::{self, BufReader};
+
+#[derive(Parser, Debug)]
+#[command(version, about, long_about=None)]
+#[command(mut_group("key_source", |group| group.required(true)))]
+/// CLI tool for decryption using the Talos encryption protocol.
+pub struct DecryptArgs {
+    /// The file to be decrypted, or `-` to read the ciphertext from stdin
+    input: String,
+    /// The file path to save the decrypted plaintext to, or `-` to write the plaintext to stdout
+    out: String,
+    #[command(flatten)]
+    /// The encryption key. Passphrases are turned into keys using the key derivation recorded in
+    /// the container header. The width of the key is also read from the header.
+    key: KeyArgs,
+    #[arg(long, action)]
+    /// Print the secret key to stderr. By default only its fingerprint is printed.
+    emit_key: bool,
+    #[arg(long, action)]
+    /// Read the input as bare ciphertext blocks without a container header, as written by
+    /// `encrypt --raw`.
+    raw: bool,
+    #[arg(long, value_enum, requires = "raw", default_value_t = Variant::Legacy)]
+    /// The variant of the Talos protocol a headerless ciphertext was encrypted with. The variant
+    /// of a container is read from its header.
+    variant: Variant,
+    #[arg(long, action)]
+    /// Accept containers without an authentication tag, as written by `encrypt --no-auth`.
+    allow_unauthenticated: bool,
+}
+
+/// Decrypts a file as described by `args`.
+///
+/// # Arguments
+/// * `args` - the parsed command line arguments
+///
+/// # Returns
+/// `()` once the plaintext has been written, or the [`CliError`] which occurred.
+pub fn run(args: DecryptArgs) -> Result<(), CliError> {
+    let (mut input_file, _) = super::open_input(&args.input)?;
+
+    // headerless ciphertexts always use 32 bit keys
+    let header = if args.raw {
+        None
+    } else {
+        match Header::read(&mut input_file) {
+            Ok(header) => Some(header),
+            Err(err) => {
+                return Err(CliError::InvalidContainer(err));
+            }
+        }
+    };
+    let width = header
+        .as_ref()
+        .map_or(KeyWidth::Bits32, |header| header.key_width);
+    // containers without key derivation parameters use the legacy derivation
+    let kdf = header.as_ref().and_then(|header| header.kdf.as_ref());
+    // a key source is required by clap
+    let key = super::get_key(args.key.key_argument().unwrap(), width, kdf)?;
+    if let Some(header) = &header {
+        match header.check_key(&key) {
+            Ok(()) => {}
+            Err(ContainerError::WrongKey()) => {
+                return Err(CliError::WrongKey());
+            }
+            Err(err) => {
+                return Err(CliError::InvalidContainer(err));
+            }
+        }
+    }
+
+    // plaintext is written before the authentication tag is verified, so a file output is only
+    // moved into place once decryption succeeds. On stdout, the tag is verified in a first pass
+    // over the input, or the plaintext is held in a private temporary file if the input is a pipe
+    let authenticated = header
+        .as_ref()
+        .is_some_and(|header| header.flags & FLAG_AUTHENTICATED != 0);
+    let output_file = if args.out != STDIO_PATH || !authenticated {
+        super::create_output(&args.out)?
+    } else if args.input != STDIO_PATH {
+        verify_file(&args.input, &key)?;
+        super::create_output(&args.out)?
+    } else {
+        Output::Staged(StagedFile::create_for_stdout()?)
+    };
+
+    super::report_key(&key, args.emit_key);
+    let now = std::time::Instant::now();
+    let output_file = match header {
+        None => {
+            let (s_automaton, t_automaton) = encrypt::get_shift_transpose_automata_with_params(
+                &CipherParams::rfc1(),
+                &GEOMETRY_256,
+                &key,
+                None,
+            )
+            .unwrap();
+            let mut decryptor = TalosDecryptor::new(
+                input_file,
+                &GEOMETRY_256,
+                args.variant.protocol_variant(),
+                s_automaton,
+                t_automaton,
+            );
+            let mut output_file = output_file;
+            io::copy(&mut decryptor, &mut output_file).map_err(CliError::IoError)?;
+            output_file
+        }
+        Some(header) => {
+            let options = DecryptOptions {
+                require_authentication: !args.allow_unauthenticated,
+            };
+            container::decrypt_stream(&header, input_file, output_file, &key, &options)
+                .map_err(decrypt_error)?
+        }
+    };
+    output_file.commit()?;
+
+    eprintln!(
+        "Finished decrypting in {} miliseconds",
+        now.elapsed().as_millis()
+    );
+
+    Ok(())
+}
+
+/// Verifies the authentication tag of the container at `path` without decrypting it. See
+/// [`container::verify_stream`].
+fn verify_file(path: &str, key: &TalosKey) -> Result<(), CliError> {
+    let mut input_file = match fs::File::open(path) {
+        Ok(file) => BufReader::new(file),
+        Err(_) => {
+            return Err(CliError::FileReadError());
+        }
+    };
+    let header = Header::read(&mut input_file).map_err(CliError::InvalidContainer)?;
+
+    container::verify_stream(&header, input_file, key).map_err(decrypt_error)
+}
+
+/// Returns the [`CliError`] for an error which occurred while decrypting a container.
+fn decrypt_error(err: ContainerError) -> CliError {
+    match err {
+        ContainerError::AuthenticationFailed() => CliError::AuthenticationFailed(),
+        ContainerError::Io(err) => CliError::IoError(err),
+        err => CliError::InvalidContainer(err),
+    }
+}
@@ -0,0 +1,210 @@
+// 2025 Steven Chiacchira
+use super::{BlockDim, CliError, Input, KeyArgs, KeyBits, Variant};
+use crate::container::{self, ContainerError, EncryptOptions};
+use crate::encrypt::{self, CipherParams, PaddingStrategy, ProtocolVariant, GEOMETRY_256};
+use crate::kdf::{self, KdfParams};
+use crate::key;
+use crate::stream::TalosEncryptor;
+use clap::{Parser, ValueEnum};
+use std::fs;
+use std::io;
+
+#[derive(Parser, Debug)]
+#[command(version, about, long_about=None)]
+/// CLI tool for encryption using the Talos encryption protocol.
+pub struct EncryptArgs {
+    /// The file to be encrypted, or `-` to read the plaintext from stdin
+    input: String,
+    /// The file path to save ciphertext to, or `-` to write the ciphertext to stdout
+    out: String,
+    #[command(flatten)]
+    /// The encryption key. Passphrases are turned into keys with PBKDF2-HMAC-SHA256 and a random
+    /// salt. If no key is given, a random key is generated, which must be recorded with
+    /// `--key-out` or `--emit-key`.
+    key: KeyArgs,
+    #[arg(long, value_enum, default_value_t = KeyBits::Bits32)]
+    /// The width of the encryption key in bits. Numeric keys can only be used as 32 bit keys.
+    key_bits: KeyBits,
+    #[arg(long, value_enum, default_value_t = BlockDim::Dim16)]
+    /// The number of rows and columns in an encryption block.
+    block_dim: BlockDim,
+    #[arg(long, value_enum)]
+    /// The variant of the Talos protocol to encrypt with. Defaults to `rfc1`, or to `legacy` with
+    /// `--raw`.
+    variant: Option<Variant>,
+    #[arg(long, value_name = "PATH")]
+    /// Write the key to a new file only readable by its owner, from which it can be read with
+    /// `--key-file`.
+    key_out: Option<String>,
+    #[arg(long, action)]
+    /// Print the secret key to stderr. By default only its fingerprint is printed.
+    emit_key: bool,
+    #[arg(
+        long,
+        action,
+        conflicts_with_all = ["padding", "corpus", "no_auth", "key_bits", "block_dim", "kdf_iterations"]
+    )]
+    /// Write the bare ciphertext blocks without a container header. The plaintext length is not
+    /// recorded, so the decrypted output will be padded with zeros. Passphrases are derived with
+    /// the legacy derivation, since there is no header to record a salt in.
+    raw: bool,
+    #[arg(long, value_enum)]
+    /// How to fill the final block of the plaintext. Defaults to `pkcs7`, or to `zeros` for
+    /// blocks too long for PKCS#7 padding.
+    padding: Option<Padding>,
+    #[arg(long, required_if_eq("padding", "corpus"))]
+    /// A text file whose words are sampled to fill the final block when using `--padding corpus`.
+    corpus: Option<String>,
+    #[arg(long, action)]
+    /// Do not append an authentication tag to the container. Tampering and decryption with the
+    /// wrong key will not be detected.
+    no_auth: bool,
+    #[arg(
+        long,
+        default_value_t = kdf::DEFAULT_ITERATIONS,
+        value_parser = clap::value_parser!(u32).range(1..),
+        conflicts_with = "legacy_kdf"
+    )]
+    /// The number of PBKDF2 iterations used to derive a key from a passphrase. The iteration
+    /// count and salt are recorded in the container header.
+    kdf_iterations: u32,
+    #[arg(long, action)]
+    /// Derive the key from a passphrase with the single unsalted SHA-256 hash used by earlier
+    /// versions. Only intended for compatibility with existing tools.
+    legacy_kdf: bool,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+/// Padding strategies selectable from the command line. See [`PaddingStrategy`].
+enum Padding {
+    /// PKCS#7 padding
+    Pkcs7,
+    /// Zero bytes
+    Zeros,
+    /// Random bytes
+    Random,
+    /// Words sampled from the file given by `--corpus`
+    Corpus,
+}
+
+/// Encrypts a file as described by `args`.
+///
+/// # Arguments
+/// * `args` - the parsed command line arguments
+///
+/// # Returns
+/// `()` once the ciphertext has been written, or the [`CliError`] which occurred.
+pub fn run(args: EncryptArgs) -> Result<(), CliError> {
+    let key_argument = args.key.key_argument().unwrap_or(key::KeyArgument::None);
+    // a generated key which is neither saved nor printed could never be used for decryption
+    if matches!(key_argument, key::KeyArgument::None) && args.key_out.is_none() && !args.emit_key {
+        return Err(CliError::UnrecordedKey());
+    }
+    let kdf = (key_argument.is_passphrase() && !args.raw && !args.legacy_kdf)
+        .then(|| KdfParams::new(args.kdf_iterations, &mut key::os_rng()));
+    let key = super::get_key(key_argument, args.key_bits.key_width(), kdf.as_ref())?;
+
+    // the plaintext length of pipes is unknown, so it is recorded after the ciphertext
+    let (mut input_file, input_len) = super::open_input(&args.input)?;
+
+    let geometry = args.block_dim.geometry();
+    let default_padding = if geometry.block_bytes() > encrypt::MAX_PKCS7_BLOCK_BYTES {
+        Padding::Zeros
+    } else {
+        Padding::Pkcs7
+    };
+    let padding = match args.padding.unwrap_or(default_padding) {
+        Padding::Pkcs7 => PaddingStrategy::Pkcs7,
+        Padding::Zeros => PaddingStrategy::Zeros,
+        Padding::Random => PaddingStrategy::Random,
+        Padding::Corpus => match fs::read_to_string(args.corpus.unwrap()) {
+            Ok(text) => PaddingStrategy::from_corpus(&text),
+            Err(_) => {
+                return Err(CliError::FileReadError());
+            }
+        },
+    };
+
+    // headerless ciphertexts were written with the legacy protocol before variants existed
+    let variant = match (args.variant, args.raw) {
+        (Some(variant), _) => variant.protocol_variant(),
+        (None, true) => ProtocolVariant::LegacyRfc1,
+        (None, false) => ProtocolVariant::Rfc1,
+    };
+
+    let output_file = super::create_output(&args.out)?;
+    // the key file is only written once the output can be created, and removed if encryption
+    // fails, so that no key is left behind without a ciphertext
+    if let Some(key_out) = &args.key_out {
+        super::write_key_file(key_out, &key)?;
+    }
+
+    super::report_key(&key, args.emit_key);
+    let now = std::time::Instant::now();
+    // the output is staged next to its path, so the input is intact until encryption succeeds
+    let result = if args.raw {
+        let (s_automaton, t_automaton) = encrypt::get_shift_transpose_automata_with_params(
+            &CipherParams::rfc1(),
+            &GEOMETRY_256,
+            &key,
+            None,
+        )
+        .unwrap();
+        let mut encryptor = TalosEncryptor::new(
+            output_file,
+            &GEOMETRY_256,
+            variant,
+            s_automaton,
+            t_automaton,
+        );
+        io::copy(&mut input_file, &mut encryptor)
+            .and_then(|_| encryptor.finish())
+            .map_err(|_| io_error(&input_file))
+    } else {
+        let options = EncryptOptions {
+            padding,
+            authenticate: !args.no_auth,
+            geometry,
+            variant,
+            kdf,
+            key_check: true,
+            params: None,
+        };
+        container::encrypt_stream(
+            &mut input_file,
+            input_len,
+            output_file,
+            &key,
+            &options,
+            &mut key::os_rng(),
+        )
+        .map_err(|err| match err {
+            ContainerError::Io(_) => io_error(&input_file),
+            // the input changed length while it was read
+            ContainerError::InvalidLength() => CliError::FileReadError(),
+            err => CliError::InvalidOptions(err),
+        })
+    };
+    if let Err(err) = result.and_then(|output_file| output_file.commit()) {
+        if let Some(key_out) = &args.key_out {
+            let _ = fs::remove_file(key_out);
+        }
+        return Err(err);
+    }
+
+    eprintln!(
+        "Finished encrypting in {} miliseconds",
+        now.elapsed().as_millis()
+    );
+
+    Ok(())
+}
+
+/// Returns the [`CliError`] for an I/O error which occurred while encrypting `input`.
+fn io_error(input: &Input) -> CliError {
+    if input.failed() {
+        CliError::FileReadError()
+    } else {
+        CliError::FileWriteError()
+    }
+}
@@ -0,0 +1,88 @@
+// 2025 Steven Chiacchira
+use super::CliError;
+use crate::container::{
+    Header, FLAG_AUTHENTICATED, FLAG_KDF, FLAG_KEY_CHECK, FLAG_KEY_WIDTH, FLAG_LENGTH_TRAILER,
+    FLAG_NONCE, FLAG_PKCS7,
+};
+use clap::Parser;
+
+#[derive(Parser, Debug)]
+#[command(version, about, long_about=None)]
+/// CLI tool describing the header of a Talos container.
+pub struct InspectArgs {
+    /// The container to inspect, or `-` to read it from stdin
+    input: String,
+}
+
+/// Names of the header flags, in the order of their bits.
+const FLAG_NAMES: [(u16, &str); 7] = [
+    (FLAG_PKCS7, "pkcs7"),
+    (FLAG_AUTHENTICATED, "authenticated"),
+    (FLAG_NONCE, "nonce"),
+    (FLAG_KEY_WIDTH, "key-width"),
+    (FLAG_LENGTH_TRAILER, "length-trailer"),
+    (FLAG_KDF, "kdf"),
+    (FLAG_KEY_CHECK, "key-check"),
+];
+
+/// Prints the header of the container described by `args` to stdout.
+///
+/// Only the header is read, so no key is needed.
+///
+/// # Arguments
+/// * `args` - the parsed command line arguments
+///
+/// # Returns
+/// `()` once the header has been printed, or the [`CliError`] which occurred.
+pub fn run(args: InspectArgs) -> Result<(), CliError> {
+    let (mut input_file, _) = super::open_input(&args.input)?;
+    let header = Header::read(&mut input_file).map_err(CliError::InvalidContainer)?;
+
+    let flags: Vec<&str> = FLAG_NAMES
+        .iter()
+        .filter(|(flag, _)| header.flags & flag != 0)
+        .map(|(_, name)| *name)
+        .collect();
+
+    println!("Format version: {}", header.version);
+    println!("Protocol variant: {:?}", header.variant);
+    println!("Flags: {}", flags.join(", "));
+    println!("Block shape: {}x{}", header.n_rows, header.n_cols);
+    println!("Generations per block: {}", header.iters_per_block);
+    println!("Key width: {} bits", header.key_width.n_bits());
+    if header.flags & FLAG_LENGTH_TRAILER != 0 {
+        println!("Plaintext length: recorded after the ciphertext");
+    } else {
+        println!("Plaintext length: {} bytes", header.plaintext_len);
+        let ciphertext_len = header
+            .ciphertext_len()
+            .map_err(CliError::InvalidContainer)?;
+        println!("Ciphertext length: {} bytes", ciphertext_len);
+    }
+    if let Some(nonce) = header.nonce {
+        let nonce: String = nonce.iter().map(|byte| format!("{:02x}", byte)).collect();
+        println!("Nonce: {}", nonce);
+    }
+    if let Some(kdf) = &header.kdf {
+        let salt: String = kdf
+            .salt
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        println!(
+            "Key derivation: PBKDF2-HMAC-SHA256, {} iterations",
+            kdf.iterations
+        );
+        println!("Salt: {}", salt);
+    }
+    if let Some(key_check) = header.key_check {
+        let key_check: String = key_check
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        println!("Key check value: {}", key_check);
+    }
+    println!("Header size: {} bytes", header.size());
+
+    Ok(())
+}
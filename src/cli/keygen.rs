@@ -0,0 +1,36 @@
+// 2025 Steven Chiacchira
+use super::{CliError, KeyBits};
+use crate::key;
+use clap::Parser;
+
+#[derive(Parser, Debug)]
+#[command(version, about, long_about=None)]
+/// CLI tool generating random keys for the Talos encryption protocol.
+pub struct KeygenArgs {
+    #[arg(long, value_enum, default_value_t = KeyBits::Bits32)]
+    /// The width of the generated key in bits.
+    key_bits: KeyBits,
+    #[arg(short, long, value_name = "PATH")]
+    /// Write the key to a new file only readable by its owner instead of printing it. The key
+    /// can be read back with `--key-file`.
+    out: Option<String>,
+}
+
+/// Generates a random key as described by `args`, and writes it to a key file or prints it to
+/// stdout. The fingerprint of the key is printed to stderr.
+///
+/// # Arguments
+/// * `args` - the parsed command line arguments
+///
+/// # Returns
+/// `()` once the key has been written, or the [`CliError`] which occurred.
+pub fn run(args: KeygenArgs) -> Result<(), CliError> {
+    let key = key::generate_key(args.key_bits.key_width());
+    match &args.out {
+        Some(path) => super::write_key_file(path, &key)?,
+        None => println!("{}", key),
+    }
+    eprintln!("Key fingerprint {}", key.fingerprint());
+
+    Ok(())
+}
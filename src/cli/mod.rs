@@ -0,0 +1,517 @@
+// 2025 Steven Chiacchira
+use crate::container::ContainerError;
+use crate::encrypt::{BlockGeometry, ProtocolVariant};
+use crate::kdf::KdfParams;
+use crate::key::{KeyArgument, KeyError, KeyWidth, TalosKey};
+use crate::matrix::MatrixConstructError;
+use crate::parse::TableReadError;
+use clap::{Args, ValueEnum};
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+/// Statistical analyses of the Talos automata.
+pub mod analyze;
+/// Measurement of encryption throughput.
+pub mod bench;
+/// Decryption of files and Talos containers.
+pub mod decrypt;
+/// Encryption of files into Talos containers.
+pub mod encrypt;
+/// Description of Talos container headers.
+pub mod inspect;
+/// Generation of random encryption keys.
+pub mod keygen;
+
+/// The path standing for stdin or stdout.
+pub const STDIO_PATH: &str = "-";
+
+#[derive(Debug)]
+/// Possible error states for the Talos CLI tools.
+pub enum CliError {
+    /// An error occurred reading the specified input file.
+    FileReadError(),
+    /// An error occurred writing to the specified output file.
+    FileWriteError(),
+    /// No key of the requested width can be computed from the given key.
+    InvalidKey(KeyError),
+    /// The container could not be built with the requested options.
+    InvalidOptions(ContainerError),
+    /// The input file is not a valid Talos container.
+    InvalidContainer(ContainerError),
+    /// The input file has been modified or the wrong key was given.
+    AuthenticationFailed(),
+    /// The key does not match the key check value recorded in the container.
+    WrongKey(),
+    /// An error occurred while streaming the input or output.
+    IoError(io::Error),
+    /// The initialization matrix file contains an invalid table.
+    InvalidTable(TableReadError),
+    /// The initialization matrix file does not describe a matrix.
+    InvalidMatrix(MatrixConstructError),
+    /// No key was given, and the generated key would neither be written to a file nor printed.
+    UnrecordedKey(),
+}
+
+impl Error for CliError {}
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::FileReadError() => {
+                write!(f, "Error reading file")
+            }
+            Self::FileWriteError() => {
+                write!(f, "Error writing output")
+            }
+            Self::InvalidKey(err) => {
+                write!(f, "Invalid key: {}", err)
+            }
+            Self::InvalidOptions(err) => {
+                write!(f, "Invalid options: {}", err)
+            }
+            Self::InvalidContainer(err) => {
+                write!(f, "Invalid container: {}", err)
+            }
+            Self::AuthenticationFailed() => {
+                write!(f, "Authentication failed")
+            }
+            Self::WrongKey() => {
+                write!(f, "The container was encrypted with a different key")
+            }
+            Self::IoError(err) => {
+                write!(f, "I/O error: {}", err)
+            }
+            Self::InvalidTable(err) => {
+                write!(f, "Invalid initialization matrix: {}", err)
+            }
+            Self::InvalidMatrix(err) => {
+                write!(f, "Invalid initialization matrix: {}", err)
+            }
+            Self::UnrecordedKey() => {
+                write!(
+                    f,
+                    "No key was given; pass --key-out or --emit-key to record the generated key"
+                )
+            }
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+#[group(id = "key_source", multiple = false)]
+/// The sources an encryption key can be read from. At most one source may be given.
+pub struct KeyArgs {
+    #[arg(short, long)]
+    /// The encryption key to be used. If a numerical input is given, it will be used as the
+    /// encryption key. If a string is given, it will be used as a passphrase.
+    key: Option<KeyArgument>,
+    #[arg(long, value_name = "HEX", value_parser = KeyArgument::from_hex)]
+    /// The encryption key as hexadecimal digits of its little-endian bytes, as printed for wide
+    /// keys. The number of digits must match the key width.
+    key_hex: Option<KeyArgument>,
+    #[arg(long, value_name = "PATH")]
+    /// A file holding the encryption key, either as hexadecimal digits or as raw bytes. The size
+    /// of the key must match the key width.
+    key_file: Option<PathBuf>,
+    #[arg(long, value_name = "VAR")]
+    /// The name of an environment variable holding a passphrase.
+    key_env: Option<String>,
+    #[arg(long, action)]
+    /// Read a passphrase from the terminal without echoing it.
+    key_prompt: bool,
+}
+
+impl KeyArgs {
+    /// Returns the key given by whichever source was selected.
+    ///
+    /// # Returns
+    /// The given key, or `None` if no source was selected.
+    #[must_use]
+    pub fn key_argument(self) -> Option<KeyArgument> {
+        if self.key_prompt {
+            return Some(KeyArgument::Prompt);
+        }
+
+        self.key
+            .or(self.key_hex)
+            .or(self.key_file.map(KeyArgument::File))
+            .or(self.key_env.map(KeyArgument::Env))
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+/// Key widths selectable from the command line. See [`KeyWidth`].
+pub enum KeyBits {
+    #[value(name = "32")]
+    /// 32 bit keys
+    Bits32,
+    #[value(name = "128")]
+    /// 128 bit keys
+    Bits128,
+    #[value(name = "256")]
+    /// 256 bit keys
+    Bits256,
+}
+
+impl KeyBits {
+    /// Returns the [`KeyWidth`] selected by the `KeyBits`.
+    ///
+    /// # Returns
+    /// The selected [`KeyWidth`].
+    #[must_use]
+    pub fn key_width(self) -> KeyWidth {
+        match self {
+            Self::Bits32 => KeyWidth::Bits32,
+            Self::Bits128 => KeyWidth::Bits128,
+            Self::Bits256 => KeyWidth::Bits256,
+        }
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+/// Block dimensions selectable from the command line. See [`BlockGeometry`].
+pub enum BlockDim {
+    #[value(name = "16")]
+    /// 16x16 blocks, as specified in RFC-0
+    Dim16,
+    #[value(name = "32")]
+    /// 32x32 blocks
+    Dim32,
+    #[value(name = "64")]
+    /// 64x64 blocks
+    Dim64,
+}
+
+impl BlockDim {
+    /// Returns the [`BlockGeometry`] selected by the `BlockDim`.
+    ///
+    /// # Returns
+    /// The selected [`BlockGeometry`].
+    #[must_use]
+    pub fn geometry(self) -> BlockGeometry {
+        let dim = match self {
+            Self::Dim16 => 16,
+            Self::Dim32 => 32,
+            Self::Dim64 => 64,
+        };
+
+        BlockGeometry::new(dim).unwrap()
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+/// Protocol variants selectable from the command line. See [`ProtocolVariant`].
+pub enum Variant {
+    /// The protocol of RFC-1
+    Rfc1,
+    /// The protocol as originally implemented, whose scrambling never swaps columns
+    Legacy,
+    /// RFC-1, additionally rotating the rows and columns of each block while scrambling
+    Rotating,
+    /// The rotating protocol as originally implemented, whose scrambling never swaps columns
+    RotatingLegacy,
+}
+
+impl Variant {
+    /// Returns the [`ProtocolVariant`] selected by the `Variant`.
+    ///
+    /// # Returns
+    /// The selected [`ProtocolVariant`].
+    #[must_use]
+    pub fn protocol_variant(self) -> ProtocolVariant {
+        match self {
+            Self::Rfc1 => ProtocolVariant::Rfc1,
+            Self::Legacy => ProtocolVariant::LegacyRfc1,
+            Self::Rotating => ProtocolVariant::RotatingScramble,
+            Self::RotatingLegacy => ProtocolVariant::LegacyRotatingScramble,
+        }
+    }
+}
+
+/// Computes the encryption key of width `width` given on the command line.
+///
+/// # Arguments
+/// * `key` - the key given on the command line
+/// * `width` - the width of the key to compute
+/// * `kdf` - the parameters to derive passphrase keys with, or `None` for the legacy derivation.
+///   See [`KeyArgument::get_key_with_kdf`]
+///
+/// # Returns
+/// The encryption key, or [`CliError::InvalidKey`] if no key of width `width` can be computed.
+pub fn get_key(
+    key: KeyArgument,
+    width: KeyWidth,
+    kdf: Option<&KdfParams>,
+) -> Result<TalosKey, CliError> {
+    match kdf {
+        Some(params) => key.get_key_with_kdf(width, params),
+        None => key.get_key(width),
+    }
+    .map_err(CliError::InvalidKey)
+}
+
+/// Writes `key` to a new file at `path` which only its owner can read, as hexadecimal digits
+/// which can be read back with `--key-file`.
+///
+/// # Arguments
+/// * `path` - the path of the key file. The file must not exist yet
+/// * `key` - the key to write
+///
+/// # Returns
+/// `()` once the key has been written, or [`CliError::FileWriteError`] if the file cannot be
+/// created.
+pub fn write_key_file(path: &str, key: &TalosKey) -> Result<(), CliError> {
+    let mut file = create_private_file(Path::new(path)).map_err(|_| CliError::FileWriteError())?;
+    writeln!(file, "{}", key.to_hex()).map_err(CliError::IoError)
+}
+
+/// Creates a new file at `path` which only its owner can read.
+///
+/// # Arguments
+/// * `path` - the path of the file. The file must not exist yet
+///
+/// # Returns
+/// The created file, or the error which occurred while creating it.
+fn create_private_file(path: &Path) -> io::Result<fs::File> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    options.open(path)
+}
+
+/// Prints the fingerprint of `key` to stderr, and the key itself if `emit_key` is set.
+///
+/// # Arguments
+/// * `key` - the key in use
+/// * `emit_key` - whether to print the secret key
+pub fn report_key(key: &TalosKey, emit_key: bool) {
+    if emit_key {
+        eprintln!("Using key {}", key);
+    }
+    eprintln!("Key fingerprint {}", key.fingerprint());
+}
+
+/// Input of a CLI tool, recording whether reading from it has failed so that I/O errors can be
+/// told apart from errors writing the output.
+pub struct Input {
+    /// The reader over the input.
+    inner: Box<dyn Read>,
+    /// Whether a read has failed.
+    failed: bool,
+}
+
+impl Input {
+    /// Returns whether reading from the `Input` has failed.
+    ///
+    /// # Returns
+    /// `true` if a read from the `Input` has returned an error.
+    #[must_use]
+    pub fn failed(&self) -> bool {
+        self.failed
+    }
+}
+
+impl Read for Input {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let result = self.inner.read(buf);
+        if result
+            .as_ref()
+            .is_err_and(|err| err.kind() != io::ErrorKind::Interrupted)
+        {
+            self.failed = true;
+        }
+
+        result
+    }
+}
+
+/// Opens the input at `path`, which is stdin if `path` is [`STDIO_PATH`].
+///
+/// # Arguments
+/// * `path` - the path of the file to read
+///
+/// # Returns
+/// A buffered reader over the input and the length of the input if it is a regular file, or
+/// [`CliError::FileReadError`] if the file cannot be opened.
+pub fn open_input(path: &str) -> Result<(Input, Option<u64>), CliError> {
+    if path == STDIO_PATH {
+        let input = Input {
+            inner: Box::new(io::stdin().lock()),
+            failed: false,
+        };
+        return Ok((input, None));
+    }
+
+    let file = fs::File::open(path).map_err(|_| CliError::FileReadError())?;
+    let input_len = match file.metadata() {
+        Ok(metadata) => metadata.is_file().then_some(metadata.len()),
+        Err(_) => {
+            return Err(CliError::FileReadError());
+        }
+    };
+    let input = Input {
+        inner: Box::new(BufReader::new(file)),
+        failed: false,
+    };
+
+    Ok((input, input_len))
+}
+
+/// Output of a CLI tool, which is either stdout or a file only moved into place once it is
+/// complete.
+pub enum Output {
+    /// Output written directly to stdout.
+    Stdout(BufWriter<io::StdoutLock<'static>>),
+    /// Output staged in a temporary file.
+    Staged(StagedFile),
+}
+
+impl Output {
+    /// Completes the `Output`, moving a staged file to its path.
+    ///
+    /// # Returns
+    /// `()` once the output is complete, or [`CliError::FileWriteError`] if it cannot be written.
+    pub fn commit(self) -> Result<(), CliError> {
+        match self {
+            Self::Stdout(mut stdout) => stdout.flush().map_err(|_| CliError::FileWriteError()),
+            Self::Staged(file) => file.commit(),
+        }
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Stdout(stdout) => stdout.write(buf),
+            Self::Staged(file) => file.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Stdout(stdout) => stdout.flush(),
+            Self::Staged(file) => file.flush(),
+        }
+    }
+}
+
+/// A file written to a temporary path next to its destination, so that the destination is never
+/// left incomplete and can also be the input being read.
+///
+/// The temporary file is only readable by its owner. It is moved to the destination by
+/// [`StagedFile::commit`], and is removed if the `StagedFile` is dropped without being
+/// committed.
+pub struct StagedFile {
+    /// The writer over the temporary file.
+    file: BufWriter<fs::File>,
+    /// The path of the temporary file.
+    temp_path: PathBuf,
+    /// The path the file is moved to once committed, or `None` to copy it to stdout.
+    path: Option<PathBuf>,
+    /// Whether the temporary file has been moved to `path`.
+    committed: bool,
+}
+
+impl StagedFile {
+    /// Creates a new `StagedFile` which will be moved to `path`.
+    ///
+    /// # Arguments
+    /// * `path` - the destination of the file
+    ///
+    /// # Returns
+    /// The created `StagedFile`, or [`CliError::FileWriteError`] if the temporary file cannot be
+    /// created.
+    pub fn create(path: &Path) -> Result<Self, CliError> {
+        let mut temp_path = path.as_os_str().to_owned();
+        temp_path.push(format!(".{:08x}.tmp", rand::random::<u32>()));
+
+        Self::create_at(PathBuf::from(temp_path), Some(path.to_owned()))
+    }
+
+    /// Creates a new `StagedFile` in the temporary directory, which will be copied to stdout.
+    ///
+    /// # Returns
+    /// The created `StagedFile`, or [`CliError::FileWriteError`] if the temporary file cannot be
+    /// created.
+    pub fn create_for_stdout() -> Result<Self, CliError> {
+        let temp_path =
+            std::env::temp_dir().join(format!("talos.{:08x}.tmp", rand::random::<u32>()));
+
+        Self::create_at(temp_path, None)
+    }
+
+    /// Creates a new `StagedFile` writing to `temp_path`.
+    fn create_at(temp_path: PathBuf, path: Option<PathBuf>) -> Result<Self, CliError> {
+        let file = create_private_file(&temp_path).map_err(|_| CliError::FileWriteError())?;
+
+        Ok(Self {
+            file: BufWriter::new(file),
+            temp_path,
+            path,
+            committed: false,
+        })
+    }
+
+    /// Flushes the temporary file and moves it to its destination, or copies it to stdout.
+    ///
+    /// # Returns
+    /// `()` once the file is in place, or [`CliError::FileWriteError`] if it cannot be written.
+    pub fn commit(mut self) -> Result<(), CliError> {
+        self.file.flush().map_err(|_| CliError::FileWriteError())?;
+        let Some(path) = &self.path else {
+            let mut temp_file =
+                fs::File::open(&self.temp_path).map_err(|_| CliError::FileWriteError())?;
+            let mut stdout = io::stdout().lock();
+            // the temporary file is removed when the `StagedFile` is dropped
+            return io::copy(&mut temp_file, &mut stdout)
+                .and_then(|_| stdout.flush())
+                .map_err(|_| CliError::FileWriteError());
+        };
+        fs::rename(&self.temp_path, path).map_err(|_| CliError::FileWriteError())?;
+        self.committed = true;
+
+        Ok(())
+    }
+}
+
+impl Write for StagedFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.file.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+impl Drop for StagedFile {
+    fn drop(&mut self) {
+        if !self.committed {
+            let _ = fs::remove_file(&self.temp_path);
+        }
+    }
+}
+
+/// Creates the output at `path`, which is stdout if `path` is [`STDIO_PATH`]. A file output is
+/// staged next to `path`, and only replaces `path` once [`Output::commit`] is called.
+///
+/// # Arguments
+/// * `path` - the path of the file to write
+///
+/// # Returns
+/// A buffered writer over the output, or [`CliError::FileWriteError`] if the file cannot be
+/// created.
+pub fn create_output(path: &str) -> Result<Output, CliError> {
+    if path == STDIO_PATH {
+        return Ok(Output::Stdout(BufWriter::new(io::stdout().lock())));
+    }
+
+    StagedFile::create(Path::new(path)).map(Output::Staged)
+}
@@ -0,0 +1,1346 @@
+// 2025 Steven Chiacchira
+use crate::encrypt::{
+    self, BlockGeometry, CipherParams, PaddingError, PaddingStrategy, ProtocolVariant,
+    TalosAutomaton, GEOMETRY_256, MAX_PKCS7_BLOCK_BYTES, NONCE_SIZE, N_ITERS_PER_BLOCK,
+};
+use crate::kdf::{KdfParams, SALT_SIZE};
+use crate::key::{self, KeyRng, KeyWidth, TalosKey};
+use crate::mac::{self, HmacSha256};
+use crate::stream::{self, TalosDecryptor, TalosEncryptor};
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Read, Write};
+
+/// Magic bytes at the start of every Talos container.
+pub const MAGIC: [u8; 5] = *b"TALOS";
+/// The container format version written by this implementation.
+pub const FORMAT_VERSION: u8 = 1;
+/// Number of bytes in the fixed part of a serialized [`Header`]. Optional fields follow the fixed
+/// part. See [`Header::size`] for the full size of a header.
+pub const HEADER_SIZE: usize = 25;
+/// Header flag set when the plaintext was padded with
+/// [`pad_message_256`](crate::encrypt::pad_message_256) before encryption. If unset, the
+/// plaintext length in the [`Header`] is used to remove padding.
+pub const FLAG_PKCS7: u16 = 1 << 0;
+/// Header flag set when the ciphertext is followed by an HMAC-SHA-256 tag over the header and
+/// ciphertext. See [`mac`].
+pub const FLAG_AUTHENTICATED: u16 = 1 << 1;
+/// Header flag set when the header contains a nonce. See
+/// [`get_shift_transpose_automata_with_params`](crate::encrypt::get_shift_transpose_automata_with_params).
+pub const FLAG_NONCE: u16 = 1 << 2;
+/// Header flag set when the header records the width of the encryption key. If unset, the
+/// container was encrypted with a 32 bit key.
+pub const FLAG_KEY_WIDTH: u16 = 1 << 3;
+/// Header flag set when the plaintext length was not known when the header was written. The
+/// `plaintext_len` field of the [`Header`] is then zero, and the length is instead stored in a
+/// [`LENGTH_TRAILER_SIZE`] byte trailer following the ciphertext.
+pub const FLAG_LENGTH_TRAILER: u16 = 1 << 4;
+/// Header flag set when the encryption key was derived from a passphrase, and the header records
+/// the [`KdfParams`] of the derivation.
+pub const FLAG_KDF: u16 = 1 << 5;
+/// Header flag set when the header contains a key check value, which allows decryption with the
+/// wrong key to be rejected before anything is decrypted. See [`Header::key_check_value`].
+pub const FLAG_KEY_CHECK: u16 = 1 << 6;
+/// Bitmask of all header flags understood by this implementation.
+const KNOWN_FLAGS: u16 = FLAG_PKCS7
+    | FLAG_AUTHENTICATED
+    | FLAG_NONCE
+    | FLAG_KEY_WIDTH
+    | FLAG_LENGTH_TRAILER
+    | FLAG_KDF
+    | FLAG_KEY_CHECK;
+/// Number of bytes in the little-endian plaintext length following the ciphertext of containers
+/// with [`FLAG_LENGTH_TRAILER`] set.
+pub const LENGTH_TRAILER_SIZE: usize = 8;
+/// Number of bytes in the key check value of a [`Header`] with [`FLAG_KEY_CHECK`] set.
+pub const KEY_CHECK_SIZE: usize = 4;
+
+/// Error occurring while reading a Talos container.
+#[derive(Debug)]
+pub enum ContainerError {
+    /// The input does not start with [`MAGIC`], and so is not a Talos container.
+    BadMagic(),
+    /// The container was written with a format version this implementation cannot read.
+    UnsupportedVersion(u8),
+    /// The container specifies a [`ProtocolVariant`] this implementation does not know.
+    UnknownVariant(u8),
+    /// The container sets header flags this implementation does not know.
+    UnknownFlags(u16),
+    /// The container specifies a block geometry or iteration count this implementation cannot
+    /// decrypt, or a container was requested with a block geometry this implementation cannot
+    /// encrypt.
+    UnsupportedParameters(),
+    /// The container ends before its header or ciphertext is complete.
+    Truncated(),
+    /// The length of the ciphertext does not match the plaintext length in the header, or the
+    /// input to [`encrypt_stream`] does not have the given length.
+    InvalidLength(),
+    /// The decrypted plaintext is not correctly padded. This usually indicates that the container
+    /// was decrypted with the wrong key or has been corrupted. Also returned by
+    /// [`encrypt_stream`] when the plaintext cannot be padded as requested.
+    InvalidPadding(PaddingError),
+    /// The authentication tag does not match the container. Either the container has been
+    /// modified or the wrong key was used.
+    AuthenticationFailed(),
+    /// The container carries no authentication tag, but authentication was required.
+    Unauthenticated(),
+    /// The container was encrypted with a key of a different width than the one given.
+    KeyWidthMismatch(KeyWidth),
+    /// The key check value in the header does not match the given key, so the container was
+    /// encrypted with a different key.
+    WrongKey(),
+    /// An error occurred reading or writing a stream.
+    Io(io::Error),
+}
+
+impl Error for ContainerError {}
+impl fmt::Display for ContainerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::BadMagic() => {
+                write!(f, "Not a Talos container")
+            }
+            Self::UnsupportedVersion(version) => {
+                write!(f, "Unsupported container version: {}", version)
+            }
+            Self::UnknownVariant(variant) => {
+                write!(f, "Unknown protocol variant: {}", variant)
+            }
+            Self::UnknownFlags(flags) => {
+                write!(f, "Unknown header flags: {:#06x}", flags)
+            }
+            Self::UnsupportedParameters() => {
+                write!(f, "Unsupported cipher parameters")
+            }
+            Self::Truncated() => {
+                write!(f, "Truncated container")
+            }
+            Self::InvalidLength() => {
+                write!(f, "Invalid ciphertext length")
+            }
+            Self::InvalidPadding(err) => {
+                write!(f, "Invalid padding: {}", err)
+            }
+            Self::AuthenticationFailed() => {
+                write!(f, "Authentication failed")
+            }
+            Self::Unauthenticated() => {
+                write!(f, "Container is not authenticated")
+            }
+            Self::KeyWidthMismatch(width) => {
+                write!(f, "Container requires a {} bit key", width.n_bits())
+            }
+            Self::WrongKey() => {
+                write!(f, "Container was encrypted with a different key")
+            }
+            Self::Io(err) => {
+                write!(f, "I/O error: {}", err)
+            }
+        }
+    }
+}
+
+/// Header written in front of the block stream of a Talos container.
+///
+/// If [`FLAG_LENGTH_TRAILER`] is set, the block stream is followed by the plaintext length. If
+/// [`FLAG_AUTHENTICATED`] is set, the block stream and length trailer are followed by a
+/// [`mac::TAG_SIZE`] byte authentication tag.
+///
+/// The fixed part of a serialized header is [`HEADER_SIZE`] bytes long and has the following
+/// little-endian layout:
+///
+/// |Offset|Size|Field            |
+/// |------|----|-----------------|
+/// |0     |5   |[`MAGIC`]        |
+/// |5     |1   |`version`        |
+/// |6     |1   |`variant`        |
+/// |7     |2   |`flags`          |
+/// |9     |2   |`n_rows`         |
+/// |11    |2   |`n_cols`         |
+/// |13    |4   |`iters_per_block`|
+/// |17    |8   |`plaintext_len`  |
+///
+/// It is followed by the optional fields below, in order, each present only if its flag is set:
+///
+/// |Flag              |Size          |Field                         |
+/// |------------------|--------------|------------------------------|
+/// |[`FLAG_NONCE`]    |[`NONCE_SIZE`]|`nonce`                       |
+/// |[`FLAG_KEY_WIDTH`]|1             |`key_width`, in bytes of key  |
+/// |[`FLAG_KDF`]      |[`SALT_SIZE`] |`kdf.salt`                    |
+/// |[`FLAG_KDF`]      |4             |`kdf.iterations`              |
+/// |[`FLAG_KEY_CHECK`]|[`KEY_CHECK_SIZE`]|`key_check`                |
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Header {
+    /// The container format version.
+    pub version: u8,
+    /// The protocol variant used to produce the ciphertext.
+    pub variant: ProtocolVariant,
+    /// Bitfield of optional container features.
+    pub flags: u16,
+    /// The number of rows in an encryption block.
+    pub n_rows: u16,
+    /// The number of columns in an encryption block.
+    pub n_cols: u16,
+    /// The number of automaton generations computed for each encryption block.
+    pub iters_per_block: u32,
+    /// The length of the plaintext in bytes.
+    pub plaintext_len: u64,
+    /// The nonce mixed into automaton initialization. Present if and only if [`FLAG_NONCE`] is
+    /// set; use [`Header::set_nonce`] to keep the two consistent.
+    pub nonce: Option<[u8; NONCE_SIZE]>,
+    /// The width of the encryption key. Recorded in the serialized header if and only if
+    /// [`FLAG_KEY_WIDTH`] is set; use [`Header::set_key_width`] to keep the two consistent.
+    pub key_width: KeyWidth,
+    /// The parameters with which the encryption key was derived from a passphrase. Present if
+    /// and only if [`FLAG_KDF`] is set; use [`Header::set_kdf`] to keep the two consistent.
+    pub kdf: Option<KdfParams>,
+    /// The key check value of the encryption key. Present if and only if [`FLAG_KEY_CHECK`] is
+    /// set; use [`Header::set_key_check`] to keep the two consistent.
+    pub key_check: Option<[u8; KEY_CHECK_SIZE]>,
+}
+
+impl Header {
+    /// Creates a new `Header` for a message encrypted with the default Talos parameters.
+    ///
+    /// The block geometry is [`GEOMETRY_256`]; use [`Header::set_geometry`] to change it.
+    ///
+    /// # Arguments
+    /// * `variant` - the protocol variant used to encrypt the message
+    /// * `plaintext_len` - the length of the plaintext in bytes
+    ///
+    /// # Returns
+    /// The created `Header`.
+    #[must_use]
+    pub fn new(variant: ProtocolVariant, plaintext_len: u64) -> Self {
+        Self {
+            version: FORMAT_VERSION,
+            variant,
+            flags: 0,
+            n_rows: GEOMETRY_256.n_rows() as u16,
+            n_cols: GEOMETRY_256.n_cols() as u16,
+            iters_per_block: N_ITERS_PER_BLOCK,
+            plaintext_len,
+            nonce: None,
+            key_width: KeyWidth::Bits32,
+            kdf: None,
+            key_check: None,
+        }
+    }
+
+    /// Sets the nonce stored in the `Header`, along with [`FLAG_NONCE`].
+    ///
+    /// # Arguments
+    /// * `nonce` - the nonce mixed into automaton initialization
+    pub fn set_nonce(&mut self, nonce: [u8; NONCE_SIZE]) {
+        self.flags |= FLAG_NONCE;
+        self.nonce = Some(nonce);
+    }
+
+    /// Sets the block geometry stored in the `Header`.
+    ///
+    /// # Arguments
+    /// * `geometry` - the shape of the encryption blocks
+    pub fn set_geometry(&mut self, geometry: &BlockGeometry) {
+        self.n_rows = geometry.n_rows() as u16;
+        self.n_cols = geometry.n_cols() as u16;
+    }
+
+    /// Returns the block geometry stored in the `Header`.
+    ///
+    /// # Returns
+    /// The shape of the encryption blocks, or `None` if the `Header` does not describe a valid
+    /// [`BlockGeometry`].
+    #[must_use]
+    pub fn geometry(&self) -> Option<BlockGeometry> {
+        if self.n_rows != self.n_cols {
+            return None;
+        }
+
+        BlockGeometry::new(self.n_rows as usize).ok()
+    }
+
+    /// Sets the key width stored in the `Header`, along with [`FLAG_KEY_WIDTH`].
+    ///
+    /// 32 bit keys are implied by the absence of [`FLAG_KEY_WIDTH`], so the flag is cleared for
+    /// [`KeyWidth::Bits32`] and headers written for 32 bit keys are unchanged.
+    ///
+    /// # Arguments
+    /// * `key_width` - the width of the encryption key
+    pub fn set_key_width(&mut self, key_width: KeyWidth) {
+        if key_width == KeyWidth::Bits32 {
+            self.flags &= !FLAG_KEY_WIDTH;
+        } else {
+            self.flags |= FLAG_KEY_WIDTH;
+        }
+        self.key_width = key_width;
+    }
+
+    /// Sets the key derivation parameters stored in the `Header`, along with [`FLAG_KDF`].
+    ///
+    /// # Arguments
+    /// * `kdf` - the parameters with which the encryption key was derived from a passphrase
+    pub fn set_kdf(&mut self, kdf: KdfParams) {
+        self.flags |= FLAG_KDF;
+        self.kdf = Some(kdf);
+    }
+
+    /// Computes the key check value of `key` for the `Header`.
+    ///
+    /// The key check value is the first [`KEY_CHECK_SIZE`] bytes of the HMAC-SHA-256 tag of the
+    /// nonce, keyed with the subkey of `key` labelled [`key::KEY_CHECK_LABEL`]. The same key
+    /// therefore has a different check value in every container, and keys derived from a
+    /// passphrase also depend on the salt recorded in the `Header`. Like the authentication tag,
+    /// the check value allows the key to be tested without decrypting, so it does not weaken
+    /// keys which are too wide to search.
+    ///
+    /// # Arguments
+    /// * `key` - the encryption key
+    ///
+    /// # Returns
+    /// The key check value of `key`.
+    #[must_use]
+    pub fn key_check_value(&self, key: &TalosKey) -> [u8; KEY_CHECK_SIZE] {
+        let subkey = key::derive_subkey(key, key::KEY_CHECK_LABEL);
+        let tag = mac::hmac_sha256(&*subkey, self.nonce.as_ref().map_or(&[], |nonce| nonce));
+
+        tag[..KEY_CHECK_SIZE].try_into().unwrap()
+    }
+
+    /// Sets the key check value stored in the `Header` to that of `key`, along with
+    /// [`FLAG_KEY_CHECK`]. The nonce must be set first.
+    ///
+    /// # Arguments
+    /// * `key` - the encryption key
+    pub fn set_key_check(&mut self, key: &TalosKey) {
+        self.flags |= FLAG_KEY_CHECK;
+        self.key_check = Some(self.key_check_value(key));
+    }
+
+    /// Checks that the container described by the `Header` was encrypted with `key`, without
+    /// decrypting anything.
+    ///
+    /// # Arguments
+    /// * `key` - the key to check
+    ///
+    /// # Returns
+    /// `()` if `key` has the recorded width and matches the recorded key check value, or if there
+    /// is no key check value, [`ContainerError::KeyWidthMismatch`] if `key` has the wrong width,
+    /// and [`ContainerError::WrongKey`] if `key` does not match the key check value.
+    pub fn check_key(&self, key: &TalosKey) -> Result<(), ContainerError> {
+        if self.key_width != key.width() {
+            return Err(ContainerError::KeyWidthMismatch(self.key_width));
+        }
+        match self.key_check {
+            Some(key_check) if !mac::tags_equal(&self.key_check_value(key), &key_check) => {
+                Err(ContainerError::WrongKey())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the size of the serialized `Header`, including its optional fields.
+    ///
+    /// # Returns
+    /// The number of bytes [`Header::to_bytes`] will produce.
+    #[must_use]
+    pub fn size(&self) -> usize {
+        header_size(self.flags)
+    }
+
+    /// Serializes the `Header` to bytes.
+    ///
+    /// # Returns
+    /// The serialized `Header`, which is [`Header::size`] bytes long.
+    #[must_use]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut result = Vec::with_capacity(self.size());
+        result.extend_from_slice(&MAGIC);
+        result.push(self.version);
+        result.push(self.variant.id());
+        result.extend_from_slice(&self.flags.to_le_bytes());
+        result.extend_from_slice(&self.n_rows.to_le_bytes());
+        result.extend_from_slice(&self.n_cols.to_le_bytes());
+        result.extend_from_slice(&self.iters_per_block.to_le_bytes());
+        result.extend_from_slice(&self.plaintext_len.to_le_bytes());
+        if let Some(nonce) = self.nonce {
+            result.extend_from_slice(&nonce);
+        }
+        if self.flags & FLAG_KEY_WIDTH != 0 {
+            result.push((self.key_width.n_bits() / u8::BITS as usize) as u8);
+        }
+        if let Some(kdf) = &self.kdf {
+            result.extend_from_slice(&kdf.salt);
+            result.extend_from_slice(&kdf.iterations.to_le_bytes());
+        }
+        if let Some(key_check) = self.key_check {
+            result.extend_from_slice(&key_check);
+        }
+
+        result
+    }
+
+    /// Parses a `Header` from the start of `bytes`.
+    ///
+    /// The magic bytes and version are checked before any other field is read, so that
+    /// containers written by a future format version are rejected with
+    /// [`ContainerError::UnsupportedVersion`] rather than misread.
+    ///
+    /// # Arguments
+    /// * `bytes` - the bytes to parse. Bytes following the header are ignored
+    ///
+    /// # Returns
+    /// The parsed `Header`, or a [`ContainerError`] if `bytes` does not start with a valid header.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ContainerError> {
+        if bytes.len() < MAGIC.len() + 1 {
+            return Err(ContainerError::Truncated());
+        }
+        if bytes[..MAGIC.len()] != MAGIC {
+            return Err(ContainerError::BadMagic());
+        }
+        let version = bytes[5];
+        if version != FORMAT_VERSION {
+            return Err(ContainerError::UnsupportedVersion(version));
+        }
+        if bytes.len() < HEADER_SIZE {
+            return Err(ContainerError::Truncated());
+        }
+
+        let variant =
+            ProtocolVariant::from_id(bytes[6]).ok_or(ContainerError::UnknownVariant(bytes[6]))?;
+        let flags = u16::from_le_bytes(bytes[7..9].try_into().unwrap());
+        if flags & !KNOWN_FLAGS != 0 {
+            return Err(ContainerError::UnknownFlags(flags & !KNOWN_FLAGS));
+        }
+
+        let mut result = Self {
+            version,
+            variant,
+            flags,
+            n_rows: u16::from_le_bytes(bytes[9..11].try_into().unwrap()),
+            n_cols: u16::from_le_bytes(bytes[11..13].try_into().unwrap()),
+            iters_per_block: u32::from_le_bytes(bytes[13..17].try_into().unwrap()),
+            plaintext_len: u64::from_le_bytes(bytes[17..25].try_into().unwrap()),
+            nonce: None,
+            key_width: KeyWidth::Bits32,
+            kdf: None,
+            key_check: None,
+        };
+        if result.geometry().is_none() {
+            return Err(ContainerError::UnsupportedParameters());
+        }
+        if bytes.len() < result.size() {
+            return Err(ContainerError::Truncated());
+        }
+
+        let mut offset = HEADER_SIZE;
+        if flags & FLAG_NONCE != 0 {
+            result.nonce = Some(bytes[offset..offset + NONCE_SIZE].try_into().unwrap());
+            offset += NONCE_SIZE;
+        }
+        if flags & FLAG_KEY_WIDTH != 0 {
+            result.key_width = KeyWidth::from_n_bits(bytes[offset] as usize * u8::BITS as usize)
+                .ok_or(ContainerError::UnsupportedParameters())?;
+            offset += 1;
+        }
+        if flags & FLAG_KDF != 0 {
+            let salt = bytes[offset..offset + SALT_SIZE].try_into().unwrap();
+            offset += SALT_SIZE;
+            let iterations = u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+            offset += 4;
+            if iterations == 0 {
+                return Err(ContainerError::UnsupportedParameters());
+            }
+            result.kdf = Some(KdfParams { salt, iterations });
+        }
+        if flags & FLAG_KEY_CHECK != 0 {
+            result.key_check = Some(bytes[offset..offset + KEY_CHECK_SIZE].try_into().unwrap());
+            offset += KEY_CHECK_SIZE;
+        }
+        debug_assert_eq!(offset, result.size());
+
+        Ok(result)
+    }
+
+    /// Reads a `Header` from the start of `input`, leaving `input` positioned at the ciphertext.
+    ///
+    /// Performs the same checks as [`Header::from_bytes`], reading no more bytes than the
+    /// header occupies.
+    ///
+    /// # Arguments
+    /// * `input` - the reader to read the header from
+    ///
+    /// # Returns
+    /// The parsed `Header`, or a [`ContainerError`] if `input` does not start with a valid header.
+    pub fn read<R: Read + ?Sized>(input: &mut R) -> Result<Self, ContainerError> {
+        let mut bytes = vec![0_u8; HEADER_SIZE];
+        let n_read = stream::read_full(input, &mut bytes).map_err(ContainerError::Io)?;
+        if n_read < HEADER_SIZE {
+            // always an error, since the fixed part of the header is incomplete
+            return Self::from_bytes(&bytes[..n_read]);
+        }
+
+        if bytes[..MAGIC.len()] != MAGIC || bytes[5] != FORMAT_VERSION {
+            // the flags of other formats do not describe the size of their header
+            return Self::from_bytes(&bytes);
+        }
+        let flags = u16::from_le_bytes(bytes[7..9].try_into().unwrap());
+        bytes.resize(header_size(flags), 0);
+        input
+            .read_exact(&mut bytes[HEADER_SIZE..])
+            .map_err(stream_error)?;
+
+        Self::from_bytes(&bytes)
+    }
+
+    /// Returns the number of ciphertext bytes which follow a `Header`.
+    ///
+    /// If [`FLAG_PKCS7`] is set, the padding added to the plaintext is included in this length.
+    /// If [`FLAG_LENGTH_TRAILER`] is set, the plaintext length is not known from the `Header`
+    /// alone, and neither is the ciphertext length.
+    ///
+    /// # Returns
+    /// The length of the block stream described by the `Header` in bytes,
+    /// [`ContainerError::UnsupportedParameters`] if the `Header` does not describe a valid
+    /// [`BlockGeometry`], or [`ContainerError::InvalidLength`] if the length does not fit in a
+    /// `u64`.
+    pub fn ciphertext_len(&self) -> Result<u64, ContainerError> {
+        let block_bytes = self
+            .geometry()
+            .ok_or(ContainerError::UnsupportedParameters())?
+            .block_bytes() as u64;
+        let n_blocks = if self.flags & FLAG_PKCS7 != 0 {
+            self.plaintext_len / block_bytes + 1
+        } else {
+            self.plaintext_len.div_ceil(block_bytes)
+        };
+
+        n_blocks
+            .checked_mul(block_bytes)
+            .ok_or(ContainerError::InvalidLength())
+    }
+}
+
+/// Options controlling how [`encrypt_with_options`] builds a Talos container.
+#[derive(Debug, Clone)]
+pub struct EncryptOptions {
+    /// The strategy used to fill the final block of the plaintext. The plaintext length is
+    /// always stored in the [`Header`], so every strategy can be removed by [`decrypt`].
+    pub padding: PaddingStrategy,
+    /// Whether to append an HMAC-SHA-256 tag over the header and ciphertext. See [`mac`].
+    pub authenticate: bool,
+    /// The shape of the encryption blocks. Must have built-in initialization matrices; see
+    /// [`default_init_matrices`](crate::encrypt::default_init_matrices). PKCS#7 padding is
+    /// rejected for blocks longer than [`MAX_PKCS7_BLOCK_BYTES`].
+    pub geometry: BlockGeometry,
+    /// The protocol variant to encrypt with. It is recorded in the [`Header`], so [`decrypt`]
+    /// needs no matching option.
+    pub variant: ProtocolVariant,
+    /// The parameters with which the key was derived from a passphrase, if it was. They are
+    /// recorded in the [`Header`] so that the same key can be derived for decryption.
+    pub kdf: Option<KdfParams>,
+    /// Whether to record a key check value in the [`Header`], so that decryption with the wrong
+    /// key fails with [`ContainerError::WrongKey`] before anything is decrypted.
+    pub key_check: bool,
+    /// The cipher parameters to encrypt with, or `None` for those given by
+    /// [`CipherParams::for_geometry`]. The [`Header`] can only record those parameters, so any
+    /// others are rejected with [`ContainerError::UnsupportedParameters`].
+    pub params: Option<CipherParams>,
+}
+
+impl Default for EncryptOptions {
+    fn default() -> Self {
+        Self {
+            padding: PaddingStrategy::Pkcs7,
+            authenticate: true,
+            geometry: GEOMETRY_256,
+            variant: ProtocolVariant::Rfc1,
+            kdf: None,
+            key_check: true,
+            params: None,
+        }
+    }
+}
+
+/// Options controlling how [`decrypt_with_options`] reads a Talos container.
+#[derive(Debug, Clone)]
+pub struct DecryptOptions {
+    /// Whether to reject containers which do not carry an authentication tag. Disabling this
+    /// allows an attacker to strip the tag from a container and modify it undetected.
+    pub require_authentication: bool,
+}
+
+impl Default for DecryptOptions {
+    fn default() -> Self {
+        Self {
+            require_authentication: true,
+        }
+    }
+}
+
+/// Encrypts `message` with `seed` and wraps the ciphertext in a Talos container.
+///
+/// Uses the default [`EncryptOptions`], padding the plaintext with
+/// [`pad_message_256`](crate::encrypt::pad_message_256) and authenticating the container.
+///
+/// # Arguments
+/// * `message` - the plaintext to be encrypted as a vector of bytes
+/// * `seed` - the key to encrypt `message` with
+///
+/// # Returns
+/// The serialized container, consisting of a [`Header`] followed by the ciphertext blocks and
+/// authentication tag.
+#[must_use]
+pub fn encrypt(message: Vec<u8>, seed: u32) -> Vec<u8> {
+    encrypt_with_options(
+        message,
+        &TalosKey::Bits32(seed),
+        &EncryptOptions::default(),
+        &mut key::os_rng(),
+    )
+    .unwrap()
+}
+
+/// Encrypts `message` with `key` according to `options` and wraps the ciphertext in a Talos
+/// container.
+///
+/// The width of `key` is recorded in the [`Header`].
+///
+/// # Arguments
+/// * `message` - the plaintext to be encrypted as a vector of bytes
+/// * `key` - the key to encrypt `message` with
+/// * `options` - options controlling how the container is built
+/// * `rng` - the random number generator used for the nonce and padding
+///
+/// # Returns
+/// The serialized container, consisting of a [`Header`] followed by the ciphertext blocks and,
+/// if requested, an authentication tag, or [`ContainerError::UnsupportedParameters`] if there
+/// are no built-in initialization matrices for the requested block geometry. PKCS#7 padding of
+/// blocks too long for it fails with [`ContainerError::InvalidPadding`].
+pub fn encrypt_with_options<R: KeyRng + ?Sized>(
+    message: Vec<u8>,
+    key: &TalosKey,
+    options: &EncryptOptions,
+    rng: &mut R,
+) -> Result<Vec<u8>, ContainerError> {
+    encrypt_stream(
+        message.as_slice(),
+        Some(message.len() as u64),
+        Vec::new(),
+        key,
+        options,
+        rng,
+    )
+}
+
+/// Encrypts the plaintext read from `input` with `key` according to `options`, writing a Talos
+/// container to `output`.
+///
+/// The plaintext is encrypted block by block with a [`TalosEncryptor`], so memory use does not
+/// depend on its length. If `plaintext_len` is given, the container is the same as that built by
+/// [`encrypt_with_options`] from the same plaintext and random number generator. Otherwise,
+/// `input` is read until it is exhausted, and the plaintext length is written after the
+/// ciphertext with [`FLAG_LENGTH_TRAILER`].
+///
+/// # Arguments
+/// * `input` - the reader providing the plaintext. Must provide exactly `plaintext_len` bytes if
+///   `plaintext_len` is given
+/// * `plaintext_len` - the length of the plaintext in bytes, recorded in the [`Header`], or
+///   `None` if it is not known in advance
+/// * `output` - the writer to write the container to
+/// * `key` - the key to encrypt the plaintext with
+/// * `options` - options controlling how the container is built
+/// * `rng` - the random number generator used for the nonce and padding
+///
+/// # Returns
+/// `output` once the container has been written to it, or a [`ContainerError`] if `input` does
+/// not provide `plaintext_len` bytes, reading or writing fails, there are no built-in
+/// initialization matrices for the requested block geometry, or PKCS#7 padding is requested for
+/// blocks longer than [`MAX_PKCS7_BLOCK_BYTES`].
+pub fn encrypt_stream<R: Read, W: Write, G: KeyRng + ?Sized>(
+    mut input: R,
+    plaintext_len: Option<u64>,
+    output: W,
+    key: &TalosKey,
+    options: &EncryptOptions,
+    rng: &mut G,
+) -> Result<W, ContainerError> {
+    let geometry = &options.geometry;
+    let params = container_params(geometry, key, options.variant)?;
+    if options
+        .params
+        .as_ref()
+        .is_some_and(|custom| *custom != params)
+    {
+        return Err(ContainerError::UnsupportedParameters());
+    }
+    let padding = &options.padding;
+    if *padding == PaddingStrategy::Pkcs7 && geometry.block_bytes() > MAX_PKCS7_BLOCK_BYTES {
+        return Err(ContainerError::InvalidPadding(PaddingError::BlockTooLarge()));
+    }
+
+    let mut header = Header::new(options.variant, plaintext_len.unwrap_or(0));
+    header.set_geometry(geometry);
+    if plaintext_len.is_none() {
+        header.flags |= FLAG_LENGTH_TRAILER;
+    }
+    if *padding == PaddingStrategy::Pkcs7 {
+        header.flags |= FLAG_PKCS7;
+    }
+    if options.authenticate {
+        header.flags |= FLAG_AUTHENTICATED;
+    }
+    header.set_key_width(key.width());
+    if let Some(kdf) = &options.kdf {
+        header.set_kdf(kdf.clone());
+    }
+    let nonce = key::random_bytes(rng);
+    header.set_nonce(nonce);
+    if options.key_check {
+        header.set_key_check(key);
+    }
+
+    let (s_automaton, t_automaton) =
+        encrypt::get_shift_transpose_automata_with_params(&params, geometry, key, Some(&nonce))
+            .map_err(|_| ContainerError::UnsupportedParameters())?;
+
+    let mut writer = MacWriter {
+        inner: output,
+        mac: options
+            .authenticate
+            .then(|| HmacSha256::new(&*key::derive_subkey(key, key::MAC_KEY_LABEL))),
+    };
+    writer
+        .write_all(&header.to_bytes())
+        .map_err(ContainerError::Io)?;
+
+    let mut encryptor =
+        TalosEncryptor::new(writer, geometry, options.variant, s_automaton, t_automaton);
+    let n_read = match plaintext_len {
+        Some(plaintext_len) => {
+            let n_read = io::copy(&mut (&mut input).take(plaintext_len), &mut encryptor)
+                .map_err(ContainerError::Io)?;
+            if n_read != plaintext_len || !at_end(&mut input)? {
+                return Err(ContainerError::InvalidLength());
+            }
+            n_read
+        }
+        None => io::copy(&mut input, &mut encryptor).map_err(ContainerError::Io)?,
+    };
+
+    let mut writer = encryptor
+        .finish_padded(padding, rng)
+        .map_err(ContainerError::Io)?;
+    if plaintext_len.is_none() {
+        writer
+            .write_all(&n_read.to_le_bytes())
+            .map_err(ContainerError::Io)?;
+    }
+    let MacWriter { mut inner, mac } = writer;
+    if let Some(mac) = mac {
+        inner
+            .write_all(&mac.finalize())
+            .map_err(ContainerError::Io)?;
+    }
+    inner.flush().map_err(ContainerError::Io)?;
+
+    Ok(inner)
+}
+
+/// Decrypts the Talos container `container` with `seed`.
+///
+/// Uses the default [`DecryptOptions`], rejecting containers without an authentication tag.
+///
+/// # Arguments
+/// * `container` - the serialized container, as produced by [`encrypt`]
+/// * `seed` - the key to decrypt the container with
+///
+/// # Returns
+/// The decrypted plaintext, or a [`ContainerError`] if `container` could not be read.
+pub fn decrypt(container: Vec<u8>, seed: u32) -> Result<Vec<u8>, ContainerError> {
+    decrypt_with_options(
+        container,
+        &TalosKey::Bits32(seed),
+        &DecryptOptions::default(),
+    )
+}
+
+/// Decrypts the Talos container `container` with `key` according to `options`.
+///
+/// If the container carries a key check value, a mismatching key is reported as
+/// [`ContainerError::WrongKey`] before the ciphertext is read. If the container carries an
+/// authentication tag, the tag is verified before any ciphertext is decrypted. A mismatching tag,
+/// which results from both tampering and decrypting with the wrong key, is reported as
+/// [`ContainerError::AuthenticationFailed`].
+///
+/// # Arguments
+/// * `container` - the serialized container, as produced by [`encrypt_with_options`]
+/// * `key` - the key to decrypt the container with. Must have the width recorded in the
+///   [`Header`]
+/// * `options` - options controlling how the container is read
+///
+/// # Returns
+/// The decrypted plaintext, or a [`ContainerError`] if `container` could not be read.
+pub fn decrypt_with_options(
+    container: Vec<u8>,
+    key: &TalosKey,
+    options: &DecryptOptions,
+) -> Result<Vec<u8>, ContainerError> {
+    let header = Header::from_bytes(&container)?;
+    if header.flags & FLAG_LENGTH_TRAILER != 0 {
+        // the ciphertext length is only known once the whole container has been read
+        return decrypt_stream(
+            &header,
+            &container[header.size()..],
+            Vec::new(),
+            key,
+            options,
+        );
+    }
+    let geometry = check_header(&header, key, options)?;
+
+    let authenticated = header.flags & FLAG_AUTHENTICATED != 0;
+    let tag_len = if authenticated { mac::TAG_SIZE } else { 0 };
+    let expected_len = header
+        .ciphertext_len()?
+        .checked_add((header.size() + tag_len) as u64)
+        .ok_or(ContainerError::InvalidLength())?;
+    if (container.len() as u64) < expected_len {
+        return Err(ContainerError::Truncated());
+    }
+    if container.len() as u64 != expected_len {
+        return Err(ContainerError::InvalidLength());
+    }
+
+    let (body, tag) = container.split_at(container.len() - tag_len);
+    if authenticated {
+        let mac_key = key::derive_subkey(key, key::MAC_KEY_LABEL);
+        if !mac::tags_equal(&mac::hmac_sha256(&*mac_key, body), tag) {
+            return Err(ContainerError::AuthenticationFailed());
+        }
+    }
+    let ciphertext = body[header.size()..].to_vec();
+
+    let params = container_params(&geometry, key, header.variant)?;
+    let (mut s_automaton, mut t_automaton) = encrypt::get_shift_transpose_automata_with_params(
+        &params,
+        &geometry,
+        key,
+        header.nonce.as_ref(),
+    )
+    .map_err(|_| ContainerError::UnsupportedParameters())?;
+    if header.flags & FLAG_PKCS7 == 0 {
+        let plaintext = encrypt::decrypt_message(
+            ciphertext,
+            &geometry,
+            header.variant,
+            &mut s_automaton,
+            &mut t_automaton,
+        );
+        return encrypt::strip_padding(plaintext, header.plaintext_len as usize, &geometry)
+            .map_err(ContainerError::InvalidPadding);
+    }
+
+    let plaintext = encrypt::decrypt_padded_message(
+        ciphertext,
+        &geometry,
+        header.variant,
+        &mut s_automaton,
+        &mut t_automaton,
+    )
+    .map_err(ContainerError::InvalidPadding)?;
+    if plaintext.len() as u64 != header.plaintext_len {
+        return Err(ContainerError::InvalidLength());
+    }
+
+    Ok(plaintext)
+}
+
+/// Decrypts the ciphertext of a Talos container read from `input` with `key` according to
+/// `options`, writing the plaintext to `output`.
+///
+/// The ciphertext is decrypted block by block with a [`TalosDecryptor`], so memory use does not
+/// depend on its length. Unlike [`decrypt_with_options`], plaintext is written to `output` before
+/// the authentication tag at the end of the container has been verified. If an error is
+/// returned, everything written to `output` must be discarded. A key which does not match the key
+/// check value in `header` is rejected with [`ContainerError::WrongKey`] before anything is
+/// written.
+///
+/// # Arguments
+/// * `header` - the header of the container, as read by [`Header::read`]
+/// * `input` - the reader providing the rest of the container, starting after its header
+/// * `output` - the writer to write the plaintext to
+/// * `key` - the key to decrypt the container with. Must have the width recorded in `header`
+/// * `options` - options controlling how the container is read
+///
+/// # Returns
+/// `output` once the plaintext has been written to it, or a [`ContainerError`] if the container
+/// could not be read.
+pub fn decrypt_stream<R: Read, W: Write>(
+    header: &Header,
+    mut input: R,
+    mut output: W,
+    key: &TalosKey,
+    options: &DecryptOptions,
+) -> Result<W, ContainerError> {
+    let geometry = check_header(header, key, options)?;
+    let authenticated = header.flags & FLAG_AUTHENTICATED != 0;
+
+    let mut mac = authenticated.then(|| {
+        let mut mac = HmacSha256::new(&*key::derive_subkey(key, key::MAC_KEY_LABEL));
+        mac.update(&header.to_bytes());
+        mac
+    });
+    let params = container_params(&geometry, key, header.variant)?;
+    let (s_automaton, t_automaton) = encrypt::get_shift_transpose_automata_with_params(
+        &params,
+        &geometry,
+        key,
+        header.nonce.as_ref(),
+    )
+    .map_err(|_| ContainerError::UnsupportedParameters())?;
+    if header.flags & FLAG_LENGTH_TRAILER != 0 {
+        return decrypt_length_trailer(header, input, output, mac, s_automaton, t_automaton);
+    }
+    let ciphertext_len = header.ciphertext_len()?;
+
+    let ciphertext = MacReader {
+        inner: (&mut input).take(ciphertext_len),
+        mac: mac.as_mut(),
+    };
+    let mut decryptor = TalosDecryptor::new(
+        ciphertext,
+        &geometry,
+        header.variant,
+        s_automaton,
+        t_automaton,
+    );
+    let n_plaintext = io::copy(
+        &mut (&mut decryptor).take(header.plaintext_len),
+        &mut output,
+    )
+    .map_err(stream_error)?;
+    let mut padding = Vec::new();
+    decryptor.read_to_end(&mut padding).map_err(stream_error)?;
+    drop(decryptor);
+    if n_plaintext.checked_add(padding.len() as u64) != Some(ciphertext_len) {
+        return Err(ContainerError::Truncated());
+    }
+
+    if let Some(mac) = mac {
+        let mut tag = [0_u8; mac::TAG_SIZE];
+        input.read_exact(&mut tag).map_err(stream_error)?;
+        if !at_end(&mut input)? {
+            return Err(ContainerError::InvalidLength());
+        }
+        if !mac::tags_equal(&mac.finalize(), &tag) {
+            return Err(ContainerError::AuthenticationFailed());
+        }
+    } else if !at_end(&mut input)? {
+        return Err(ContainerError::InvalidLength());
+    }
+
+    let n_padding = padding.len();
+    if header.flags & FLAG_PKCS7 != 0
+        && (n_padding == 0 || padding.iter().any(|byte| *byte as usize != n_padding))
+    {
+        return Err(ContainerError::InvalidPadding(
+            PaddingError::MalformedPadding(),
+        ));
+    }
+    output.flush().map_err(ContainerError::Io)?;
+
+    Ok(output)
+}
+
+/// Verifies the authentication tag of a Talos container without decrypting it.
+///
+/// This allows callers to check a container they can read twice before releasing any plaintext
+/// from [`decrypt_stream`], which verifies the tag again.
+///
+/// # Arguments
+/// * `header` - the header of the container, as read by [`Header::read`]
+/// * `input` - the remainder of the container following the header
+/// * `key` - the key to verify the container with
+///
+/// # Returns
+/// `()` if the tag is valid, [`ContainerError::Unauthenticated`] if the container has no tag,
+/// [`ContainerError::AuthenticationFailed`] if the tag is invalid, or another
+/// [`ContainerError`] if the container could not be read.
+pub fn verify_stream<R: Read>(
+    header: &Header,
+    input: R,
+    key: &TalosKey,
+) -> Result<(), ContainerError> {
+    if header.flags & FLAG_AUTHENTICATED == 0 {
+        return Err(ContainerError::Unauthenticated());
+    }
+
+    let mut mac = HmacSha256::new(&*key::derive_subkey(key, key::MAC_KEY_LABEL));
+    mac.update(&header.to_bytes());
+    // everything between the header and the tag is authenticated
+    let mut authenticated = MacReader {
+        inner: TailReader::new(input, mac::TAG_SIZE),
+        mac: Some(&mut mac),
+    };
+    io::copy(&mut authenticated, &mut io::sink()).map_err(stream_error)?;
+    let tag = authenticated.inner.into_tail();
+    if tag.len() < mac::TAG_SIZE {
+        return Err(ContainerError::Truncated());
+    }
+    if !mac::tags_equal(&mac.finalize(), &tag) {
+        return Err(ContainerError::AuthenticationFailed());
+    }
+
+    Ok(())
+}
+
+/// Completes [`decrypt_stream`] for a container with [`FLAG_LENGTH_TRAILER`] set.
+///
+/// The most recently decrypted block is held back until the plaintext length has been read from
+/// the trailer, so that padding is never written to `output`.
+fn decrypt_length_trailer<R: Read, W: Write>(
+    header: &Header,
+    input: R,
+    mut output: W,
+    mut mac: Option<HmacSha256>,
+    s_automaton: TalosAutomaton,
+    t_automaton: TalosAutomaton,
+) -> Result<W, ContainerError> {
+    let geometry = header.geometry().unwrap();
+    let tag_len = if mac.is_some() { mac::TAG_SIZE } else { 0 };
+    let ciphertext = MacReader {
+        inner: TailReader::new(input, LENGTH_TRAILER_SIZE + tag_len),
+        mac: mac.as_mut(),
+    };
+    let mut decryptor = TalosDecryptor::new(
+        ciphertext,
+        &geometry,
+        header.variant,
+        s_automaton,
+        t_automaton,
+    );
+    let block_bytes = geometry.block_bytes();
+    let mut n_ciphertext = 0_u64;
+    let mut last_block = Vec::new();
+    let mut block = vec![0_u8; block_bytes];
+    loop {
+        let n_read = stream::read_full(&mut decryptor, &mut block).map_err(stream_error)?;
+        if n_read == 0 {
+            break;
+        }
+        output.write_all(&last_block).map_err(ContainerError::Io)?;
+        last_block = block[..n_read].to_vec();
+        n_ciphertext += n_read as u64;
+    }
+
+    let trailer = decryptor.into_inner().inner.into_tail();
+    if trailer.len() < LENGTH_TRAILER_SIZE + tag_len {
+        return Err(ContainerError::Truncated());
+    }
+    let (length, tag) = trailer.split_at(LENGTH_TRAILER_SIZE);
+    if let Some(mut mac) = mac {
+        mac.update(length);
+        if !mac::tags_equal(&mac.finalize(), tag) {
+            return Err(ContainerError::AuthenticationFailed());
+        }
+    }
+
+    let mut expected = header.clone();
+    expected.plaintext_len = u64::from_le_bytes(length.try_into().unwrap());
+    if n_ciphertext != expected.ciphertext_len()? {
+        return Err(ContainerError::InvalidLength());
+    }
+    let n_last = (expected.plaintext_len - (n_ciphertext - last_block.len() as u64)) as usize;
+    let (plaintext, padding) = last_block.split_at(n_last);
+    let n_padding = padding.len();
+    if header.flags & FLAG_PKCS7 != 0
+        && (n_padding == 0 || padding.iter().any(|byte| *byte as usize != n_padding))
+    {
+        return Err(ContainerError::InvalidPadding(
+            PaddingError::MalformedPadding(),
+        ));
+    }
+    output.write_all(plaintext).map_err(ContainerError::Io)?;
+    output.flush().map_err(ContainerError::Io)?;
+
+    Ok(output)
+}
+
+/// Returns the cipher parameters of a container, which are selected by the block geometry, key
+/// width and protocol variant recorded in its [`Header`].
+///
+/// # Returns
+/// The parameters of [`CipherParams::for_geometry`], or
+/// [`ContainerError::UnsupportedParameters`] if there are no built-in matrices for `geometry`.
+fn container_params(
+    geometry: &BlockGeometry,
+    key: &TalosKey,
+    variant: ProtocolVariant,
+) -> Result<CipherParams, ContainerError> {
+    CipherParams::for_geometry(geometry, key.width(), variant)
+        .map_err(|_| ContainerError::UnsupportedParameters())
+}
+
+/// Checks that a container with header `header` can be decrypted with `key` according to
+/// `options`.
+///
+/// # Returns
+/// The block geometry of the container, or a [`ContainerError`] if it cannot be decrypted.
+fn check_header(
+    header: &Header,
+    key: &TalosKey,
+    options: &DecryptOptions,
+) -> Result<BlockGeometry, ContainerError> {
+    let geometry = match header.geometry() {
+        Some(geometry) if header.iters_per_block == N_ITERS_PER_BLOCK => geometry,
+        _ => {
+            return Err(ContainerError::UnsupportedParameters());
+        }
+    };
+    if encrypt::default_init_matrices(&geometry, header.key_width).is_none() {
+        return Err(ContainerError::UnsupportedParameters());
+    }
+    if header.flags & FLAG_PKCS7 != 0 && geometry.block_bytes() > MAX_PKCS7_BLOCK_BYTES {
+        return Err(ContainerError::UnsupportedParameters());
+    }
+    header.check_key(key)?;
+    if options.require_authentication && header.flags & FLAG_AUTHENTICATED == 0 {
+        return Err(ContainerError::Unauthenticated());
+    }
+    if header.flags & FLAG_LENGTH_TRAILER != 0 && header.plaintext_len != 0 {
+        return Err(ContainerError::InvalidLength());
+    }
+
+    Ok(geometry)
+}
+
+/// Returns the size of a serialized [`Header`] with flags `flags`.
+fn header_size(flags: u16) -> usize {
+    let mut result = HEADER_SIZE;
+    if flags & FLAG_NONCE != 0 {
+        result += NONCE_SIZE;
+    }
+    if flags & FLAG_KEY_WIDTH != 0 {
+        result += 1;
+    }
+    if flags & FLAG_KDF != 0 {
+        result += SALT_SIZE + 4;
+    }
+    if flags & FLAG_KEY_CHECK != 0 {
+        result += KEY_CHECK_SIZE;
+    }
+
+    result
+}
+
+/// Converts an error reading a container into a [`ContainerError`]. A container ending too early
+/// is reported as [`ContainerError::Truncated`].
+fn stream_error(err: io::Error) -> ContainerError {
+    if err.kind() == io::ErrorKind::UnexpectedEof {
+        ContainerError::Truncated()
+    } else {
+        ContainerError::Io(err)
+    }
+}
+
+/// Returns whether `input` is exhausted.
+fn at_end<R: Read + ?Sized>(input: &mut R) -> Result<bool, ContainerError> {
+    let n_read = stream::read_full(input, &mut [0_u8; 1]).map_err(ContainerError::Io)?;
+    Ok(n_read == 0)
+}
+
+/// Writer passing everything written to it to `inner`, and to `mac` if present.
+struct MacWriter<W: Write> {
+    inner: W,
+    mac: Option<HmacSha256>,
+}
+
+impl<W: Write> Write for MacWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n_bytes = self.inner.write(buf)?;
+        if let Some(mac) = &mut self.mac {
+            mac.update(&buf[..n_bytes]);
+        }
+        Ok(n_bytes)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Reader passing on everything read from `inner` except its final `keep` bytes, which are
+/// returned by [`TailReader::into_tail`] once `inner` is exhausted.
+struct TailReader<R: Read> {
+    inner: R,
+    keep: usize,
+    buffer: Vec<u8>,
+    exhausted: bool,
+}
+
+impl<R: Read> TailReader<R> {
+    fn new(inner: R, keep: usize) -> Self {
+        Self {
+            inner,
+            keep,
+            buffer: Vec::new(),
+            exhausted: false,
+        }
+    }
+
+    /// Returns the final bytes of `inner`, which are fewer than `keep` if `inner` was shorter.
+    fn into_tail(self) -> Vec<u8> {
+        self.buffer
+    }
+}
+
+impl<R: Read> Read for TailReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            if self.buffer.len() > self.keep {
+                let n_bytes = buf.len().min(self.buffer.len() - self.keep);
+                buf[..n_bytes].copy_from_slice(&self.buffer[..n_bytes]);
+                self.buffer.drain(..n_bytes);
+                return Ok(n_bytes);
+            }
+            if self.exhausted {
+                return Ok(0);
+            }
+
+            let mut chunk = [0_u8; 4096];
+            match self.inner.read(&mut chunk)? {
+                0 => self.exhausted = true,
+                n_bytes => self.buffer.extend_from_slice(&chunk[..n_bytes]),
+            }
+        }
+    }
+}
+
+/// Reader passing everything read from `inner` to `mac` if present.
+struct MacReader<'a, R: Read> {
+    inner: R,
+    mac: Option<&'a mut HmacSha256>,
+}
+
+impl<R: Read> Read for MacReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n_bytes = self.inner.read(buf)?;
+        if let Some(mac) = &mut self.mac {
+            mac.update(&buf[..n_bytes]);
+        }
+        Ok(n_bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::container::{ContainerError, Header, FORMAT_VERSION, HEADER_SIZE, KEY_CHECK_SIZE};
+    use crate::encrypt::{ProtocolVariant, NONCE_SIZE};
+    use crate::key::{KeyWidth, TalosKey};
+
+    #[test]
+    fn test_header_round_trip() {
+        let header = Header::new(ProtocolVariant::Rfc1, 425);
+        let bytes = header.to_bytes();
+
+        assert_eq!(bytes.len(), HEADER_SIZE);
+        assert_eq!(Header::from_bytes(&bytes).unwrap(), header);
+    }
+
+    #[test]
+    fn test_header_nonce_round_trip() {
+        let mut header = Header::new(ProtocolVariant::Rfc1, 425);
+        header.set_nonce([7; NONCE_SIZE]);
+        let bytes = header.to_bytes();
+
+        assert_eq!(bytes.len(), HEADER_SIZE + NONCE_SIZE);
+        assert_eq!(header.size(), bytes.len());
+        assert_eq!(Header::from_bytes(&bytes).unwrap(), header);
+        assert!(matches!(
+            Header::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(ContainerError::Truncated())
+        ));
+    }
+
+    #[test]
+    fn test_header_key_width_round_trip() {
+        let mut header = Header::new(ProtocolVariant::Rfc1, 425);
+        header.set_nonce([7; NONCE_SIZE]);
+        header.set_key_width(KeyWidth::Bits256);
+        let bytes = header.to_bytes();
+
+        assert_eq!(bytes.len(), HEADER_SIZE + NONCE_SIZE + 1);
+        assert_eq!(Header::from_bytes(&bytes).unwrap(), header);
+
+        header.set_key_width(KeyWidth::Bits32);
+        assert_eq!(header.size(), HEADER_SIZE + NONCE_SIZE);
+        assert_eq!(Header::from_bytes(&header.to_bytes()).unwrap(), header);
+    }
+
+    #[test]
+    fn test_header_key_check_round_trip() {
+        let mut header = Header::new(ProtocolVariant::Rfc1, 425);
+        header.set_nonce([7; NONCE_SIZE]);
+        header.set_key_check(&TalosKey::Bits32(42));
+        let bytes = header.to_bytes();
+
+        assert_eq!(bytes.len(), HEADER_SIZE + NONCE_SIZE + KEY_CHECK_SIZE);
+        assert_eq!(Header::from_bytes(&bytes).unwrap(), header);
+        assert!(matches!(
+            Header::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(ContainerError::Truncated())
+        ));
+    }
+
+    #[test]
+    fn test_header_bad_magic() {
+        let mut bytes = Header::new(ProtocolVariant::Rfc1, 0).to_bytes();
+        bytes[0] = b'X';
+
+        assert!(matches!(
+            Header::from_bytes(&bytes),
+            Err(ContainerError::BadMagic())
+        ));
+    }
+
+    #[test]
+    fn test_header_unsupported_version() {
+        let mut bytes = Header::new(ProtocolVariant::Rfc1, 0).to_bytes();
+        bytes[5] = FORMAT_VERSION + 1;
+
+        assert!(matches!(
+            Header::from_bytes(&bytes),
+            Err(ContainerError::UnsupportedVersion(v)) if v == FORMAT_VERSION + 1
+        ));
+        // an unknown version is reported even if the rest of the header is missing
+        assert!(matches!(
+            Header::from_bytes(&bytes[..6]),
+            Err(ContainerError::UnsupportedVersion(_))
+        ));
+    }
+
+    #[test]
+    fn test_header_truncated() {
+        let bytes = Header::new(ProtocolVariant::Rfc1, 0).to_bytes();
+
+        assert!(matches!(
+            Header::from_bytes(&bytes[..HEADER_SIZE - 1]),
+            Err(ContainerError::Truncated())
+        ));
+        assert!(matches!(
+            Header::from_bytes(&bytes[..3]),
+            Err(ContainerError::Truncated())
+        ));
+    }
+
+    #[test]
+    fn test_header_unknown_flags() {
+        let mut header = Header::new(ProtocolVariant::Rfc1, 0);
+        header.flags = 0x8000;
+
+        assert!(matches!(
+            Header::from_bytes(&header.to_bytes()),
+            Err(ContainerError::UnknownFlags(0x8000))
+        ));
+    }
+}
@@ -1,7 +1,14 @@
 // 2025 Steven Chiacchira
-use crate::automata::{AutomatonRule, ToroidalAutomaton};
+use crate::automata::{AutomatonRule, Neighborhood, ToroidalAutomaton};
+use crate::key::{KeyWidth, TalosKey};
 use crate::matrix::{ToroidalBinaryMatrix, ToroidalBitMatrix, ToroidalMatrixIndex};
-use crate::parse;
+use crate::parse::{self, TableReadError};
+use crate::seeding::{self, SeedingSchedule};
+use rand::seq::IndexedRandom;
+use rand::Rng;
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
 
 /// Number of rows in a matrix for the Talos encryption protocol.
 pub const N_ROWS: usize = 16;
@@ -9,6 +16,8 @@ pub const N_ROWS: usize = 16;
 pub const N_COLS: usize = 16;
 /// Number of elements in an encryption block for the Talos encryption protocol.
 pub const BLOCK_SIZE: usize = N_ROWS * N_COLS;
+/// Number of bytes in an encryption block for the Talos encryption protocol.
+pub const BLOCK_BYTES: usize = BLOCK_SIZE / u8::BITS as usize;
 
 /// Initialization string for Transpose Matrix. See RFC-0 section 2.2.1 for details.
 pub const T_INIT_MATRIX: &str = include_str!(concat!(
@@ -20,14 +29,443 @@ pub const S_INIT_MATRIX: &str = include_str!(concat!(
     env!("CARGO_MANIFEST_DIR"),
     "/data/init_matrix/S_init_matrix.txt"
 ));
-/// Automaton rule used in the Talos encryption protocol. See RFC-0 section 2.2.2 for details.
+/// Initialization string for Transpose Matrix with keys wider than 32 bits. Each variable cell of
+/// [`T_INIT_MATRIX`] references a distinct key bit. See [`parse::key_digit`].
+pub const T_INIT_MATRIX_128: &str = include_str!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/data/init_matrix/T_init_matrix_128.txt"
+));
+/// Initialization string for Shift Matrix with keys wider than 32 bits. Each variable cell of
+/// [`S_INIT_MATRIX`] references a distinct key bit. See [`parse::key_digit`].
+pub const S_INIT_MATRIX_128: &str = include_str!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/data/init_matrix/S_init_matrix_128.txt"
+));
+/// Initialization string for Transpose Matrix with 32x32 blocks. Each of 256 key bits is
+/// referenced by 2 cells. See [`parse::key_digit`].
+pub const T_INIT_MATRIX_32X32: &str = include_str!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/data/init_matrix/T_init_matrix_32x32.txt"
+));
+/// Initialization string for Shift Matrix with 32x32 blocks. Each of 256 key bits is referenced
+/// by 2 cells. See [`parse::key_digit`].
+pub const S_INIT_MATRIX_32X32: &str = include_str!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/data/init_matrix/S_init_matrix_32x32.txt"
+));
+/// Initialization string for Transpose Matrix with 64x64 blocks. Each of 256 key bits is
+/// referenced by 8 cells. See [`parse::key_digit`].
+pub const T_INIT_MATRIX_64X64: &str = include_str!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/data/init_matrix/T_init_matrix_64x64.txt"
+));
+/// Initialization string for Shift Matrix with 64x64 blocks. Each of 256 key bits is referenced
+/// by 8 cells. See [`parse::key_digit`].
+pub const S_INIT_MATRIX_64X64: &str = include_str!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/data/init_matrix/S_init_matrix_64x64.txt"
+));
+/// Automaton rule used in the Talos encryption protocol, `B23456/S234` in B/S notation. See RFC-0
+/// section 2.2.2 for details.
 pub const AUTOMATA_RULE: AutomatonRule = AutomatonRule {
-    born: [false, false, true, true, true, true, true, false, false],
-    dies: [true, true, false, false, false, true, true, true, true],
+    born: Cow::Borrowed(&[false, false, true, true, true, true, true, false, false]),
+    dies: Cow::Borrowed(&[true, true, false, false, false, true, true, true, true]),
+    neighborhood: Neighborhood::Moore,
 };
 
 /// Number of iterations to perform for each encryption block.
-const N_ITERS_PER_BLOCK: u32 = 11;
+pub const N_ITERS_PER_BLOCK: u32 = 11;
+/// Number of bytes in a nonce. See [`get_shift_transpose_automata_with_params`].
+pub const NONCE_SIZE: usize = 16;
+
+/// Largest number of bytes in a block which can be padded with PKCS#7 padding. See
+/// [`pad_message`].
+pub const MAX_PKCS7_BLOCK_BYTES: usize = u8::MAX as usize;
+
+/// The 16x16 block geometry of RFC-0. See [`BlockGeometry`].
+pub const GEOMETRY_256: BlockGeometry = BlockGeometry { dim: N_ROWS };
+
+/// Error occurring while preparing the Talos cipher for a [`BlockGeometry`].
+#[derive(Debug)]
+pub enum GeometryError {
+    /// Blocks must be square, with a side length which is a power of two between 4 and 32768.
+    UnsupportedDimension(usize),
+    /// No built-in initialization matrices exist for the block geometry. Initialization matrices
+    /// can be supplied in [`CipherParams`] instead.
+    NoDefaultMatrices(),
+    /// An initialization matrix contains an invalid character.
+    InvalidMatrix(TableReadError),
+    /// The shape of an initialization matrix does not match the block geometry.
+    MatrixShapeMismatch(),
+    /// An initialization matrix contains no cells referencing key bits.
+    NoKeyCells(),
+}
+
+impl Error for GeometryError {}
+impl fmt::Display for GeometryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::UnsupportedDimension(dim) => {
+                write!(f, "Unsupported block dimension: {}", dim)
+            }
+            Self::NoDefaultMatrices() => {
+                write!(f, "No initialization matrices for block geometry")
+            }
+            Self::InvalidMatrix(err) => {
+                write!(f, "Invalid initialization matrix: {}", err)
+            }
+            Self::MatrixShapeMismatch() => {
+                write!(
+                    f,
+                    "Initialization matrix shape does not match block geometry"
+                )
+            }
+            Self::NoKeyCells() => {
+                write!(f, "Initialization matrix references no key bits")
+            }
+        }
+    }
+}
+
+/// The shape of the square blocks encrypted by the Talos cipher.
+///
+/// RFC-0 specifies 16x16 blocks (see [`GEOMETRY_256`]), while its section on larger automata
+/// sizes proposes bigger states. For a block with side length `dim`, the scrambling algorithm `V`
+/// of RFC-0 section 2.2.3 builds each row and column index from `log2(dim)` key bits, spaced
+/// [`BlockGeometry::index_stride`] bits apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockGeometry {
+    /// The number of rows and columns in a block.
+    dim: usize,
+}
+
+impl BlockGeometry {
+    /// Creates a new `BlockGeometry` for `dim` x `dim` blocks.
+    ///
+    /// # Arguments
+    /// * `dim` - the number of rows and columns in a block. Must be a power of two between 4 and
+    ///   32768
+    ///
+    /// # Returns
+    /// The created `BlockGeometry`, or [`GeometryError::UnsupportedDimension`] if `dim` is
+    /// invalid.
+    pub fn new(dim: usize) -> Result<Self, GeometryError> {
+        if !dim.is_power_of_two() || !(4..=1 << 15).contains(&dim) {
+            return Err(GeometryError::UnsupportedDimension(dim));
+        }
+
+        Ok(Self { dim })
+    }
+
+    /// Returns the number of rows in a block.
+    ///
+    /// # Returns
+    /// The number of rows in a block.
+    #[must_use]
+    pub fn n_rows(&self) -> usize {
+        self.dim
+    }
+
+    /// Returns the number of columns in a block.
+    ///
+    /// # Returns
+    /// The number of columns in a block.
+    #[must_use]
+    pub fn n_cols(&self) -> usize {
+        self.dim
+    }
+
+    /// Returns the number of bits in a block.
+    ///
+    /// # Returns
+    /// The number of bits in a block.
+    #[must_use]
+    pub fn block_size(&self) -> usize {
+        self.dim * self.dim
+    }
+
+    /// Returns the number of bytes in a block.
+    ///
+    /// # Returns
+    /// The number of bytes in a block.
+    #[must_use]
+    pub fn block_bytes(&self) -> usize {
+        self.block_size() / u8::BITS as usize
+    }
+
+    /// Returns the number of key bits read for each row or column index of the scrambling
+    /// algorithm, which is `log2` of the block dimension.
+    ///
+    /// # Returns
+    /// The number of bits in a row or column index.
+    #[must_use]
+    pub fn index_bits(&self) -> usize {
+        self.dim.trailing_zeros() as usize
+    }
+
+    /// Returns the distance between the key bits read for a row or column index of the
+    /// scrambling algorithm. Rows and columns are scrambled in groups of this size.
+    ///
+    /// # Returns
+    /// The distance between consecutive bits of a row or column index.
+    #[must_use]
+    pub fn index_stride(&self) -> usize {
+        self.dim / self.index_bits()
+    }
+
+    /// Returns the offset of the first key bit read for each row of a group in the scrambling
+    /// algorithm.
+    ///
+    /// The offsets are `0..index_stride` in bit-reversed order, so that neighboring rows read
+    /// distant bits. For 16x16 blocks, these are the offsets `[0, 2, 1, 3]` of RFC-0.
+    #[must_use]
+    fn scramble_offsets(&self) -> Vec<usize> {
+        let mut offsets: Vec<usize> = (0..self.index_stride()).collect();
+        offsets.sort_by_key(|offset| offset.reverse_bits());
+
+        offsets
+    }
+}
+
+/// Returns the built-in shift and transpose initialization matrices for `geometry` and keys of
+/// width `key_width`.
+///
+/// # Arguments
+/// * `geometry` - the block geometry to return initialization matrices for
+/// * `key_width` - the width of the key which will be used with the matrices
+///
+/// # Returns
+/// A tuple containing the shift and transpose initialization matrices, or `None` if there are no
+/// built-in matrices for `geometry`.
+#[must_use]
+pub fn default_init_matrices(
+    geometry: &BlockGeometry,
+    key_width: KeyWidth,
+) -> Option<(&'static str, &'static str)> {
+    match (geometry.n_rows(), key_width) {
+        (16, KeyWidth::Bits32) => Some((S_INIT_MATRIX, T_INIT_MATRIX)),
+        (16, _) => Some((S_INIT_MATRIX_128, T_INIT_MATRIX_128)),
+        (32, _) => Some((S_INIT_MATRIX_32X32, T_INIT_MATRIX_32X32)),
+        (64, _) => Some((S_INIT_MATRIX_64X64, T_INIT_MATRIX_64X64)),
+        _ => None,
+    }
+}
+
+/// Variants of the Talos protocol which produce incompatible ciphertexts.
+///
+/// The variant is recorded in the header of every Talos container (see
+/// [`container`](crate::container)) so that ciphertexts can be decrypted by the protocol which
+/// produced them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProtocolVariant {
+    /// The protocol as originally implemented, scrambling blocks with [`Scrambler::LegacySwap`].
+    /// Only kept to decrypt existing ciphertexts.
+    LegacyRfc1,
+    /// The rotating protocol as originally implemented, scrambling blocks with
+    /// [`Scrambler::LegacySwapRotate`]. Only kept to decrypt existing ciphertexts.
+    LegacyRotatingScramble,
+    /// The protocol described in RFC-0 with the temporal seeding described in RFC-1.
+    Rfc1,
+    /// The protocol of [`ProtocolVariant::Rfc1`], scrambling blocks with
+    /// [`Scrambler::SwapRotate`].
+    RotatingScramble,
+}
+
+impl ProtocolVariant {
+    /// Returns the identifier of the `ProtocolVariant` as stored in a container header.
+    ///
+    /// # Returns
+    /// The identifier of the `ProtocolVariant`.
+    #[must_use]
+    pub fn id(&self) -> u8 {
+        match self {
+            Self::LegacyRfc1 => 0,
+            Self::LegacyRotatingScramble => 1,
+            Self::Rfc1 => 2,
+            Self::RotatingScramble => 3,
+        }
+    }
+
+    /// Returns the `ProtocolVariant` with identifier `id`, or `None` if no such variant exists.
+    ///
+    /// # Arguments
+    /// * `id` - the identifier of the variant, as returned by [`ProtocolVariant::id`]
+    ///
+    /// # Returns
+    /// The `ProtocolVariant` with identifier `id`, or `None` if `id` is unknown.
+    #[must_use]
+    pub fn from_id(id: u8) -> Option<Self> {
+        match id {
+            0 => Some(Self::LegacyRfc1),
+            1 => Some(Self::LegacyRotatingScramble),
+            2 => Some(Self::Rfc1),
+            3 => Some(Self::RotatingScramble),
+            _ => None,
+        }
+    }
+
+    /// Returns the matrix scrambling algorithm used by the `ProtocolVariant`.
+    ///
+    /// # Returns
+    /// The [`Scrambler`] used by the `ProtocolVariant`.
+    #[must_use]
+    pub fn scrambler(&self) -> Scrambler {
+        match self {
+            Self::LegacyRfc1 => Scrambler::LegacySwap,
+            Self::LegacyRotatingScramble => Scrambler::LegacySwapRotate,
+            Self::Rfc1 => Scrambler::Swap,
+            Self::RotatingScramble => Scrambler::SwapRotate,
+        }
+    }
+}
+
+/// Matrix scrambling algorithms used by the variants of the Talos protocol. See
+/// [`ProtocolVariant::scrambler`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scrambler {
+    /// The matrix scrambling algorithm `V` as originally implemented, whose column phase swaps
+    /// the rows at the indices computed for each column instead of the columns.
+    LegacySwap,
+    /// The matrix scrambling algorithm `V` of RFC-0 section 2.2.3, which only swaps rows and
+    /// columns.
+    Swap,
+    /// The algorithm `V`, followed by a toroidal rotation of every row and then of every column
+    /// by amounts read from the transpose automaton.
+    ///
+    /// Swapping rows and columns preserves the multiset of row and column sums of a block, as
+    /// noted in RFC-0 section 5.3.2. Rotating rows mixes bits between columns and rotating columns
+    /// mixes bits between rows, so that neither multiset is preserved.
+    SwapRotate,
+    /// The algorithm [`Scrambler::LegacySwap`], followed by the rotations of
+    /// [`Scrambler::SwapRotate`].
+    LegacySwapRotate,
+}
+
+impl Scrambler {
+    /// Returns whether the `Scrambler` rotates rows and columns after swapping them.
+    fn rotates(self) -> bool {
+        matches!(self, Self::SwapRotate | Self::LegacySwapRotate)
+    }
+}
+
+/// The replaceable components of the Talos cipher, as described in the "Modularity" section of
+/// RFC-0.
+///
+/// [`CipherParams::rfc1`] gives the cipher of RFC-1. Other parameters produce ciphertexts which
+/// can only be decrypted with the same parameters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CipherParams {
+    /// The rule followed by both automata.
+    pub rule: AutomatonRule,
+    /// The initialization matrix of the shift automaton. See
+    /// [`get_shift_transpose_automata_with_params`] for its format.
+    pub s_init_matrix: Cow<'static, str>,
+    /// The initialization matrix of the transpose automaton.
+    pub t_init_matrix: Cow<'static, str>,
+    /// The number of generations both automata are iterated before each block.
+    pub iters_per_block: u32,
+    /// The temporal seeding schedule.
+    pub schedule: SeedingSchedule,
+    /// The matrix scrambling algorithm applied to each block.
+    pub scrambler: Scrambler,
+}
+
+impl CipherParams {
+    /// Returns the parameters of the protocol described in RFC-0 with the temporal seeding
+    /// described in RFC-1, for 16x16 blocks and 32 bit keys.
+    ///
+    /// # Returns
+    /// The parameters of [`ProtocolVariant::Rfc1`].
+    #[must_use]
+    pub fn rfc1() -> Self {
+        Self::for_variant(ProtocolVariant::Rfc1)
+    }
+
+    /// Returns the parameters of `variant` for 16x16 blocks and 32 bit keys.
+    ///
+    /// # Arguments
+    /// * `variant` - the protocol variant selecting the matrix scrambling algorithm
+    ///
+    /// # Returns
+    /// The parameters of `variant`.
+    #[must_use]
+    pub fn for_variant(variant: ProtocolVariant) -> Self {
+        Self {
+            rule: AUTOMATA_RULE,
+            s_init_matrix: Cow::Borrowed(S_INIT_MATRIX),
+            t_init_matrix: Cow::Borrowed(T_INIT_MATRIX),
+            iters_per_block: N_ITERS_PER_BLOCK,
+            schedule: SeedingSchedule::rfc1(),
+            scrambler: variant.scrambler(),
+        }
+    }
+
+    /// Returns the parameters of `variant` for `geometry` and keys of width `key_width`, using
+    /// the built-in initialization matrices returned by [`default_init_matrices`].
+    ///
+    /// # Arguments
+    /// * `geometry` - the block geometry to return parameters for
+    /// * `key_width` - the width of the key which will be used with the parameters
+    /// * `variant` - the protocol variant selecting the matrix scrambling algorithm
+    ///
+    /// # Returns
+    /// The parameters of `variant`, or [`GeometryError::NoDefaultMatrices`] if there are no
+    /// built-in matrices for `geometry`.
+    pub fn for_geometry(
+        geometry: &BlockGeometry,
+        key_width: KeyWidth,
+        variant: ProtocolVariant,
+    ) -> Result<Self, GeometryError> {
+        let (s_init_matrix, t_init_matrix) =
+            default_init_matrices(geometry, key_width).ok_or(GeometryError::NoDefaultMatrices())?;
+
+        Ok(Self {
+            s_init_matrix: Cow::Borrowed(s_init_matrix),
+            t_init_matrix: Cow::Borrowed(t_init_matrix),
+            ..Self::for_variant(variant)
+        })
+    }
+}
+
+impl Default for CipherParams {
+    fn default() -> Self {
+        Self::rfc1()
+    }
+}
+
+/// Error occurring while adding padding to a message or removing it from a decrypted message.
+#[derive(Debug)]
+pub enum PaddingError {
+    /// PKCS#7 padding was requested for blocks of more than [`MAX_PKCS7_BLOCK_BYTES`] bytes,
+    /// whose padding length does not fit in a byte.
+    BlockTooLarge(),
+    /// A padded message must contain a positive multiple of the number of bytes in a block.
+    InvalidLength(),
+    /// The padding at the end of the message is not valid PKCS#7 padding. This usually indicates
+    /// that the message was decrypted with the wrong key or has been corrupted.
+    MalformedPadding(),
+}
+
+impl Error for PaddingError {}
+impl fmt::Display for PaddingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::BlockTooLarge() => {
+                write!(
+                    f,
+                    "PKCS#7 padding requires blocks of at most {} bytes",
+                    MAX_PKCS7_BLOCK_BYTES
+                )
+            }
+            Self::InvalidLength() => {
+                write!(f, "Invalid padded message length")
+            }
+            Self::MalformedPadding() => {
+                write!(f, "Malformed padding")
+            }
+        }
+    }
+}
 
 /// A ToroidalBitMatrix backed by a `Vec<u8>`. Allows for quick reading of character values.
 pub type TalosMatrix = ToroidalBitMatrix<u8>;
@@ -48,31 +486,124 @@ pub type TalosAutomaton = ToroidalAutomaton<TalosMatrix>;
 /// # Returns
 /// A tuple containing the initialized transpose and shift automata.
 #[must_use]
+#[deprecated(note = "use `get_shift_transpose_automata_with_params` with `CipherParams::rfc1()`")]
 pub fn get_shift_transpose_automata(seed: u32) -> (TalosAutomaton, TalosAutomaton) {
-    let mut char_map = parse::gen_char_map(seed);
+    get_shift_transpose_automata_with_params(
+        &CipherParams::rfc1(),
+        &GEOMETRY_256,
+        &TalosKey::Bits32(seed),
+        None,
+    )
+    .unwrap()
+}
+
+/// Prepares and returns the transpose and shift automata described by `params`.
+///
+/// Both automata follow `params.rule`, and are initialized from the matrices of `params`. Each
+/// matrix is read like those of RFC-0 section 2.2.1, with `#` and `.` for fixed cells and the
+/// digits of [`parse::key_digit`] for cells taking the value of a key bit. A matrix referencing
+/// `n` key digits sets digit `i` to key bit `i % key.n_bits()`. Temporal seeding is performed
+/// with [`temporal_seed_automaton_with_key`] following `params.schedule`, so every key bit
+/// affects the automata.
+///
+/// If `nonce` is given, its bits are then seeded in rounds following `params.schedule`, each
+/// filling every key bit cell once. Bit `j` of the nonce is xored with key bit
+/// `j % key.n_bits()`, the bit which initially filled its cell for the 32 bit keys of RFC-1, so
+/// that messages encrypted under the same key with different nonces use unrelated keystreams.
+///
+/// [`CipherParams::rfc1`] with [`GEOMETRY_256`], a [`TalosKey::Bits32`] key and no nonce gives
+/// the automata of RFC-1. See [`CipherParams::for_geometry`] for the built-in matrices of other
+/// geometries and key widths.
+///
+/// # Arguments
+/// * `params` - the cipher parameters
+/// * `geometry` - the block geometry the automata will encrypt. Both matrices of `params` must
+///   have its shape
+/// * `key` - the key to use for automaton initialization and temporal seeding.
+/// * `nonce` - a value unique to the message being encrypted, or `None` for the protocol of
+///   RFC-1. It does not need to be secret.
+///
+/// # Returns
+/// A tuple containing the initialized transpose and shift automata, or a [`GeometryError`] if
+/// either matrix is invalid.
+pub fn get_shift_transpose_automata_with_params(
+    params: &CipherParams,
+    geometry: &BlockGeometry,
+    key: &TalosKey,
+    nonce: Option<&[u8; NONCE_SIZE]>,
+) -> Result<(TalosAutomaton, TalosAutomaton), GeometryError> {
+    Ok((
+        init_automaton(
+            &params.s_init_matrix,
+            &params.rule,
+            geometry,
+            key,
+            nonce,
+            &params.schedule,
+        )?,
+        init_automaton(
+            &params.t_init_matrix,
+            &params.rule,
+            geometry,
+            key,
+            nonce,
+            &params.schedule,
+        )?,
+    ))
+}
+
+/// Prepares a single automaton following `rule` for
+/// [`get_shift_transpose_automata_with_params`].
+fn init_automaton(
+    init_matrix: &str,
+    rule: &AutomatonRule,
+    geometry: &BlockGeometry,
+    key: &TalosKey,
+    nonce: Option<&[u8; NONCE_SIZE]>,
+    schedule: &SeedingSchedule,
+) -> Result<TalosAutomaton, GeometryError> {
+    let n_slots = parse::count_key_digits(init_matrix);
+    if n_slots == 0 {
+        return Err(GeometryError::NoKeyCells());
+    }
+
+    let mut char_map = parse::gen_key_char_map(key, n_slots);
     char_map.insert('#', true);
     char_map.insert('.', false);
 
-    let s_table = parse::parse_bool_table(S_INIT_MATRIX, &char_map).unwrap();
-    let t_table = parse::parse_bool_table(T_INIT_MATRIX, &char_map).unwrap();
-
-    let s_state = TalosMatrix::new(s_table).unwrap();
-    let t_state = TalosMatrix::new(t_table).unwrap();
+    let table =
+        parse::parse_bool_table(init_matrix, &char_map).map_err(GeometryError::InvalidMatrix)?;
+    if table.len() != geometry.n_rows() || table.iter().any(|row| row.len() != geometry.n_cols()) {
+        return Err(GeometryError::MatrixShapeMismatch());
+    }
 
-    let mut s_automaton = ToroidalAutomaton::new(s_state, AUTOMATA_RULE);
-    let mut t_automaton = ToroidalAutomaton::new(t_state, AUTOMATA_RULE);
+    let state = TalosMatrix::new(table).unwrap();
+    let mut automaton = ToroidalAutomaton::new(state, rule.clone());
 
-    let s_temporal_seed_map = parse::get_temporal_seed_map(S_INIT_MATRIX);
-    let t_temporal_seed_map = parse::get_temporal_seed_map(T_INIT_MATRIX);
+    let temporal_seed_map = parse::get_key_temporal_seed_map(init_matrix, n_slots);
+    temporal_seed_automaton_with_key(&mut automaton, key, &temporal_seed_map, schedule);
 
-    temporal_seed_automaton(&mut s_automaton, seed, &s_temporal_seed_map);
-    temporal_seed_automaton(&mut t_automaton, seed, &t_temporal_seed_map);
+    if let Some(nonce) = nonce {
+        let n_key_bits = key.n_bits();
+        // matrices with more key cells than the nonce has bits seed the remaining cells with key bits
+        let nonce_bit = |i: usize| i < NONCE_SIZE * 8 && (nonce[i / 8] >> (i % 8)) & 1 != 0;
+        for round in 0..(NONCE_SIZE * 8).div_ceil(n_slots) {
+            let round_bit = |i: usize| {
+                let bit = round * n_slots + i;
+                nonce_bit(bit) ^ key.bit(bit % n_key_bits)
+            };
+            seeding::seed_automaton(&mut automaton, schedule, &temporal_seed_map, round_bit);
+        }
+    }
 
-    (s_automaton, t_automaton)
+    Ok(automaton)
 }
 
 /// Encrypts a message with a 256 bit block using the Talos algorithm.
 ///
+/// Equivalent to [`encrypt_message`] with [`GEOMETRY_256`] and [`ProtocolVariant::LegacyRfc1`],
+/// the protocol of existing headerless ciphertexts.
+///
 /// <div class="warning">
 /// *DOES NOT* perform the temporal seeding as defined in RFC-1 section 2.1.
 /// Matrix initialization is performed by other methods. See [`get_transpose_shift_automata`].
@@ -91,178 +622,787 @@ pub fn encrypt_message_256(
     shift_automata: &mut TalosAutomaton,
     transpose_automata: &mut TalosAutomaton,
 ) -> Vec<u8> {
-    let blocks = block_split_256_message(message);
+    encrypt_message(
+        message,
+        &GEOMETRY_256,
+        ProtocolVariant::LegacyRfc1,
+        shift_automata,
+        transpose_automata,
+    )
+}
+
+/// Decrypts a message with a 256 bit block using the Talos algorithm.
+///
+/// Equivalent to [`decrypt_message`] with [`GEOMETRY_256`] and [`ProtocolVariant::LegacyRfc1`],
+/// the protocol of existing headerless ciphertexts.
+///
+/// <div class="warning">
+/// *DOES NOT* perform the temporal seeding as defined in RFC-1 section 2.1.
+/// Matrix initialization is performed by other methods. See [`get_transpose_shift_automata`].
+/// </div>
+///
+/// # Arguments
+/// * `ciphertext` - the ciphertext to be decrypted as a vector of bytes
+/// * `shift_automata` - the initial automaton to be used for shifting during decryption
+/// * `transpose_automata` - the initial automaton to be used for unscrambling during decryption
+///
+/// # Returns
+/// The decrypted message as a vector of bytes.
+#[must_use]
+pub fn decrypt_message_256(
+    ciphertext: Vec<u8>,
+    shift_automata: &mut TalosAutomaton,
+    transpose_automata: &mut TalosAutomaton,
+) -> Vec<u8> {
+    decrypt_message(
+        ciphertext,
+        &GEOMETRY_256,
+        ProtocolVariant::LegacyRfc1,
+        shift_automata,
+        transpose_automata,
+    )
+}
+
+/// Encrypts a message with blocks of shape `geometry` using the Talos algorithm.
+///
+/// <div class="warning">
+/// *DOES NOT* perform the temporal seeding as defined in RFC-1 section 2.1.
+/// Matrix initialization is performed by other methods. See
+/// [`get_shift_transpose_automata_with_params`].
+/// </div>
+///
+/// # Arguments
+/// * `message` - the plaintext to be encrypted as a vector of bytes
+/// * `geometry` - the shape of the encryption blocks. Must match the shape of both automata
+/// * `variant` - the protocol variant selecting the matrix scrambling algorithm
+/// * `shift_automata` - the initial automaton to be used for shifting during decryption
+/// * `transpose_automata` - the initial automaton to be used for scrambling during decryption
+///
+/// # Returns
+/// The encrypted message as a vector of bytes.
+#[must_use]
+pub fn encrypt_message(
+    message: Vec<u8>,
+    geometry: &BlockGeometry,
+    variant: ProtocolVariant,
+    shift_automata: &mut TalosAutomaton,
+    transpose_automata: &mut TalosAutomaton,
+) -> Vec<u8> {
+    encrypt_message_with_params(
+        message,
+        geometry,
+        &CipherParams::for_variant(variant),
+        shift_automata,
+        transpose_automata,
+    )
+}
+
+/// Encrypts a message with blocks of shape `geometry` using the Talos algorithm with the
+/// scrambling algorithm and iterations per block of `params`.
+///
+/// With [`CipherParams::for_variant`], the result is the same as that of [`encrypt_message`].
+///
+/// <div class="warning">
+/// *DOES NOT* perform the temporal seeding as defined in RFC-1 section 2.1.
+/// Matrix initialization is performed by other methods. See
+/// [`get_shift_transpose_automata_with_params`].
+/// </div>
+///
+/// # Arguments
+/// * `message` - the plaintext to be encrypted as a vector of bytes
+/// * `geometry` - the shape of the encryption blocks. Must match the shape of both automata
+/// * `params` - the cipher parameters the automata were prepared with
+/// * `shift_automata` - the initial automaton to be used for shifting during encryption
+/// * `transpose_automata` - the initial automaton to be used for scrambling during encryption
+///
+/// # Returns
+/// The encrypted message as a vector of bytes.
+#[must_use]
+pub fn encrypt_message_with_params(
+    message: Vec<u8>,
+    geometry: &BlockGeometry,
+    params: &CipherParams,
+    shift_automata: &mut TalosAutomaton,
+    transpose_automata: &mut TalosAutomaton,
+) -> Vec<u8> {
+    let blocks = block_split_message(message, geometry);
 
     blocks
         .iter()
-        .flat_map(|b| encrypt_block_256(b.to_vec(), shift_automata, transpose_automata))
+        .flat_map(|b| {
+            encrypt_block(
+                b.to_vec(),
+                geometry,
+                params.scrambler,
+                params.iters_per_block,
+                shift_automata,
+                transpose_automata,
+            )
+        })
         .collect()
 }
 
-/// Decrypts a message with a 256 bit block using the Talos algorithm.
+/// Decrypts a message with blocks of shape `geometry` using the Talos algorithm.
 ///
 /// <div class="warning">
 /// *DOES NOT* perform the temporal seeding as defined in RFC-1 section 2.1.
-/// Matrix initialization is performed by other methods. See [`get_transpose_shift_automata`].
+/// Matrix initialization is performed by other methods. See
+/// [`get_shift_transpose_automata_with_params`].
 /// </div>
 ///
 /// # Arguments
 /// * `ciphertext` - the ciphertext to be decrypted as a vector of bytes
+/// * `geometry` - the shape of the encryption blocks. Must match the shape of both automata
+/// * `variant` - the protocol variant selecting the matrix scrambling algorithm
 /// * `shift_automata` - the initial automaton to be used for shifting during decryption
 /// * `transpose_automata` - the initial automaton to be used for unscrambling during decryption
 ///
 /// # Returns
 /// The decrypted message as a vector of bytes.
 #[must_use]
-pub fn decrypt_message_256(
+pub fn decrypt_message(
+    ciphertext: Vec<u8>,
+    geometry: &BlockGeometry,
+    variant: ProtocolVariant,
+    shift_automata: &mut TalosAutomaton,
+    transpose_automata: &mut TalosAutomaton,
+) -> Vec<u8> {
+    decrypt_message_with_params(
+        ciphertext,
+        geometry,
+        &CipherParams::for_variant(variant),
+        shift_automata,
+        transpose_automata,
+    )
+}
+
+/// Decrypts a message with blocks of shape `geometry` using the Talos algorithm with the
+/// scrambling algorithm and iterations per block of `params`.
+///
+/// With [`CipherParams::for_variant`], the result is the same as that of [`decrypt_message`].
+///
+/// <div class="warning">
+/// *DOES NOT* perform the temporal seeding as defined in RFC-1 section 2.1.
+/// Matrix initialization is performed by other methods. See
+/// [`get_shift_transpose_automata_with_params`].
+/// </div>
+///
+/// # Arguments
+/// * `ciphertext` - the ciphertext to be decrypted as a vector of bytes
+/// * `geometry` - the shape of the encryption blocks. Must match the shape of both automata
+/// * `params` - the cipher parameters the message was encrypted with
+/// * `shift_automata` - the initial automaton to be used for shifting during decryption
+/// * `transpose_automata` - the initial automaton to be used for unscrambling during decryption
+///
+/// # Returns
+/// The decrypted message as a vector of bytes.
+#[must_use]
+pub fn decrypt_message_with_params(
     ciphertext: Vec<u8>,
+    geometry: &BlockGeometry,
+    params: &CipherParams,
     shift_automata: &mut TalosAutomaton,
     transpose_automata: &mut TalosAutomaton,
 ) -> Vec<u8> {
-    let blocks = block_split_256_message(ciphertext);
+    let blocks = block_split_message(ciphertext, geometry);
     blocks
         .iter()
-        .flat_map(|b| decrypt_block_256(b.to_vec(), shift_automata, transpose_automata))
+        .flat_map(|b| {
+            decrypt_block(
+                b.to_vec(),
+                geometry,
+                params.scrambler,
+                params.iters_per_block,
+                shift_automata,
+                transpose_automata,
+            )
+        })
         .collect()
 }
 
-/// Applies the matrix scrambling algorithm `V` explained in RFC-0 section 2.2.3.
+/// Encrypts a message with a 256 bit block using the Talos algorithm, padding it with
+/// [`pad_message_256`] first.
+///
+/// Unlike [`encrypt_message_256`], the plaintext can be recovered exactly with
+/// [`decrypt_padded_message_256`].
+///
+/// # Arguments
+/// * `message` - the plaintext to be encrypted as a vector of bytes
+/// * `shift_automata` - the initial automaton to be used for shifting during encryption
+/// * `transpose_automata` - the initial automaton to be used for scrambling during encryption
+///
+/// # Returns
+/// The encrypted message as a vector of bytes.
+#[must_use]
+pub fn encrypt_padded_message_256(
+    message: Vec<u8>,
+    shift_automata: &mut TalosAutomaton,
+    transpose_automata: &mut TalosAutomaton,
+) -> Vec<u8> {
+    encrypt_message_256(pad_message_256(message), shift_automata, transpose_automata)
+}
+
+/// Decrypts a message produced by [`encrypt_padded_message_256`], removing its padding with
+/// [`unpad_message_256`].
+///
+/// # Arguments
+/// * `ciphertext` - the ciphertext to be decrypted as a vector of bytes
+/// * `shift_automata` - the initial automaton to be used for shifting during decryption
+/// * `transpose_automata` - the initial automaton to be used for unscrambling during decryption
+///
+/// # Returns
+/// The decrypted message, or a [`PaddingError`] if the decrypted message is not correctly padded.
+pub fn decrypt_padded_message_256(
+    ciphertext: Vec<u8>,
+    shift_automata: &mut TalosAutomaton,
+    transpose_automata: &mut TalosAutomaton,
+) -> Result<Vec<u8>, PaddingError> {
+    decrypt_padded_message(
+        ciphertext,
+        &GEOMETRY_256,
+        ProtocolVariant::LegacyRfc1,
+        shift_automata,
+        transpose_automata,
+    )
+}
+
+/// Decrypts a message encrypted with blocks of shape `geometry` after padding with
+/// [`pad_message`], removing its padding with [`unpad_message`].
+///
+/// # Arguments
+/// * `ciphertext` - the ciphertext to be decrypted as a vector of bytes
+/// * `geometry` - the shape of the encryption blocks. Must match the shape of both automata
+/// * `variant` - the protocol variant selecting the matrix scrambling algorithm
+/// * `shift_automata` - the initial automaton to be used for shifting during decryption
+/// * `transpose_automata` - the initial automaton to be used for unscrambling during decryption
+///
+/// # Returns
+/// The decrypted message, or a [`PaddingError`] if the decrypted message is not correctly padded.
+pub fn decrypt_padded_message(
+    ciphertext: Vec<u8>,
+    geometry: &BlockGeometry,
+    variant: ProtocolVariant,
+    shift_automata: &mut TalosAutomaton,
+    transpose_automata: &mut TalosAutomaton,
+) -> Result<Vec<u8>, PaddingError> {
+    let block_bytes = geometry.block_bytes();
+    if ciphertext.is_empty() || !ciphertext.len().is_multiple_of(block_bytes) {
+        return Err(PaddingError::InvalidLength());
+    }
+
+    unpad_message(
+        decrypt_message(
+            ciphertext,
+            geometry,
+            variant,
+            shift_automata,
+            transpose_automata,
+        ),
+        geometry,
+    )
+}
+
+/// Pads `message` to a multiple of [`BLOCK_BYTES`] using
+/// [PKCS#7](https://datatracker.ietf.org/doc/html/rfc5652#section-6.3) padding.
+///
+/// Between 1 and [`BLOCK_BYTES`] bytes are always appended, each containing the number of
+/// appended bytes. A message which already fills its final block gains a full block of padding.
+///
+/// # Arguments
+/// * `message` - the message to pad
+///
+/// # Returns
+/// The padded message.
+///
+/// # Examples
+/// A 30 byte message is padded with the two bytes `[2, 2]`, while a 32 byte message is padded
+/// with 32 bytes of value `32`.
+#[must_use]
+pub fn pad_message_256(message: Vec<u8>) -> Vec<u8> {
+    // 32 byte blocks are short enough for PKCS#7 padding
+    pad_message(message, &GEOMETRY_256).unwrap()
+}
+
+/// Removes [PKCS#7](https://datatracker.ietf.org/doc/html/rfc5652#section-6.3) padding added by
+/// [`pad_message_256`] from `message`.
+///
+/// # Arguments
+/// * `message` - the padded message
+///
+/// # Returns
+/// `message` without its padding, or a [`PaddingError`] if `message` is not correctly padded.
+pub fn unpad_message_256(message: Vec<u8>) -> Result<Vec<u8>, PaddingError> {
+    unpad_message(message, &GEOMETRY_256)
+}
+
+/// Pads `message` to a multiple of the block size of `geometry` using
+/// [PKCS#7](https://datatracker.ietf.org/doc/html/rfc5652#section-6.3) padding.
+///
+/// See [`pad_message_256`] for details. PKCS#7 padding stores the number of padding bytes in a
+/// single byte, so it can only be used with blocks of at most [`MAX_PKCS7_BLOCK_BYTES`] bytes.
+///
+/// # Arguments
+/// * `message` - the message to pad
+/// * `geometry` - the shape of the encryption blocks
+///
+/// # Returns
+/// The padded message, or [`PaddingError::BlockTooLarge`] if a block of `geometry` contains more
+/// than [`MAX_PKCS7_BLOCK_BYTES`] bytes.
+pub fn pad_message(
+    mut message: Vec<u8>,
+    geometry: &BlockGeometry,
+) -> Result<Vec<u8>, PaddingError> {
+    let block_bytes = geometry.block_bytes();
+    if block_bytes > MAX_PKCS7_BLOCK_BYTES {
+        return Err(PaddingError::BlockTooLarge());
+    }
+
+    let n_padding = block_bytes - message.len() % block_bytes;
+    message.resize(message.len() + n_padding, n_padding as u8);
+
+    Ok(message)
+}
+
+/// Removes [PKCS#7](https://datatracker.ietf.org/doc/html/rfc5652#section-6.3) padding added by
+/// [`pad_message`] from `message`.
+///
+/// # Arguments
+/// * `message` - the padded message
+/// * `geometry` - the shape of the encryption blocks
+///
+/// # Returns
+/// `message` without its padding, or a [`PaddingError`] if `message` is not correctly padded.
+pub fn unpad_message(
+    mut message: Vec<u8>,
+    geometry: &BlockGeometry,
+) -> Result<Vec<u8>, PaddingError> {
+    let block_bytes = geometry.block_bytes();
+    if message.is_empty() || !message.len().is_multiple_of(block_bytes) {
+        return Err(PaddingError::InvalidLength());
+    }
+
+    let n_padding = *message.last().unwrap() as usize;
+    if n_padding == 0 || n_padding > block_bytes {
+        return Err(PaddingError::MalformedPadding());
+    }
+    let content_len = message.len() - n_padding;
+    if message[content_len..]
+        .iter()
+        .any(|b| *b as usize != n_padding)
+    {
+        return Err(PaddingError::MalformedPadding());
+    }
+
+    message.truncate(content_len);
+    Ok(message)
+}
+
+/// Strategies for filling the final block of a message before encryption.
+///
+/// RFC-0 section 2.1 notes that a sparsely filled final block leaks the state of the shift
+/// automaton, and recommends padding with random noise or sampled words instead of zeros. Every
+/// strategy other than [`PaddingStrategy::Pkcs7`] relies on the plaintext length being stored
+/// alongside the ciphertext, and is removed with [`strip_padding_256`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PaddingStrategy {
+    /// Self-describing PKCS#7 padding. See [`pad_message_256`].
+    Pkcs7,
+    /// Fill the final block with zeros, as in RFC-0.
+    Zeros,
+    /// Fill the final block with random bytes.
+    Random,
+    /// Fill the final block with words sampled at random from a corpus, separated by spaces.
+    Corpus(Vec<Vec<u8>>),
+}
+
+impl PaddingStrategy {
+    /// Creates a [`PaddingStrategy::Corpus`] from the whitespace-separated words of `text`.
+    ///
+    /// If `text` contains no words, the final block will be filled with spaces.
+    ///
+    /// # Arguments
+    /// * `text` - the corpus to sample padding words from
+    ///
+    /// # Returns
+    /// The created `PaddingStrategy`.
+    #[must_use]
+    pub fn from_corpus(text: &str) -> Self {
+        Self::Corpus(
+            text.split_whitespace()
+                .map(|word| word.as_bytes().to_vec())
+                .collect(),
+        )
+    }
+}
+
+/// Pads `message` to a multiple of [`BLOCK_BYTES`] with `strategy`.
+///
+/// With the exception of [`PaddingStrategy::Pkcs7`], no padding is added to a message which
+/// already fills its final block.
+///
+/// # Arguments
+/// * `message` - the message to pad
+/// * `strategy` - the strategy used to generate padding bytes
+/// * `rng` - the random number generator used by [`PaddingStrategy::Random`] and
+///   [`PaddingStrategy::Corpus`]
+///
+/// # Returns
+/// The padded message.
+#[must_use]
+pub fn pad_message_with_256<R: Rng + ?Sized>(
+    message: Vec<u8>,
+    strategy: &PaddingStrategy,
+    rng: &mut R,
+) -> Vec<u8> {
+    // 32 byte blocks are short enough for PKCS#7 padding
+    pad_message_with(message, strategy, &GEOMETRY_256, rng).unwrap()
+}
+
+/// Pads `message` to a multiple of the block size of `geometry` with `strategy`.
+///
+/// See [`pad_message_with_256`] for details.
+///
+/// # Arguments
+/// * `message` - the message to pad
+/// * `strategy` - the strategy used to generate padding bytes
+/// * `geometry` - the shape of the encryption blocks
+/// * `rng` - the random number generator used by [`PaddingStrategy::Random`] and
+///   [`PaddingStrategy::Corpus`]
+///
+/// # Returns
+/// The padded message, or [`PaddingError::BlockTooLarge`] if `strategy` is
+/// [`PaddingStrategy::Pkcs7`] and a block of `geometry` contains more than
+/// [`MAX_PKCS7_BLOCK_BYTES`] bytes. See [`pad_message`].
+pub fn pad_message_with<R: Rng + ?Sized>(
+    mut message: Vec<u8>,
+    strategy: &PaddingStrategy,
+    geometry: &BlockGeometry,
+    rng: &mut R,
+) -> Result<Vec<u8>, PaddingError> {
+    let block_bytes = geometry.block_bytes();
+    let padded_len = message.len().div_ceil(block_bytes) * block_bytes;
+    match strategy {
+        PaddingStrategy::Pkcs7 => return pad_message(message, geometry),
+        PaddingStrategy::Zeros => message.resize(padded_len, 0),
+        PaddingStrategy::Random => {
+            let content_len = message.len();
+            message.resize(padded_len, 0);
+            rng.fill_bytes(&mut message[content_len..]);
+        }
+        PaddingStrategy::Corpus(words) => {
+            while message.len() < padded_len {
+                message.push(b' ');
+                if let Some(word) = words.choose(rng) {
+                    message.extend(word);
+                }
+            }
+            message.truncate(padded_len);
+        }
+    }
+
+    Ok(message)
+}
+
+/// Removes padding added by [`pad_message_with_256`] from `message`, given the length of the
+/// original message.
+///
+/// # Arguments
+/// * `message` - the padded message
+/// * `message_len` - the length of the message before padding
+///
+/// # Returns
+/// `message` without its padding, or a [`PaddingError::InvalidLength`] if `message` could not
+/// have been produced by padding a message of length `message_len`.
+pub fn strip_padding_256(message: Vec<u8>, message_len: usize) -> Result<Vec<u8>, PaddingError> {
+    strip_padding(message, message_len, &GEOMETRY_256)
+}
+
+/// Removes padding added by [`pad_message_with`] from `message`, given the length of the
+/// original message.
+///
+/// # Arguments
+/// * `message` - the padded message
+/// * `message_len` - the length of the message before padding
+/// * `geometry` - the shape of the encryption blocks
+///
+/// # Returns
+/// `message` without its padding, or a [`PaddingError::InvalidLength`] if `message` could not
+/// have been produced by padding a message of length `message_len`.
+pub fn strip_padding(
+    mut message: Vec<u8>,
+    message_len: usize,
+    geometry: &BlockGeometry,
+) -> Result<Vec<u8>, PaddingError> {
+    let block_bytes = geometry.block_bytes();
+    if message.len() != message_len.div_ceil(block_bytes) * block_bytes {
+        return Err(PaddingError::InvalidLength());
+    }
+
+    message.truncate(message_len);
+    Ok(message)
+}
+
+/// Applies the matrix scrambling algorithm `V` explained in RFC-0 section 2.2.3, generalized to
+/// blocks of shape `geometry`.
+///
+/// Rows are visited in groups of [`BlockGeometry::index_stride`]. The index for each row is read
+/// from [`BlockGeometry::index_bits`] bits of the corresponding row of `key`, spaced
+/// `index_stride` bits apart, and the first row of the group is swapped with the row at that
+/// index. The process is then repeated over the columns. [`Scrambler::LegacySwap`] and
+/// [`Scrambler::LegacySwapRotate`] swap rows rather than columns while processing the columns.
+///
+/// With [`Scrambler::SwapRotate`] and [`Scrambler::LegacySwapRotate`], each row is then rotated
+/// by an amount read from other bits of the corresponding row of `key`, followed by each column.
 ///
 /// # Arguments
 /// * `message_matrix` - the matrix to scramble with `V`. Modified inplace
 /// * `key` - the key to use for unscrambling
-fn scramble_matrix_256<T: ToroidalBinaryMatrix>(message_matrix: &mut T, key: &T) {
-    for row_block in 0..4 {
-        // iterate over each row in the 'row block' and swap
-        let block_offset: isize = 4 * row_block;
-        for (row_offset, col_offset) in [0, 2, 1, 3].iter().enumerate() {
-            let (r_offset, c_offset) = (row_offset as isize, *col_offset as isize);
-            let row_swap_idx = read_4_bits(
-                key,
-                (block_offset + r_offset, c_offset),
-                (block_offset + r_offset, 4 + c_offset),
-                (block_offset + r_offset, 8 + c_offset),
-                (block_offset + r_offset, 12 + c_offset),
-            ) as isize;
-            message_matrix.swap_rows(block_offset, row_swap_idx);
-        }
+/// * `geometry` - the shape of `message_matrix` and `key`
+/// * `scrambler` - the scrambling algorithm to apply
+fn scramble_matrix<T: ToroidalBinaryMatrix>(
+    message_matrix: &mut T,
+    key: &T,
+    geometry: &BlockGeometry,
+    scrambler: Scrambler,
+) {
+    for row in 0..geometry.n_rows() {
+        let (block_offset, row_swap_idx) = scramble_row_index(key, geometry, row);
+        message_matrix.swap_rows(block_offset, row_swap_idx);
     }
-    for col_block in 0..4 {
-        // iterate over each col in the 'col block' and swap
-        let block_offset: isize = 4 * col_block;
-        for (col_offset, row_offset) in [3, 0, 2, 1].iter().enumerate() {
-            let (r_offset, c_offset) = (*row_offset as isize, col_offset as isize);
-            let row_swap_idx = read_4_bits(
-                key,
-                (r_offset, block_offset + c_offset),
-                (4 + r_offset, block_offset + c_offset),
-                (8 + r_offset, block_offset + c_offset),
-                (12 + r_offset, block_offset + c_offset),
-            ) as isize;
-            message_matrix.swap_rows(block_offset, row_swap_idx);
+    for col in 0..geometry.n_cols() {
+        let (block_offset, col_swap_idx) = scramble_col_index(key, geometry, col);
+        swap_scrambled_cols(message_matrix, scrambler, block_offset, col_swap_idx);
+    }
+
+    if scrambler.rotates() {
+        for row in 0..geometry.n_rows() {
+            message_matrix.rotate_row(row as isize, rotate_row_amount(key, geometry, row));
+        }
+        for col in 0..geometry.n_cols() {
+            message_matrix.rotate_col(col as isize, rotate_col_amount(key, geometry, col));
         }
     }
 }
 
-/// Applies the inverse matrix scrambling algorithm `V^(-1)` explained in RFC-0 section 2.2.3.
+/// Applies the inverse matrix scrambling algorithm `V^(-1)` explained in RFC-0 section 2.2.3,
+/// generalized to blocks of shape `geometry`. See [`scramble_matrix`].
 ///
 /// # Arguments
 /// * `message_matrix` - the matrix to unscramble with `V^(-1)`. Modified inplace
 /// * `key` - the key to use for unscrambling
-fn unscramble_matrix_256<T: ToroidalBinaryMatrix>(message_matrix: &mut T, key: &T) {
-    for col_block in (0..4).rev() {
-        // iterate over each col in the 'col block' and swap
-        let block_offset: isize = 4 * col_block;
-        for (col_offset, row_offset) in [3, 0, 2, 1].iter().enumerate().rev() {
-            let (r_offset, c_offset) = (*row_offset as isize, col_offset as isize);
-            let row_swap_idx = read_4_bits(
-                key,
-                (r_offset, block_offset + c_offset),
-                (4 + r_offset, block_offset + c_offset),
-                (8 + r_offset, block_offset + c_offset),
-                (12 + r_offset, block_offset + c_offset),
-            ) as isize;
-            message_matrix.swap_rows(block_offset, row_swap_idx);
+/// * `geometry` - the shape of `message_matrix` and `key`
+/// * `scrambler` - the scrambling algorithm to invert
+fn unscramble_matrix<T: ToroidalBinaryMatrix>(
+    message_matrix: &mut T,
+    key: &T,
+    geometry: &BlockGeometry,
+    scrambler: Scrambler,
+) {
+    if scrambler.rotates() {
+        for col in 0..geometry.n_cols() {
+            message_matrix.rotate_col(col as isize, -rotate_col_amount(key, geometry, col));
+        }
+        for row in 0..geometry.n_rows() {
+            message_matrix.rotate_row(row as isize, -rotate_row_amount(key, geometry, row));
         }
     }
 
-    for row_block in (0..4).rev() {
-        // iterate over each row in the 'row block' and swap
-        let block_offset: isize = 4 * row_block;
-        for (row_offset, col_offset) in [0, 2, 1, 3].iter().enumerate().rev() {
-            let (r_offset, c_offset) = (row_offset as isize, *col_offset as isize);
-            let col_swap_idx = read_4_bits(
-                key,
-                (block_offset + r_offset, c_offset),
-                (block_offset + r_offset, 4 + c_offset),
-                (block_offset + r_offset, 8 + c_offset),
-                (block_offset + r_offset, 12 + c_offset),
-            ) as isize;
-            message_matrix.swap_rows(block_offset, col_swap_idx);
-        }
+    for col in (0..geometry.n_cols()).rev() {
+        let (block_offset, col_swap_idx) = scramble_col_index(key, geometry, col);
+        swap_scrambled_cols(message_matrix, scrambler, block_offset, col_swap_idx);
+    }
+    for row in (0..geometry.n_rows()).rev() {
+        let (block_offset, row_swap_idx) = scramble_row_index(key, geometry, row);
+        message_matrix.swap_rows(block_offset, row_swap_idx);
+    }
+}
+
+/// Performs a swap of the column phase of the scrambling algorithm. See [`scramble_matrix`].
+fn swap_scrambled_cols<T: ToroidalBinaryMatrix>(
+    message_matrix: &mut T,
+    scrambler: Scrambler,
+    col1: isize,
+    col2: isize,
+) {
+    match scrambler {
+        Scrambler::LegacySwap | Scrambler::LegacySwapRotate => message_matrix.swap_rows(col1, col2),
+        Scrambler::Swap | Scrambler::SwapRotate => message_matrix.swap_cols(col1, col2),
     }
 }
 
-/// Splits `message` into 256 bit blocks, represented as flat vectors of bytes.
+/// Returns the first row of the group containing `row`, along with the index read from `key`
+/// for `row` by the scrambling algorithm. See [`scramble_matrix`].
+pub(crate) fn scramble_row_index<T: ToroidalBinaryMatrix>(
+    key: &T,
+    geometry: &BlockGeometry,
+    row: usize,
+) -> (isize, isize) {
+    let stride = geometry.index_stride();
+    let swap_idx = read_row_index(key, geometry, row, row % stride);
+
+    ((row - row % stride) as isize, swap_idx as isize)
+}
+
+/// Returns the first column of the group containing `col`, along with the index read from `key`
+/// for `col` by the scrambling algorithm. See [`scramble_matrix`].
+pub(crate) fn scramble_col_index<T: ToroidalBinaryMatrix>(
+    key: &T,
+    geometry: &BlockGeometry,
+    col: usize,
+) -> (isize, isize) {
+    let stride = geometry.index_stride();
+    // columns use the row offsets rotated by one, so that no key bit is read twice
+    let swap_idx = read_col_index(key, geometry, col, (col + stride - 1) % stride);
+
+    ((col - col % stride) as isize, swap_idx as isize)
+}
+
+/// Returns the amount `row` is rotated by in [`Scrambler::SwapRotate`].
+fn rotate_row_amount<T: ToroidalBinaryMatrix>(
+    key: &T,
+    geometry: &BlockGeometry,
+    row: usize,
+) -> isize {
+    let stride = geometry.index_stride();
+    // offsets half a group away from those of the swaps, so that rotations read other key bits
+    read_row_index(key, geometry, row, (row + stride / 2) % stride) as isize
+}
+
+/// Returns the amount `col` is rotated by in [`Scrambler::SwapRotate`].
+fn rotate_col_amount<T: ToroidalBinaryMatrix>(
+    key: &T,
+    geometry: &BlockGeometry,
+    col: usize,
+) -> isize {
+    let stride = geometry.index_stride();
+    read_col_index(key, geometry, col, (col + stride - 1 + stride / 2) % stride) as isize
+}
+
+/// Reads an index from [`BlockGeometry::index_bits`] bits of row `row` of `key`, spaced
+/// [`BlockGeometry::index_stride`] bits apart from the column given by scramble offset `phase`.
+fn read_row_index<T: ToroidalBinaryMatrix>(
+    key: &T,
+    geometry: &BlockGeometry,
+    row: usize,
+    phase: usize,
+) -> usize {
+    let stride = geometry.index_stride();
+    let col_offset = geometry.scramble_offsets()[phase];
+    read_bits(
+        key,
+        (0..geometry.index_bits()).map(|i| (row as isize, (col_offset + i * stride) as isize)),
+    )
+}
+
+/// Reads an index from [`BlockGeometry::index_bits`] bits of column `col` of `key`, spaced
+/// [`BlockGeometry::index_stride`] bits apart from the row given by scramble offset `phase`.
+fn read_col_index<T: ToroidalBinaryMatrix>(
+    key: &T,
+    geometry: &BlockGeometry,
+    col: usize,
+    phase: usize,
+) -> usize {
+    let stride = geometry.index_stride();
+    let row_offset = geometry.scramble_offsets()[phase];
+    read_bits(
+        key,
+        (0..geometry.index_bits()).map(|i| ((row_offset + i * stride) as isize, col as isize)),
+    )
+}
+
+/// Splits `message` into blocks of shape `geometry`, represented as flat vectors of bytes.
 ///
-/// Each element of the result will contain 32 `u8s`.
-/// The final block of `message` is padded to 256 bits.
+/// Each element of the result will contain [`BlockGeometry::block_bytes`] `u8s`.
+/// The final block of `message` is padded with zeros.
 ///
 /// # Arguments
 /// * `message` - the message to split into blocks
+/// * `geometry` - the shape of the blocks
 ///
 /// # Returns
-/// `message` split into blocks containing 256 bits (32 `u8s`).
+/// `message` split into blocks.
 #[must_use]
-fn block_split_256_message(message: Vec<u8>) -> Vec<Vec<u8>> {
-    let u8s_per_block = BLOCK_SIZE / u8::BITS as usize;
-    let mut blocks: Vec<Vec<u8>> = message.chunks(u8s_per_block).map(|c| c.to_vec()).collect();
+fn block_split_message(message: Vec<u8>, geometry: &BlockGeometry) -> Vec<Vec<u8>> {
+    let block_bytes = geometry.block_bytes();
+    let mut blocks: Vec<Vec<u8>> = message.chunks(block_bytes).map(|c| c.to_vec()).collect();
 
     if let Some(last) = blocks.last_mut() {
-        last.resize(u8s_per_block, 0_u8);
+        last.resize(block_bytes, 0_u8);
     }
 
     blocks
 }
 
-/// Encrypts a 256 bit message block with the Talos algorithm.
+/// Encrypts a message block of shape `geometry` with the Talos algorithm.
+///
+/// # Arguments
+/// * `message_block` - the block to encrypt as a vector of bytes
+/// * `geometry` - the shape of the block
+/// * `scrambler` - the matrix scrambling algorithm to apply
+/// * `iters_per_block` - the number of generations to iterate both automata before the block
+/// * `shift_automata` - the Automaton to use as the shift automaton
+/// * `transpose_automata` - the Automaton to use for the scrambling algorithm
+///
+/// # Returns
+/// The encrypted message block as a vector of bytes.
 #[must_use]
-fn encrypt_block_256(
+pub(crate) fn encrypt_block(
     message_block: Vec<u8>,
+    geometry: &BlockGeometry,
+    scrambler: Scrambler,
+    iters_per_block: u32,
     shift_automata: &mut TalosAutomaton,
     transpose_automata: &mut TalosAutomaton,
 ) -> Vec<u8> {
-    let mut message_matrix = TalosMatrix::from_storage(N_ROWS, N_COLS, message_block).unwrap();
-    shift_automata.iter_rule(N_ITERS_PER_BLOCK);
-    transpose_automata.iter_rule(N_ITERS_PER_BLOCK);
+    let mut message_matrix =
+        TalosMatrix::from_storage(geometry.n_rows(), geometry.n_cols(), message_block).unwrap();
+    shift_automata.iter_rule(iters_per_block);
+    transpose_automata.iter_rule(iters_per_block);
 
-    scramble_matrix_256(&mut message_matrix, transpose_automata.get_state());
+    scramble_matrix(
+        &mut message_matrix,
+        transpose_automata.get_state(),
+        geometry,
+        scrambler,
+    );
     let _ = message_matrix.bitwise_xor(shift_automata.get_state());
 
     message_matrix.get_storage().to_vec()
 }
 
-/// Decrypts a 256 bit message block with the Talos algorithm.
+/// Decrypts a message block of shape `geometry` with the Talos algorithm.
 ///
 /// # Arguments
 /// * `encrypted_block` - the block to decrypt as a vector of bytes
+/// * `geometry` - the shape of the block
+/// * `scrambler` - the matrix scrambling algorithm to invert
+/// * `iters_per_block` - the number of generations to iterate both automata before the block
 /// * `shift_automata` - the Automaton to use as the shift automaton
 /// * `transpose_automata` - the Automaton to use for the scrambling algorithm
 ///
 /// # Returns
 /// The decrypted ciphertext block as a vector of bytes.
 #[must_use]
-fn decrypt_block_256(
+pub(crate) fn decrypt_block(
     encrypted_block: Vec<u8>,
+    geometry: &BlockGeometry,
+    scrambler: Scrambler,
+    iters_per_block: u32,
     shift_automata: &mut TalosAutomaton,
     transpose_automata: &mut TalosAutomaton,
 ) -> Vec<u8> {
-    let mut message_matrix = TalosMatrix::from_storage(N_ROWS, N_COLS, encrypted_block).unwrap();
-    shift_automata.iter_rule(N_ITERS_PER_BLOCK);
-    transpose_automata.iter_rule(N_ITERS_PER_BLOCK);
+    let mut message_matrix =
+        TalosMatrix::from_storage(geometry.n_rows(), geometry.n_cols(), encrypted_block).unwrap();
+    shift_automata.iter_rule(iters_per_block);
+    transpose_automata.iter_rule(iters_per_block);
 
     let _ = message_matrix.bitwise_xor(shift_automata.get_state());
-    unscramble_matrix_256(&mut message_matrix, transpose_automata.get_state());
+    unscramble_matrix(
+        &mut message_matrix,
+        transpose_automata.get_state(),
+        geometry,
+        scrambler,
+    );
 
     message_matrix.get_storage().to_vec()
 }
@@ -280,14 +1420,39 @@ pub fn temporal_seed_automaton(
     key: u32,
     seed_positions: &[Vec<ToroidalMatrixIndex>],
 ) {
-    automaton.iter_rule(8);
-    for (bit_pos, seed_position) in seed_positions.iter().enumerate() {
-        let overwritten_value: bool = (key >> bit_pos & 1) > 0;
-        for matrix_idx in seed_position {
-            automaton.set_state(matrix_idx, overwritten_value);
-        }
+    seeding::seed_automaton(
+        automaton,
+        &SeedingSchedule::rfc1(),
+        seed_positions,
+        |bit_pos| (key >> bit_pos & 1) > 0,
+    );
+}
+
+/// Performs temporal seeding following `schedule` with a key of any width.
+///
+/// If `key` has more bits than there are entries in `seed_positions`, `schedule` is repeated until
+/// every key bit has been seeded: in round `j`, the cells `seed_positions[i]` are set to key bit
+/// `j * seed_positions.len() + i`. For a [`TalosKey::Bits32`] key, 32 seed positions and
+/// [`SeedingSchedule::rfc1`], this is the same as [`temporal_seed_automaton`].
+///
+/// # Arguments
+/// * `automaton` - the `ToroidalAutomaton` to be seeded.
+/// * `key` - the key to use for temporal seeding.
+/// * `seed_positions` - a vector containing the ToroidalMatrixIndices to seed each key bit at.
+///   Must contain at most [`TalosKey::n_bits`] entries.
+/// * `schedule` - the schedule of generations and injections of each round.
+pub fn temporal_seed_automaton_with_key(
+    automaton: &mut TalosAutomaton,
+    key: &TalosKey,
+    seed_positions: &[Vec<ToroidalMatrixIndex>],
+    schedule: &SeedingSchedule,
+) {
+    let n_slots = seed_positions.len();
+    for round in 0..key.n_bits().div_ceil(n_slots) {
+        seeding::seed_automaton(automaton, schedule, seed_positions, |bit_pos| {
+            key.bit((round * n_slots + bit_pos) % key.n_bits())
+        });
     }
-    automaton.iter_rule(8);
 }
 
 /// Reads 4 bit values at `idx0`, `idx`, `idx2`, `idx3`, in `matrix`, then concatenates them into a
@@ -317,13 +1482,30 @@ pub fn read_4_bits<T: ToroidalBinaryMatrix>(
     idx2: ToroidalMatrixIndex,
     idx3: ToroidalMatrixIndex,
 ) -> u8 {
-    let mut result: u8 = 0;
-    for (i, idx) in [idx0, idx1, idx2, idx3].iter().enumerate() {
-        result += if matrix.at(idx) {
-            2_u8.pow(i as u32)
-        } else {
-            0
-        };
+    read_bits(matrix, [idx0, idx1, idx2, idx3]) as u8
+}
+
+/// Reads the bit values at `indices` in `matrix`, then concatenates them into a `usize`, with the
+/// first value read as the least significant bit.
+///
+/// Generalizes [`read_4_bits`] to the row and column indices of any [`BlockGeometry`].
+///
+/// # Arguments
+/// * `matrix` - the matrix to read from.
+/// * `indices` - the indices to read bit values from. At most `usize::BITS` indices are read
+///
+/// # Returns
+/// The concatenation of the read values as a `usize`.
+#[must_use]
+pub fn read_bits<T: ToroidalBinaryMatrix>(
+    matrix: &T,
+    indices: impl IntoIterator<Item = ToroidalMatrixIndex>,
+) -> usize {
+    let mut result: usize = 0;
+    for (i, idx) in indices.into_iter().enumerate() {
+        if matrix.at(&idx) {
+            result |= 1 << i;
+        }
     }
 
     result
@@ -0,0 +1,155 @@
+// 2025 Steven Chiacchira
+use crate::key::{self, KeyRng, KeyWidth, TalosKey};
+use crate::mac::{HmacSha256, TAG_SIZE};
+use zeroize::{Zeroize, Zeroizing};
+
+/// Number of bytes in the salt of a passphrase-derived key.
+pub const SALT_SIZE: usize = 16;
+/// Number of PBKDF2 iterations used unless another count is requested.
+pub const DEFAULT_ITERATIONS: u32 = 600_000;
+
+/// Parameters of a passphrase-based key derivation with [`pbkdf2_hmac_sha256`].
+///
+/// The parameters are not secret, and are stored in the header of Talos containers so that the
+/// same key can be derived for decryption.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KdfParams {
+    /// The salt mixed into the derivation, so that identical passphrases give different keys.
+    pub salt: [u8; SALT_SIZE],
+    /// The number of PBKDF2 iterations. Must be nonzero.
+    pub iterations: u32,
+}
+
+impl KdfParams {
+    /// Creates new `KdfParams` with a random salt.
+    ///
+    /// # Arguments
+    /// * `iterations` - the number of PBKDF2 iterations. Must be nonzero
+    /// * `rng` - the random number generator used for the salt
+    ///
+    /// # Returns
+    /// The created `KdfParams`.
+    #[must_use]
+    pub fn new<R: KeyRng + ?Sized>(iterations: u32, rng: &mut R) -> Self {
+        Self {
+            salt: key::random_bytes(rng),
+            iterations,
+        }
+    }
+
+    /// Derives a key of width `width` from `passphrase`.
+    ///
+    /// The key consists of the first `width / 8` bytes of the output of
+    /// [`pbkdf2_hmac_sha256`].
+    ///
+    /// # Arguments
+    /// * `passphrase` - the passphrase to derive a key from
+    /// * `width` - the width of the key to derive
+    ///
+    /// # Returns
+    /// The derived key.
+    #[must_use]
+    pub fn derive_key(&self, passphrase: &[u8], width: KeyWidth) -> TalosKey {
+        let mut bytes = Zeroizing::new(vec![0_u8; width.n_bits() / u8::BITS as usize]);
+        pbkdf2_hmac_sha256(passphrase, &self.salt, self.iterations, &mut bytes);
+
+        TalosKey::from_bytes(&bytes).unwrap()
+    }
+}
+
+/// Fills `output` with the [PBKDF2](https://datatracker.ietf.org/doc/html/rfc8018#section-5.2)
+/// derivation of `password`, using HMAC-SHA-256 as the pseudorandom function.
+///
+/// # Arguments
+/// * `password` - the password to derive key material from
+/// * `salt` - the salt mixed into the derivation
+/// * `iterations` - the number of iterations of the pseudorandom function per output block.
+///   Must be nonzero
+/// * `output` - the buffer to fill with derived key material
+pub fn pbkdf2_hmac_sha256(password: &[u8], salt: &[u8], iterations: u32, output: &mut [u8]) {
+    assert!(iterations > 0, "PBKDF2 requires at least one iteration");
+    let keyed = HmacSha256::new(password);
+
+    for (i, chunk) in output.chunks_mut(TAG_SIZE).enumerate() {
+        let mut mac = keyed.clone();
+        mac.update(salt);
+        mac.update(&(i as u32 + 1).to_be_bytes());
+        let mut u = mac.finalize();
+        let mut block = u;
+        for _ in 1..iterations {
+            let mut mac = keyed.clone();
+            mac.update(&u);
+            u = mac.finalize();
+            for (b, u_byte) in block.iter_mut().zip(u) {
+                *b ^= u_byte;
+            }
+        }
+
+        chunk.copy_from_slice(&block[..chunk.len()]);
+        u.zeroize();
+        block.zeroize();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn from_hex(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn pbkdf2(password: &[u8], salt: &[u8], iterations: u32, len: usize) -> Vec<u8> {
+        let mut output = vec![0_u8; len];
+        pbkdf2_hmac_sha256(password, salt, iterations, &mut output);
+        output
+    }
+
+    #[test]
+    fn test_pbkdf2_known_answers() {
+        assert_eq!(
+            pbkdf2(b"password", b"salt", 1, 32),
+            from_hex("120fb6cffcf8b32c43e7225256c4f837a86548c92ccc35480805987cb70be17b")
+        );
+        assert_eq!(
+            pbkdf2(b"password", b"salt", 2, 32),
+            from_hex("ae4d0c95af6b46d32d0adff928f06dd02a303f8ef3c251dfd6e2d85a95474c43")
+        );
+        assert_eq!(
+            pbkdf2(b"password", b"salt", 4096, 32),
+            from_hex("c5e478d59288c841aa530db6845c4c8d962893a001ce4e11a4963873aa98134a")
+        );
+        assert_eq!(
+            pbkdf2(
+                b"passwordPASSWORDpassword",
+                b"saltSALTsaltSALTsaltSALTsaltSALTsalt",
+                4096,
+                40
+            ),
+            from_hex(
+                "348c89dbcbd32b2f32d814b8116e84cf2b17347ebc1800181c4e2a1fb8dd53e1c635518c7dac47e9"
+            )
+        );
+    }
+
+    #[test]
+    fn test_derive_key_widths() {
+        let params = KdfParams {
+            salt: [7; SALT_SIZE],
+            iterations: 3,
+        };
+        let key_256 = params.derive_key(b"Foo", KeyWidth::Bits256).to_bytes();
+        let key_128 = params.derive_key(b"Foo", KeyWidth::Bits128).to_bytes();
+        let key_32 = params.derive_key(b"Foo", KeyWidth::Bits32).to_bytes();
+
+        assert_eq!(*key_128, key_256[..16]);
+        assert_eq!(*key_32, key_256[..4]);
+        assert_ne!(
+            params.derive_key(b"Bar", KeyWidth::Bits256).to_bytes(),
+            key_256
+        );
+    }
+}
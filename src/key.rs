@@ -1,60 +1,656 @@
 // 2025 Steven Chiacchira
 use std::str::FromStr;
 
+use crate::kdf::KdfParams;
+use crate::mac;
 use num_traits;
-use rand;
+use rand::rand_core::UnwrapErr;
+use rand::rngs::OsRng;
+use rand::{CryptoRng, TryRngCore};
 use sha2::{Digest, Sha256};
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use zeroize::{DefaultIsZeroes, Zeroize, Zeroizing};
 
-pub trait Key = num_traits::PrimInt + num_traits::Unsigned;
+pub trait Key = num_traits::PrimInt + num_traits::Unsigned + DefaultIsZeroes;
+
+/// Random number generators suitable for generating keys, nonces and salts.
+///
+/// Only cryptographically secure generators qualify. [`os_rng`] is used unless another generator
+/// is injected, such as a seeded [`StdRng`](rand::rngs::StdRng) for deterministic tests.
+pub trait KeyRng = CryptoRng;
+
+/// Label used to derive the subkey authenticating Talos containers. See [`derive_subkey`].
+pub const MAC_KEY_LABEL: &[u8] = b"talos mac key";
+/// Label used to derive the subkey computing key check values. See [`derive_subkey`].
+pub const KEY_CHECK_LABEL: &[u8] = b"talos key check";
+/// Number of bytes of the SHA-256 hash kept in a key fingerprint. See [`TalosKey::fingerprint`].
+pub const FINGERPRINT_SIZE: usize = 8;
+
+/// Error occurring while computing an encryption key from a [`KeyArgument`].
+#[derive(Debug)]
+pub enum KeyError {
+    /// Numeric keys are only 32 bits wide, and cannot be used as wider keys.
+    NumericKeyTooNarrow(KeyWidth),
+    /// A hex-encoded key contains a character other than a hexadecimal digit, or an odd number of
+    /// digits.
+    InvalidHex(),
+    /// A key given as bytes has the wrong number of bytes for the requested width. Holds the
+    /// requested width and the number of bytes given.
+    KeyLengthMismatch(KeyWidth, usize),
+    /// The key file could not be read.
+    UnreadableKeyFile(PathBuf, io::Error),
+    /// The environment variable holding the passphrase is not set or is not valid unicode.
+    MissingEnvVar(String),
+    /// The passphrase could not be read from the terminal.
+    PromptUnavailable(io::Error),
+    /// A key given as a number or passphrase looks like hexadecimal digits, so it is unclear
+    /// whether it was meant as key bytes or as a passphrase.
+    AmbiguousHex(),
+}
+
+impl Error for KeyError {}
+impl fmt::Display for KeyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NumericKeyTooNarrow(width) => {
+                write!(
+                    f,
+                    "Numeric keys cannot be used as {} bit keys",
+                    width.n_bits()
+                )
+            }
+            Self::InvalidHex() => {
+                write!(
+                    f,
+                    "Hex keys must consist of an even number of hexadecimal digits"
+                )
+            }
+            Self::KeyLengthMismatch(width, n_bytes) => {
+                write!(
+                    f,
+                    "Expected a {} bit key, but {} bits were given",
+                    width.n_bits(),
+                    n_bytes * u8::BITS as usize
+                )
+            }
+            Self::UnreadableKeyFile(path, err) => {
+                write!(f, "Could not read key file {}: {}", path.display(), err)
+            }
+            Self::MissingEnvVar(name) => {
+                write!(
+                    f,
+                    "Environment variable {} is not set or is not valid unicode",
+                    name
+                )
+            }
+            Self::PromptUnavailable(err) => {
+                write!(f, "Could not read a passphrase from the terminal: {}", err)
+            }
+            Self::AmbiguousHex() => {
+                write!(
+                    f,
+                    "The key looks like hexadecimal digits. Give hex keys with --key-hex, or \
+                    passphrases with --key-env or --key-prompt"
+                )
+            }
+        }
+    }
+}
+
+/// The supported widths of Talos encryption keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyWidth {
+    /// 32 bit keys, as specified in RFC-0 and RFC-1.
+    Bits32,
+    /// 128 bit keys.
+    Bits128,
+    /// 256 bit keys.
+    Bits256,
+}
+
+impl KeyWidth {
+    /// Returns the number of bits in a key of this width.
+    ///
+    /// # Returns
+    /// The number of bits in a key of this width.
+    #[must_use]
+    pub fn n_bits(&self) -> usize {
+        match self {
+            Self::Bits32 => 32,
+            Self::Bits128 => 128,
+            Self::Bits256 => 256,
+        }
+    }
+
+    /// Returns the `KeyWidth` with `n_bits` bits, or `None` if no such width is supported.
+    ///
+    /// # Arguments
+    /// * `n_bits` - the number of bits in the key
+    ///
+    /// # Returns
+    /// The `KeyWidth` with `n_bits` bits, or `None` if `n_bits` is unsupported.
+    #[must_use]
+    pub fn from_n_bits(n_bits: usize) -> Option<Self> {
+        match n_bits {
+            32 => Some(Self::Bits32),
+            128 => Some(Self::Bits128),
+            256 => Some(Self::Bits256),
+            _ => None,
+        }
+    }
+}
+
+/// A Talos encryption key.
+///
+/// The 32 bit variant is the key described in RFC-0 and RFC-1. Wider keys drive the same cipher
+/// with additional temporal seeding; see
+/// [`temporal_seed_automaton_with_key`](crate::encrypt::temporal_seed_automaton_with_key).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TalosKey {
+    /// A 32 bit key.
+    Bits32(u32),
+    /// A 128 bit key.
+    Bits128([u8; 16]),
+    /// A 256 bit key.
+    Bits256([u8; 32]),
+}
+
+impl TalosKey {
+    /// Returns the width of the key.
+    ///
+    /// # Returns
+    /// The width of the key.
+    #[must_use]
+    pub fn width(&self) -> KeyWidth {
+        match self {
+            Self::Bits32(_) => KeyWidth::Bits32,
+            Self::Bits128(_) => KeyWidth::Bits128,
+            Self::Bits256(_) => KeyWidth::Bits256,
+        }
+    }
+
+    /// Returns the number of bits in the key.
+    ///
+    /// # Returns
+    /// The number of bits in the key.
+    #[must_use]
+    pub fn n_bits(&self) -> usize {
+        self.width().n_bits()
+    }
+
+    /// Returns the key as little-endian bytes.
+    ///
+    /// # Returns
+    /// The key as a vector of `n_bits / 8` bytes, which is wiped when dropped.
+    #[must_use]
+    pub fn to_bytes(&self) -> Zeroizing<Vec<u8>> {
+        Zeroizing::new(match self {
+            Self::Bits32(key) => key.to_le_bytes().to_vec(),
+            Self::Bits128(key) => key.to_vec(),
+            Self::Bits256(key) => key.to_vec(),
+        })
+    }
+
+    /// Creates a `TalosKey` from little-endian bytes. The width of the key is given by the number
+    /// of bytes.
+    ///
+    /// # Arguments
+    /// * `bytes` - the key as 4, 16, or 32 bytes
+    ///
+    /// # Returns
+    /// The created `TalosKey`, or `None` if `bytes` has an unsupported length.
+    #[must_use]
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        match bytes.len() {
+            4 => Some(Self::Bits32(u32::from_le_bytes(bytes.try_into().unwrap()))),
+            16 => Some(Self::Bits128(bytes.try_into().unwrap())),
+            32 => Some(Self::Bits256(bytes.try_into().unwrap())),
+            _ => None,
+        }
+    }
+
+    /// Returns the key as hexadecimal digits of its little-endian bytes, as read by
+    /// [`KeyArgument::from_hex`].
+    ///
+    /// # Returns
+    /// The key as `n_bits / 4` lowercase hexadecimal digits.
+    #[must_use]
+    pub fn to_hex(&self) -> String {
+        self.to_bytes()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+
+    /// Returns a fingerprint identifying the key, which can be logged and compared in place of
+    /// the key itself.
+    ///
+    /// The fingerprint is the first [`FINGERPRINT_SIZE`] bytes of the SHA-256 hash of
+    /// [`TalosKey::to_bytes`], as hexadecimal digits. It reveals nothing about wide keys, but a
+    /// 32 bit key can be recovered from its fingerprint by trying every key.
+    ///
+    /// # Returns
+    /// The fingerprint of the key.
+    #[must_use]
+    pub fn fingerprint(&self) -> String {
+        Sha256::digest(self.to_bytes())[..FINGERPRINT_SIZE]
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+
+    /// Overwrites the key with zeros, in a way which is not optimized away. Keys are also wiped
+    /// when they are dropped.
+    pub fn wipe(&mut self) {
+        match self {
+            Self::Bits32(key) => key.zeroize(),
+            Self::Bits128(key) => key.zeroize(),
+            Self::Bits256(key) => key.zeroize(),
+        }
+    }
+
+    /// Returns the value of bit `bit_index` of the key.
+    ///
+    /// Bits are numbered from the least significant bit of the first byte, so bit `i` of a
+    /// [`TalosKey::Bits32`] key `k` is `(k >> i) & 1`.
+    ///
+    /// # Arguments
+    /// * `bit_index` - the index of the bit to read. Must be less than [`TalosKey::n_bits`]
+    ///
+    /// # Returns
+    /// `true` if the bit is set, and `false` otherwise.
+    #[must_use]
+    pub fn bit(&self, bit_index: usize) -> bool {
+        match self {
+            Self::Bits32(key) => (key >> bit_index) & 1 != 0,
+            Self::Bits128(key) => (key[bit_index / 8] >> (bit_index % 8)) & 1 != 0,
+            Self::Bits256(key) => (key[bit_index / 8] >> (bit_index % 8)) & 1 != 0,
+        }
+    }
+}
+
+impl Drop for TalosKey {
+    fn drop(&mut self) {
+        self.wipe();
+    }
+}
+
+impl fmt::Display for TalosKey {
+    /// Formats 32 bit keys as decimal numbers, and wider keys as hexadecimal strings of their
+    /// little-endian bytes.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Bits32(key) => write!(f, "{}", key),
+            _ => write!(f, "{}", self.to_hex()),
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 /// Enum of possible input key values. Used for the encryption and decryption CLI interfaces.
 ///
-/// Allows for computing encryption keys from Strings and unsigned integers.
+/// Allows for computing encryption keys from Strings and unsigned integers, from raw or
+/// hex-encoded key bytes, and from passphrases read from the environment or the terminal.
 pub enum KeyArgument {
     /// A string to be used to generate an encryption key.
     String(String),
     /// A number to be used as an encryption key.
     Num(u32),
+    /// The little-endian bytes of an encryption key, as given by [`KeyArgument::from_hex`].
+    Hex(Vec<u8>),
+    /// A file holding an encryption key, either as raw bytes or as hexadecimal digits.
+    File(PathBuf),
+    /// The name of an environment variable holding a passphrase.
+    Env(String),
+    /// A passphrase to be read from the terminal without echoing it.
+    Prompt,
     /// No encryption key provided, indicating that one should be generated.
     None,
 }
 
 impl KeyArgument {
+    /// Parses a key given as hexadecimal digits, such as the wide keys printed by the CLI tools.
+    ///
+    /// The digits encode the little-endian bytes of the key, as returned by
+    /// [`TalosKey::to_bytes`]. Surrounding whitespace is ignored.
+    ///
+    /// # Arguments
+    /// * `hex` - the hexadecimal digits of the key
+    ///
+    /// # Returns
+    /// A `KeyArgument::Hex`, or [`KeyError::InvalidHex`] if `hex` is not an even number of
+    /// hexadecimal digits.
+    pub fn from_hex(hex: &str) -> Result<Self, KeyError> {
+        decode_hex(hex.trim()).map(Self::Hex)
+    }
+
+    /// Returns whether the key is a passphrase, which should be turned into a key with a key
+    /// derivation function.
+    ///
+    /// # Returns
+    /// `true` for `KeyArgument::String`, `KeyArgument::Env` and `KeyArgument::Prompt`, and
+    /// `false` otherwise.
+    #[must_use]
+    pub fn is_passphrase(&self) -> bool {
+        matches!(self, Self::String(_) | Self::Env(_) | Self::Prompt)
+    }
+
     /// Computes or generates an encryption key.
     ///
-    /// The following three behavior variants are possible:
+    /// The following behavior variants are possible:
     /// * If `KeyArgument` is a `KeyArgument::String`, an encryption key will be deterministically
     ///   generated via sha256. See [`sha2`] crate for details.
     /// * If `KeyArgument` is a `KeyArgument::Num`, its value will be used as an encryption key.
-    /// * If `KeyArgument` is a `KeyArgument::None`, a random key will be generated.
+    /// * If `KeyArgument` is a `KeyArgument::None`, a random key will be generated with
+    ///   [`generate_key`].
+    /// * Otherwise, the key is read as described by [`KeyArgument::get_key`].
+    ///
+    /// # Panics
+    /// If the key cannot be read, or is not a 32 bit key.
     ///
     /// # Returns
     /// An encryption key.
     #[must_use]
     pub fn get(self) -> u32 {
-        match self {
+        match &self {
             Self::String(key) => {
                 let mut hasher = Sha256::new();
                 hasher.update(key.as_bytes());
-                let bytes = hasher.finalize();
+                let mut bytes: [u8; 32] = hasher.finalize().into();
 
                 let first_four_bytes: [u8; 4] = bytes[0..4].try_into().unwrap();
+                bytes.zeroize();
                 u32::from_le_bytes(first_four_bytes)
             }
-            Self::Num(key) => key,
-            Self::None => rand::random::<u32>(),
+            Self::Num(key) => *key,
+            _ => match self.get_key(KeyWidth::Bits32) {
+                Ok(TalosKey::Bits32(key)) => key,
+                Ok(_) => unreachable!(),
+                Err(err) => panic!("{}", err),
+            },
+        }
+    }
+
+    /// Computes or generates an encryption key of width `width`, deriving keys from passphrases
+    /// with the legacy unsalted derivation.
+    ///
+    /// The legacy derivation hashes a passphrase once, so it is cheap to attack with a
+    /// dictionary and always gives the same key for the same passphrase. It is only kept to
+    /// decrypt existing ciphertexts; see [`KeyArgument::get_key_with_kdf`].
+    ///
+    /// Behaves like [`KeyArgument::get`], except that:
+    /// * a `KeyArgument::String` keeps the first `width / 8` bytes of its SHA-256 hash.
+    /// * a `KeyArgument::Num` can only be used as a [`KeyWidth::Bits32`] key.
+    /// * a `KeyArgument::Hex` must hold exactly `width / 8` bytes.
+    /// * a `KeyArgument::File` is read as hexadecimal digits if it only contains hexadecimal
+    ///   digits and whitespace, and as raw bytes otherwise. Either way it must hold exactly
+    ///   `width / 8` bytes. Files of hexadecimal digits which cannot be decoded are rejected
+    ///   with [`KeyError::InvalidHex`] rather than read as raw bytes.
+    /// * a `KeyArgument::Env` or `KeyArgument::Prompt` is read, then treated as a
+    ///   `KeyArgument::String`.
+    ///
+    /// For [`KeyWidth::Bits32`], the result is the same as that of [`KeyArgument::get`].
+    ///
+    /// # Arguments
+    /// * `width` - the width of the key to compute
+    ///
+    /// # Returns
+    /// An encryption key, or a [`KeyError`] if no key of width `width` can be computed.
+    pub fn get_key(self, width: KeyWidth) -> Result<TalosKey, KeyError> {
+        let n_bytes = width.n_bits() / u8::BITS as usize;
+        match (&self.resolve()?, width) {
+            (Self::Num(key), KeyWidth::Bits32) => Ok(TalosKey::Bits32(*key)),
+            (Self::Num(_), _) => Err(KeyError::NumericKeyTooNarrow(width)),
+            (Self::String(key), _) => {
+                let mut bytes: [u8; 32] = Sha256::digest(key.as_bytes()).into();
+                let result = TalosKey::from_bytes(&bytes[..n_bytes]).unwrap();
+                bytes.zeroize();
+                Ok(result)
+            }
+            (Self::Hex(bytes), _) if bytes.len() == n_bytes => {
+                Ok(TalosKey::from_bytes(bytes).unwrap())
+            }
+            (Self::Hex(bytes), _) => Err(KeyError::KeyLengthMismatch(width, bytes.len())),
+            (Self::None, _) => Ok(generate_key(width)),
+            (Self::File(_) | Self::Env(_) | Self::Prompt, _) => unreachable!(),
+        }
+    }
+
+    /// Computes or generates an encryption key of width `width`, deriving keys from passphrases
+    /// with PBKDF2-HMAC-SHA256.
+    ///
+    /// Behaves like [`KeyArgument::get_key`], except that passphrases are derived with
+    /// [`KdfParams::derive_key`].
+    ///
+    /// # Arguments
+    /// * `width` - the width of the key to compute
+    /// * `params` - the salt and iteration count of the derivation
+    ///
+    /// # Returns
+    /// An encryption key, or a [`KeyError`] if no key of width `width` can be computed.
+    pub fn get_key_with_kdf(
+        self,
+        width: KeyWidth,
+        params: &KdfParams,
+    ) -> Result<TalosKey, KeyError> {
+        let key = self.resolve()?;
+        match &key {
+            Self::String(passphrase) => Ok(params.derive_key(passphrase.as_bytes(), width)),
+            _ => key.get_key(width),
+        }
+    }
+
+    /// Reads keys held outside of the `KeyArgument`, turning passphrases from the environment or
+    /// the terminal into `KeyArgument::String`s and key files into `KeyArgument::Hex`s.
+    fn resolve(self) -> Result<Self, KeyError> {
+        match &self {
+            Self::File(path) => {
+                let mut contents = match fs::read(path) {
+                    Ok(contents) => contents,
+                    Err(err) => {
+                        return Err(KeyError::UnreadableKeyFile(path.clone(), err));
+                    }
+                };
+                // raw keys are random bytes, so they are almost never hexadecimal text
+                let hex = std::str::from_utf8(&contents).ok().filter(|text| {
+                    !text.trim().is_empty()
+                        && text
+                            .chars()
+                            .all(|c| c.is_ascii_hexdigit() || c.is_ascii_whitespace())
+                });
+                match hex.map(|text| decode_hex(text.trim())) {
+                    Some(bytes) => {
+                        contents.zeroize();
+                        bytes.map(Self::Hex)
+                    }
+                    None => Ok(Self::Hex(contents)),
+                }
+            }
+            Self::Env(name) => match env::var(name) {
+                Ok(passphrase) => Ok(Self::String(passphrase)),
+                Err(_) => Err(KeyError::MissingEnvVar(name.clone())),
+            },
+            Self::Prompt => read_passphrase("Passphrase: ")
+                .map(Self::String)
+                .map_err(KeyError::PromptUnavailable),
+            _ => Ok(self),
+        }
+    }
+
+    /// Overwrites the passphrase or key bytes held by the `KeyArgument` with zeros, in a way
+    /// which is not optimized away. `KeyArgument`s are also wiped when they are dropped.
+    pub fn wipe(&mut self) {
+        match self {
+            Self::String(passphrase) => passphrase.zeroize(),
+            Self::Num(key) => key.zeroize(),
+            Self::Hex(bytes) => bytes.zeroize(),
+            Self::File(_) | Self::Env(_) | Self::Prompt | Self::None => {}
         }
     }
 }
 
+impl Drop for KeyArgument {
+    fn drop(&mut self) {
+        self.wipe();
+    }
+}
+
 impl FromStr for KeyArgument {
     // Taken from https://stackoverflow.com/questions/73658377/how-to-have-number-or-string-as-a-cli-argument-in-clap
     // We don't ever parse to the None variant, so this works (use Option<KeyArgument> for clap)
-    type Err = &'static str;
+    type Err = KeyError;
+    /// Parses a number as a `KeyArgument::Num`, and any other string as a passphrase.
+    ///
+    /// Strings which look like hexadecimal key bytes, starting with `0x` or consisting of
+    /// hexadecimal digits including at least one of `a-f`, are rejected with
+    /// [`KeyError::AmbiguousHex`], since they would otherwise silently be used as passphrases.
+    /// Such keys must be parsed with [`KeyArgument::from_hex`].
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(s.parse::<u32>()
-            .map(KeyArgument::Num)
-            .unwrap_or_else(|_| KeyArgument::String(s.to_string())))
+        if let Ok(key) = s.parse::<u32>() {
+            return Ok(KeyArgument::Num(key));
+        }
+        let has_hex_prefix = s.starts_with("0x") || s.starts_with("0X");
+        let is_hex_digits =
+            s.chars().all(|c| c.is_ascii_hexdigit()) && s.chars().any(|c| c.is_ascii_alphabetic());
+        if has_hex_prefix || is_hex_digits {
+            return Err(KeyError::AmbiguousHex());
+        }
+
+        Ok(KeyArgument::String(s.to_string()))
+    }
+}
+
+/// Decodes a string of hexadecimal digits into bytes, two digits per byte.
+fn decode_hex(hex: &str) -> Result<Vec<u8>, KeyError> {
+    if hex.is_empty() || !hex.len().is_multiple_of(2) || !hex.bytes().all(|c| c.is_ascii_hexdigit())
+    {
+        return Err(KeyError::InvalidHex());
     }
+
+    Ok((0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+        .collect())
+}
+
+/// Prints `prompt` and reads a line from the controlling terminal with echo disabled.
+#[cfg(unix)]
+fn read_passphrase(prompt: &str) -> io::Result<String> {
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::fd::AsRawFd;
+
+    let mut tty = fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")?;
+    tty.write_all(prompt.as_bytes())?;
+    tty.flush()?;
+
+    let fd = tty.as_raw_fd();
+    // SAFETY: `termios` is plain data, filled in by `tcgetattr` before it is read
+    let mut termios: libc::termios = unsafe { std::mem::zeroed() };
+    if unsafe { libc::tcgetattr(fd, &mut termios) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let original = termios;
+    termios.c_lflag &= !libc::ECHO;
+    termios.c_lflag |= libc::ECHONL;
+    if unsafe { libc::tcsetattr(fd, libc::TCSANOW, &termios) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    let mut line = String::new();
+    let result = BufReader::new(&tty).read_line(&mut line);
+    // the terminal is restored even if reading failed
+    unsafe { libc::tcsetattr(fd, libc::TCSANOW, &original) };
+    result?;
+
+    let passphrase = line.trim_end_matches(['\r', '\n']).to_string();
+    line.zeroize();
+    Ok(passphrase)
+}
+
+/// Reading passphrases without echo is only supported on unix platforms.
+#[cfg(not(unix))]
+fn read_passphrase(_prompt: &str) -> io::Result<String> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "passphrase prompts are not supported on this platform",
+    ))
+}
+
+/// Returns the cryptographically secure random number generator of the operating system.
+///
+/// # Returns
+/// A [`KeyRng`] reading from the operating system, which panics if the operating system fails
+/// to provide randomness.
+#[must_use]
+pub fn os_rng() -> UnwrapErr<OsRng> {
+    OsRng.unwrap_err()
+}
+
+/// Generates a random key of width `width` with [`os_rng`].
+///
+/// # Arguments
+/// * `width` - the width of the key to generate
+///
+/// # Returns
+/// The generated key.
+#[must_use]
+pub fn generate_key(width: KeyWidth) -> TalosKey {
+    generate_key_with_rng(width, &mut os_rng())
+}
+
+/// Generates a random key of width `width` with `rng`.
+///
+/// # Arguments
+/// * `width` - the width of the key to generate
+/// * `rng` - the random number generator to draw the key from
+///
+/// # Returns
+/// The generated key.
+#[must_use]
+pub fn generate_key_with_rng<R: KeyRng + ?Sized>(width: KeyWidth, rng: &mut R) -> TalosKey {
+    let mut bytes = vec![0_u8; width.n_bits() / u8::BITS as usize];
+    rng.fill_bytes(&mut bytes);
+    let result = TalosKey::from_bytes(&bytes).unwrap();
+    bytes.zeroize();
+
+    result
+}
+
+/// Draws `N` random bytes from `rng`, such as a nonce or salt.
+///
+/// # Arguments
+/// * `rng` - the random number generator to draw the bytes from
+///
+/// # Returns
+/// The random bytes.
+#[must_use]
+pub fn random_bytes<const N: usize, R: KeyRng + ?Sized>(rng: &mut R) -> [u8; N] {
+    let mut result = [0_u8; N];
+    rng.fill_bytes(&mut result);
+
+    result
+}
+
+/// Derives a subkey for a purpose other than encryption from an encryption key.
+///
+/// The subkey is the HMAC-SHA-256 tag of `label` keyed with the bytes of `key`, so subkeys with
+/// different labels are independent and reveal nothing about `key`.
+///
+/// # Arguments
+/// * `key` - the encryption key to derive a subkey from
+/// * `label` - a label identifying the purpose of the subkey, such as [`MAC_KEY_LABEL`]
+///
+/// # Returns
+/// The derived subkey, which is wiped when dropped.
+#[must_use]
+pub fn derive_subkey(key: &TalosKey, label: &[u8]) -> Zeroizing<[u8; mac::TAG_SIZE]> {
+    Zeroizing::new(mac::hmac_sha256(&key.to_bytes(), label))
 }
@@ -10,12 +10,24 @@
 pub mod automata;
 /// Module exposing bit access and mutation methods for unsigned integer types.
 pub mod bits;
+/// Command-line interfaces shared by the `talos` tool and the standalone binaries.
+pub mod cli;
+/// Self-describing file format wrapping Talos ciphertexts with a versioned header.
+pub mod container;
 /// High-level subroutines for encryption per the Talos protocol.
 pub mod encrypt;
+/// Salted, iterated derivation of encryption keys from passphrases.
+pub mod kdf;
 /// Utilities for getting encryption keys from users. Supports String keys via SHA-256.
 pub mod key;
+/// HMAC-SHA-256 message authentication for Talos ciphertexts.
+pub mod mac;
 /// Module implementing binary matrix interfaces and implementations.
 pub mod matrix;
 /// Utilities for parsing String representations of binary matrices to binary matrix states.
 /// See page 3 of RFC-0 for an example of such a String representation.
 pub mod parse;
+/// Temporal seeding schedules for initializing Talos automata. See RFC-1 section 2.1.
+pub mod seeding;
+/// Streaming encryption and decryption over [`std::io::Read`] and [`std::io::Write`].
+pub mod stream;
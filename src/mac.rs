@@ -0,0 +1,190 @@
+// 2025 Steven Chiacchira
+use sha2::{Digest, Sha256};
+use zeroize::Zeroize;
+
+/// Number of bytes in an HMAC-SHA-256 tag.
+pub const TAG_SIZE: usize = 32;
+/// Number of bytes in a SHA-256 input block.
+const SHA256_BLOCK_SIZE: usize = 64;
+
+/// Incremental [HMAC](https://datatracker.ietf.org/doc/html/rfc2104) computation over SHA-256.
+///
+/// See [`hmac_sha256`] for computing the tag of a message available all at once.
+#[derive(Clone)]
+pub struct HmacSha256 {
+    /// Hasher for the inner hash, already fed the inner padded key.
+    inner: Sha256,
+    /// The key xored with the outer padding.
+    outer_key: [u8; SHA256_BLOCK_SIZE],
+}
+
+impl HmacSha256 {
+    /// Creates a new `HmacSha256` instance keyed with `key`.
+    ///
+    /// Keys longer than the SHA-256 block size are hashed first, as specified by RFC 2104.
+    ///
+    /// # Arguments
+    /// * `key` - the secret key to authenticate with
+    ///
+    /// # Returns
+    /// The created `HmacSha256` instance.
+    #[must_use]
+    pub fn new(key: &[u8]) -> Self {
+        let mut block_key = [0_u8; SHA256_BLOCK_SIZE];
+        if key.len() > SHA256_BLOCK_SIZE {
+            block_key[..TAG_SIZE].copy_from_slice(&Sha256::digest(key));
+        } else {
+            block_key[..key.len()].copy_from_slice(key);
+        }
+
+        let mut inner_key = block_key;
+        let mut outer_key = block_key;
+        for (inner, outer) in inner_key.iter_mut().zip(outer_key.iter_mut()) {
+            *inner ^= 0x36;
+            *outer ^= 0x5c;
+        }
+
+        let mut inner = Sha256::new();
+        inner.update(inner_key);
+        block_key.zeroize();
+        inner_key.zeroize();
+
+        Self { inner, outer_key }
+    }
+
+    /// Feeds `data` into the tag computation.
+    ///
+    /// # Arguments
+    /// * `data` - the next bytes of the message to authenticate
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+
+    /// Completes the tag computation.
+    ///
+    /// # Returns
+    /// The HMAC-SHA-256 tag of all data passed to [`HmacSha256::update`].
+    #[must_use]
+    pub fn finalize(mut self) -> [u8; TAG_SIZE] {
+        let mut outer = Sha256::new();
+        outer.update(self.outer_key);
+        outer.update(self.inner.finalize_reset());
+
+        outer.finalize_reset().into()
+    }
+}
+
+impl Drop for HmacSha256 {
+    /// Wipes the padded key. The inner hasher cannot be zeroized, so it is reset to forget the
+    /// inner padded key instead.
+    fn drop(&mut self) {
+        self.outer_key.zeroize();
+        Digest::reset(&mut self.inner);
+    }
+}
+
+/// Computes the HMAC-SHA-256 tag of `data` under `key`.
+///
+/// # Arguments
+/// * `key` - the secret key to authenticate with
+/// * `data` - the message to authenticate
+///
+/// # Returns
+/// The HMAC-SHA-256 tag of `data`.
+#[must_use]
+pub fn hmac_sha256(key: &[u8], data: &[u8]) -> [u8; TAG_SIZE] {
+    let mut mac = HmacSha256::new(key);
+    mac.update(data);
+    mac.finalize()
+}
+
+/// Compares two tags in constant time.
+///
+/// The comparison always inspects every byte, so the time taken does not reveal the position of
+/// the first mismatching byte.
+///
+/// # Arguments
+/// * `expected` - the tag computed locally
+/// * `actual` - the tag read from the ciphertext
+///
+/// # Returns
+/// `true` if the tags are equal, and `false` otherwise.
+#[must_use]
+pub fn tags_equal(expected: &[u8], actual: &[u8]) -> bool {
+    if expected.len() != actual.len() {
+        return false;
+    }
+
+    expected
+        .iter()
+        .zip(actual)
+        .fold(0_u8, |acc, (a, b)| acc | (a ^ b))
+        == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mac::{hmac_sha256, tags_equal, HmacSha256};
+
+    fn from_hex(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_rfc4231_case_1() {
+        let key = [0x0b; 20];
+        let tag = hmac_sha256(&key, b"Hi There");
+
+        assert_eq!(
+            tag.to_vec(),
+            from_hex("b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7")
+        );
+    }
+
+    #[test]
+    fn test_rfc4231_case_2() {
+        let tag = hmac_sha256(b"Jefe", b"what do ya want for nothing?");
+
+        assert_eq!(
+            tag.to_vec(),
+            from_hex("5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843")
+        );
+    }
+
+    #[test]
+    fn test_rfc4231_case_6() {
+        // key longer than the SHA-256 block size
+        let key = [0xaa; 131];
+        let tag = hmac_sha256(
+            &key,
+            b"Test Using Larger Than Block-Size Key - Hash Key First",
+        );
+
+        assert_eq!(
+            tag.to_vec(),
+            from_hex("60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54")
+        );
+    }
+
+    #[test]
+    fn test_incremental() {
+        let mut mac = HmacSha256::new(b"Jefe");
+        mac.update(b"what do ya ");
+        mac.update(b"want for nothing?");
+
+        assert_eq!(
+            mac.finalize(),
+            hmac_sha256(b"Jefe", b"what do ya want for nothing?")
+        );
+    }
+
+    #[test]
+    fn test_tags_equal() {
+        assert!(tags_equal(&[1, 2, 3], &[1, 2, 3]));
+        assert!(!tags_equal(&[1, 2, 3], &[1, 2, 4]));
+        assert!(!tags_equal(&[1, 2, 3], &[1, 2]));
+    }
+}
@@ -382,11 +382,44 @@ pub trait ToroidalBinaryMatrix: Sized {
             self.swap_entries(&entry1, &entry2);
         }
     }
+    /// Rotates the row indexed by `row` of this Matrix by `amount` columns, wrapping around its
+    /// edges. The entry in column `col` is moved to column `col + amount`.
+    ///
+    /// # Arguments
+    /// * `row` - Possibly canonized index of the row to be rotated
+    /// * `amount` - the number of columns to rotate by. Negative amounts rotate the other way
+    fn rotate_row(&mut self, row: isize, amount: isize) {
+        let values: Vec<bool> = (0..self.get_n_cols())
+            .map(|col| self.at(&(row, col as isize)))
+            .collect();
+        for (col, value) in values.into_iter().enumerate() {
+            self.set(&(row, col as isize + amount), value);
+        }
+    }
+    /// Rotates the column indexed by `col` of this Matrix by `amount` rows, wrapping around its
+    /// edges. The entry in row `row` is moved to row `row + amount`.
+    ///
+    /// # Arguments
+    /// * `col` - Possibly canonized index of the column to be rotated
+    /// * `amount` - the number of rows to rotate by. Negative amounts rotate the other way
+    fn rotate_col(&mut self, col: isize, amount: isize) {
+        let values: Vec<bool> = (0..self.get_n_rows())
+            .map(|row| self.at(&(row as isize, col)))
+            .collect();
+        for (row, value) in values.into_iter().enumerate() {
+            self.set(&(row as isize + amount, col), value);
+        }
+    }
     /// Returns the number of true elements in the Matrix.
     ///
     /// # Returns
     /// The number of `true` elements in the Matrix.
     fn popcount(&self) -> u32;
+    /// Sets every element of the Matrix to `false`, in a way which is not optimized away.
+    ///
+    /// Used to erase key-derived state before its memory is freed. Implementors should also wipe
+    /// the Matrix when it is dropped.
+    fn wipe(&mut self);
 }
 
 #[cfg(test)]
@@ -410,4 +443,25 @@ mod tests {
         assert_eq!(mat_1.canonize_index((0, -1)), (0, 2));
         assert_eq!(mat_1.canonize_index((-1, -1)), (1, 2));
     }
+
+    #[test]
+    fn test_rotate_row_col() {
+        let table = vec![vec![true, false, false], vec![true, true, false]];
+        let mut mat_1 = ToroidalBitMatrix::<u32>::new(table.clone()).unwrap();
+
+        mat_1.rotate_row(0, 1);
+        assert_eq!(
+            mat_1.to_table(),
+            vec![vec![false, true, false], vec![true, true, false]]
+        );
+        mat_1.rotate_col(-3, 3);
+        assert_eq!(
+            mat_1.to_table(),
+            vec![vec![true, true, false], vec![false, true, false]]
+        );
+
+        mat_1.rotate_col(0, -3);
+        mat_1.rotate_row(0, -1);
+        assert_eq!(mat_1.to_table(), table);
+    }
 }
@@ -1,7 +1,9 @@
 // 2025 Steven Chiacchira
+use crate::automata::{AutomatonRule, AutomatonStep};
 use crate::matrix::{
     MatrixConstructError, MatrixOpError, ToroidalBinaryMatrix, ToroidalMatrixIndex,
 };
+use zeroize::Zeroize;
 
 #[derive(Debug, Clone)]
 /// Struct implementing [`ToroidalBinaryMatrix`] backed by a `Vec<bool>`.
@@ -80,6 +82,59 @@ impl ToroidalBinaryMatrix for ToroidalBoolMatrix {
     fn popcount(&self) -> u32 {
         self.storage.iter().map(|b| *b as u32).sum()
     }
+    fn wipe(&mut self) {
+        self.storage.as_mut_slice().zeroize();
+    }
+}
+
+impl Drop for ToroidalBoolMatrix {
+    fn drop(&mut self) {
+        self.wipe();
+    }
+}
+
+impl AutomatonStep for ToroidalBoolMatrix {
+    /// Steps the Matrix reading its storage directly, with the wrapped row and column offsets of
+    /// each neighbor computed once per row and column rather than canonized for every lookup.
+    fn step(&self, rule: &AutomatonRule, next: &mut Self) -> Result<(), MatrixOpError> {
+        if self.rows != next.rows || self.cols != next.cols {
+            return Err(MatrixOpError::DifferentShapes());
+        }
+
+        let (rows, cols) = (self.rows, self.cols);
+        let offsets = rule.neighborhood.offsets();
+        // the wrapped column of every neighbor of each column
+        let col_offsets: Vec<Vec<usize>> = (0..cols)
+            .map(|col| {
+                offsets
+                    .iter()
+                    .map(|(_, c)| self.canonize_col_index(col as isize + c))
+                    .collect()
+            })
+            .collect();
+        for row in 0..rows {
+            let row_offsets: Vec<usize> = offsets
+                .iter()
+                .map(|(r, _)| self.canonize_row_index(row as isize + r) * cols)
+                .collect();
+            for (col, col_offsets) in col_offsets.iter().enumerate() {
+                let idx = row * cols + col;
+                let n_alive_neighbors = row_offsets
+                    .iter()
+                    .zip(col_offsets)
+                    .filter(|(row_offset, col_offset)| self.storage[*row_offset + *col_offset])
+                    .count();
+
+                next.storage[idx] = if self.storage[idx] {
+                    !rule.dies[n_alive_neighbors]
+                } else {
+                    rule.born[n_alive_neighbors]
+                };
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl ToroidalBoolMatrix {
@@ -204,4 +259,14 @@ mod tests {
         assert_eq!(mat_1.popcount(), 3);
         assert_eq!(mat_2.popcount(), 5);
     }
+
+    #[test]
+    fn test_wipe() {
+        let table = vec![vec![true; 5], vec![false, true, true, false, true]];
+        let mut mat = ToroidalBoolMatrix::new(table).unwrap();
+        mat.wipe();
+
+        assert_eq!(mat.popcount(), 0);
+        assert_eq!(mat.to_table(), vec![vec![false; 5]; 2]);
+    }
 }
@@ -1,4 +1,5 @@
 // 2025 Steven Chiacchira
+use crate::key::TalosKey;
 use crate::matrix::ToroidalMatrixIndex;
 use std::collections::HashMap;
 use std::error::Error;
@@ -71,6 +72,98 @@ pub fn gen_char_map(seed: u32) -> HashMap<char, bool> {
     .collect::<HashMap<char, bool>>()
 }
 
+/// First character of the block of characters used for key bits 32 and above in initialization
+/// templates. See [`key_digit`].
+const WIDE_KEY_DIGITS_START: u32 = 0x2800;
+
+/// Returns the character used to reference key bit `bit` in an initialization template.
+///
+/// Bits 0 through 31 are referenced by their base-32 digit (see [`BASE_32_DIGITS`]), so templates
+/// for 32 bit keys are unchanged. Bits 32 through 255 are referenced by the
+/// [Braille pattern](https://en.wikipedia.org/wiki/Braille_Patterns) `U+2800 + bit`, whose dots
+/// spell out the bit index in binary.
+///
+/// # Arguments
+/// * `bit` - the index of the key bit
+///
+/// # Returns
+/// The character referencing key bit `bit`, or `None` if `bit` is 256 or greater.
+///
+/// # Examples
+/// * `0 -> 'A'`
+/// * `31 -> '7'`
+/// * `32 -> '⠠'` (`U+2820`)
+#[must_use]
+pub fn key_digit(bit: usize) -> Option<char> {
+    if bit < BASE_32_DIGITS.len() {
+        BASE_32_DIGITS.chars().nth(bit)
+    } else if bit < 256 {
+        char::from_u32(WIDE_KEY_DIGITS_START + bit as u32)
+    } else {
+        None
+    }
+}
+
+/// Returns the key bit referenced by `character` in an initialization template. This is the
+/// inverse of [`key_digit`].
+///
+/// # Arguments
+/// * `character` - the character to read
+///
+/// # Returns
+/// The index of the key bit referenced by `character`, or `None` if `character` is not a key
+/// digit.
+#[must_use]
+pub fn key_digit_value(character: char) -> Option<usize> {
+    if let Some(bit) = BASE_32_DIGITS.find(character) {
+        return Some(bit);
+    }
+
+    let bit = (character as u32).checked_sub(WIDE_KEY_DIGITS_START)? as usize;
+    (BASE_32_DIGITS.len()..256).contains(&bit).then_some(bit)
+}
+
+/// Returns the number of key digits referenced by an initialization template, which is one more
+/// than the largest key bit referenced by `string`.
+///
+/// # Arguments
+/// * `string` - the initialization template to read
+///
+/// # Returns
+/// The number of key digits referenced by `string`, or 0 if `string` contains no key digits.
+///
+/// # Examples
+/// The string `A.#B` references 2 key digits, while `A.#D` references 4.
+#[must_use]
+pub fn count_key_digits(string: &str) -> usize {
+    string
+        .chars()
+        .filter_map(key_digit_value)
+        .max()
+        .map_or(0, |bit| bit + 1)
+}
+
+/// Generates a map from the first `n_digits` key digits to `bool` values from a [`TalosKey`] of
+/// any width.
+///
+/// See [`key_digit`] for the digit referencing each key bit. Digit `i` is mapped to bit
+/// `i % key.n_bits()`, so templates referencing more digits than `key` has bits repeat the key.
+/// For a [`TalosKey::Bits32`] key `seed` and 32 digits, the result is the same as that of
+/// [`gen_char_map`] for `seed`.
+///
+/// # Arguments
+/// * `key` - the key to generate a character map from.
+/// * `n_digits` - the number of key digits to map. Must be at most 256
+///
+/// # Returns
+/// A [`HashMap`] mapping the first `n_digits` key digits to their values.
+#[must_use]
+pub fn gen_key_char_map(key: &TalosKey, n_digits: usize) -> HashMap<char, bool> {
+    (0..n_digits)
+        .map(|digit| (key_digit(digit).unwrap(), key.bit(digit % key.n_bits())))
+        .collect::<HashMap<char, bool>>()
+}
+
 /// Reads `string` as a `bool` table state with characters from `char_map`.
 ///
 /// `string` and `char_map` must meet the following criteria:
@@ -153,6 +246,26 @@ pub fn get_temporal_seed_map(string: &str) -> Vec<Vec<ToroidalMatrixIndex>> {
     result
 }
 
+/// Given a string representing an initial matrix state with key digits for variable values,
+/// returns a vector `X`, where `X\[i\]` is the set of ToroidalMatrixIndices of [`key_digit`]`(i)`
+/// in the string, for each `i` less than `n_bits`.
+///
+/// This generalizes [`get_temporal_seed_map`], which is equivalent when `n_bits` is 32, to
+/// templates referencing more than 32 key bits.
+///
+/// # Arguments
+/// * `string` - the string to find the ToroidalMatrixIndices of key digits in
+/// * `n_bits` - the number of key bits to find. Must be at most 256
+///
+/// # Returns
+/// A vector containing the ToroidalMatrixIndices for each key digit in `string`
+#[must_use]
+pub fn get_key_temporal_seed_map(string: &str, n_bits: usize) -> Vec<Vec<ToroidalMatrixIndex>> {
+    (0..n_bits)
+        .map(|bit| get_char_indices(string, key_digit(bit).unwrap()))
+        .collect()
+}
+
 /// Returns the indices of `character` in `string` as canonical
 /// [`ToroidalMatrixIndices`](talos::matrix::ToroidalMatrixIndex).
 ///
@@ -0,0 +1,232 @@
+// 2025 Steven Chiacchira
+use crate::automata::{AutomatonStep, ToroidalAutomaton};
+use crate::matrix::ToroidalMatrixIndex;
+use std::num::NonZeroUsize;
+
+/// The key digits whose cells are overwritten by a [`SeedingStep`].
+///
+/// Key digits are numbered as in [`key_digit`](crate::parse::key_digit). Selections are
+/// independent of the number of key digits referenced by an initialization matrix, so a single
+/// [`SeedingSchedule`] can seed matrices for keys of any width.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeySlots {
+    /// Every key digit.
+    All,
+    /// Every key digit `i` with `i % step == offset`.
+    Every {
+        /// The first key digit selected.
+        offset: usize,
+        /// The distance between selected key digits.
+        step: NonZeroUsize,
+    },
+    /// An explicit list of key digits. Digits not referenced by the initialization matrix are
+    /// ignored.
+    Only(Vec<usize>),
+}
+
+impl KeySlots {
+    /// Returns the selected key digits among the first `n_slots`.
+    ///
+    /// # Arguments
+    /// * `n_slots` - the number of key digits referenced by the initialization matrix
+    ///
+    /// # Returns
+    /// The selected key digits in ascending order, or in the given order for
+    /// [`KeySlots::Only`].
+    #[must_use]
+    pub fn select(&self, n_slots: usize) -> Vec<usize> {
+        match self {
+            Self::All => (0..n_slots).collect(),
+            Self::Every { offset, step } => (*offset..n_slots).step_by(step.get()).collect(),
+            Self::Only(slots) => slots
+                .iter()
+                .copied()
+                .filter(|slot| *slot < n_slots)
+                .collect(),
+        }
+    }
+}
+
+/// A single injection of key bits into an automaton during temporal seeding.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeedingStep {
+    /// The number of generations to simulate before the injection.
+    pub generations: u32,
+    /// The key digits whose cells are overwritten with their key bit.
+    pub slots: KeySlots,
+}
+
+/// A schedule for temporal seeding, as proposed in RFC-0 section 6.1 and described in RFC-1
+/// section 2.1.
+///
+/// Seeding follows the steps in order, simulating each step's generations and then overwriting
+/// the cells of its key digits, before simulating `final_generations` more generations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeedingSchedule {
+    /// The injections to perform, in order.
+    pub steps: Vec<SeedingStep>,
+    /// The number of generations to simulate after the final injection.
+    pub final_generations: u32,
+}
+
+impl SeedingSchedule {
+    /// Returns the schedule used by the Talos protocol: 8 generations, an injection of every key
+    /// digit, then 8 more generations.
+    ///
+    /// # Returns
+    /// The schedule of the RFC-1 protocol.
+    #[must_use]
+    pub fn rfc1() -> Self {
+        Self {
+            steps: vec![SeedingStep {
+                generations: 8,
+                slots: KeySlots::All,
+            }],
+            final_generations: 8,
+        }
+    }
+
+    /// Returns a schedule smearing the injection of key digits across `n_steps` injections,
+    /// `generations` generations apart.
+    ///
+    /// Step `j` injects the key digits `i` with `i % n_steps == j`, so that consecutive key bits
+    /// are seeded at different generations. With `n_steps` equal to the number of key digits,
+    /// each key bit is injected on its own, as in the prose of RFC-1 section 2.1.
+    ///
+    /// # Arguments
+    /// * `n_steps` - the number of injections
+    /// * `generations` - the number of generations before each injection and after the last
+    ///
+    /// # Returns
+    /// The created `SeedingSchedule`.
+    #[must_use]
+    pub fn smeared(n_steps: NonZeroUsize, generations: u32) -> Self {
+        Self {
+            steps: (0..n_steps.get())
+                .map(|offset| SeedingStep {
+                    generations,
+                    slots: KeySlots::Every {
+                        offset,
+                        step: n_steps,
+                    },
+                })
+                .collect(),
+            final_generations: generations,
+        }
+    }
+
+    /// Returns the total number of generations simulated by the schedule.
+    ///
+    /// # Returns
+    /// The number of generations simulated by [`seed_automaton`], or `None` if it does not fit in
+    /// a `u32`.
+    #[must_use]
+    pub fn n_generations(&self) -> Option<u32> {
+        self.steps
+            .iter()
+            .try_fold(self.final_generations, |total, step| {
+                total.checked_add(step.generations)
+            })
+    }
+}
+
+impl Default for SeedingSchedule {
+    fn default() -> Self {
+        Self::rfc1()
+    }
+}
+
+/// Performs temporal seeding of `automaton` following `schedule`.
+///
+/// At each step, the cells `seed_positions[i]` of each selected key digit `i` are set to
+/// `bit(i)`.
+///
+/// # Arguments
+/// * `automaton` - the `ToroidalAutomaton` to be seeded
+/// * `schedule` - the schedule of generations and injections to follow
+/// * `seed_positions` - a vector containing the ToroidalMatrixIndices of each key digit.
+///   `seed_positions[i]` contains the ToroidalMatrixIndices in `automaton` referencing key digit
+///   `i`
+/// * `bit` - returns the value to seed key digit `i` with
+pub fn seed_automaton<T: AutomatonStep + Clone>(
+    automaton: &mut ToroidalAutomaton<T>,
+    schedule: &SeedingSchedule,
+    seed_positions: &[Vec<ToroidalMatrixIndex>],
+    bit: impl Fn(usize) -> bool,
+) {
+    for step in &schedule.steps {
+        automaton.iter_rule(step.generations);
+        for slot in step.slots.select(seed_positions.len()) {
+            let overwritten_value = bit(slot);
+            for matrix_idx in &seed_positions[slot] {
+                automaton.set_state(matrix_idx, overwritten_value);
+            }
+        }
+    }
+    automaton.iter_rule(schedule.final_generations);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nonzero(n: usize) -> NonZeroUsize {
+        NonZeroUsize::new(n).unwrap()
+    }
+
+    #[test]
+    fn test_key_slots_select() {
+        assert_eq!(KeySlots::All.select(4), vec![0, 1, 2, 3]);
+        assert_eq!(
+            KeySlots::Every {
+                offset: 1,
+                step: nonzero(3)
+            }
+            .select(8),
+            vec![1, 4, 7]
+        );
+        assert_eq!(
+            KeySlots::Every {
+                offset: 0,
+                step: NonZeroUsize::MIN
+            }
+            .select(4),
+            vec![0, 1, 2, 3]
+        );
+        assert_eq!(KeySlots::Only(vec![5, 2, 40]).select(32), vec![5, 2]);
+    }
+
+    #[test]
+    fn test_schedule_generations() {
+        assert_eq!(SeedingSchedule::rfc1().n_generations(), Some(16));
+        assert_eq!(
+            SeedingSchedule::smeared(nonzero(32), 8).n_generations(),
+            Some(264)
+        );
+        assert_eq!(
+            SeedingSchedule::smeared(nonzero(2), u32::MAX / 3).n_generations(),
+            Some(u32::MAX / 3 * 3)
+        );
+        assert_eq!(
+            SeedingSchedule::smeared(nonzero(3), u32::MAX / 3).n_generations(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_smeared_covers_every_slot_once() {
+        for n_steps in [1, 5, 32] {
+            let schedule = SeedingSchedule::smeared(nonzero(n_steps), 2);
+            assert_eq!(schedule.steps.len(), n_steps);
+
+            let mut slots: Vec<usize> = schedule
+                .steps
+                .iter()
+                .flat_map(|step| step.slots.select(32))
+                .collect();
+            slots.sort();
+
+            assert_eq!(slots, (0..32).collect::<Vec<_>>());
+        }
+    }
+}
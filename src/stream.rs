@@ -0,0 +1,390 @@
+// 2025 Steven Chiacchira
+use crate::encrypt::{
+    self, BlockGeometry, CipherParams, PaddingStrategy, ProtocolVariant, Scrambler, TalosAutomaton,
+};
+use rand::Rng;
+use std::io::{self, Read, Write};
+use std::mem;
+
+/// Adapter encrypting everything written to it with the Talos algorithm, and writing the
+/// ciphertext to an inner writer.
+///
+/// Plaintext is buffered until a full block of shape `geometry` is available, so at most one
+/// block is held in memory. The final partial block is written by [`TalosEncryptor::finish`] or
+/// [`TalosEncryptor::finish_padded`], one of which must be called once all plaintext has been
+/// written.
+///
+/// The ciphertext is the same as that of [`encrypt_message`](encrypt::encrypt_message) given the
+/// same automata. Ciphertext the inner writer fails to accept is kept and written again by the
+/// next call to [`write`](Write::write), [`flush`](Write::flush) or [`TalosEncryptor::finish`],
+/// so a failed write never loses a block.
+pub struct TalosEncryptor<W: Write> {
+    /// The writer receiving the ciphertext.
+    inner: W,
+    /// The shape of the encryption blocks.
+    geometry: BlockGeometry,
+    /// The matrix scrambling algorithm to apply.
+    scrambler: Scrambler,
+    /// The number of generations to iterate both automata before each block.
+    iters_per_block: u32,
+    /// The automaton used for shifting.
+    shift_automaton: TalosAutomaton,
+    /// The automaton used for scrambling.
+    transpose_automaton: TalosAutomaton,
+    /// Plaintext which does not yet fill a block.
+    block: Vec<u8>,
+    /// Ciphertext not yet accepted by the inner writer.
+    pending: Vec<u8>,
+}
+
+impl<W: Write> TalosEncryptor<W> {
+    /// Creates a new `TalosEncryptor` writing ciphertext to `inner`.
+    ///
+    /// <div class="warning">
+    /// *DOES NOT* perform the temporal seeding as defined in RFC-1 section 2.1.
+    /// Matrix initialization is performed by other methods. See
+    /// [`get_shift_transpose_automata_with_params`](encrypt::get_shift_transpose_automata_with_params).
+    /// </div>
+    ///
+    /// # Arguments
+    /// * `inner` - the writer to write ciphertext to
+    /// * `geometry` - the shape of the encryption blocks. Must match the shape of both automata
+    /// * `variant` - the protocol variant selecting the matrix scrambling algorithm
+    /// * `shift_automaton` - the initial automaton to be used for shifting during encryption
+    /// * `transpose_automaton` - the initial automaton to be used for scrambling during
+    ///   encryption
+    ///
+    /// # Returns
+    /// The created `TalosEncryptor`.
+    #[must_use]
+    pub fn new(
+        inner: W,
+        geometry: &BlockGeometry,
+        variant: ProtocolVariant,
+        shift_automaton: TalosAutomaton,
+        transpose_automaton: TalosAutomaton,
+    ) -> Self {
+        Self::with_params(
+            inner,
+            geometry,
+            &CipherParams::for_variant(variant),
+            shift_automaton,
+            transpose_automaton,
+        )
+    }
+
+    /// Creates a new `TalosEncryptor` using the scrambling algorithm and iterations per block of
+    /// `params`.
+    ///
+    /// The output is the same as that of [`encrypt_message_with_params`](encrypt::encrypt_message_with_params)
+    /// given the same automata.
+    ///
+    /// # Arguments
+    /// * `inner` - the writer to write ciphertext to
+    /// * `geometry` - the shape of the encryption blocks. Must match the shape of both automata
+    /// * `params` - the cipher parameters the automata were prepared with
+    /// * `shift_automaton` - the initial automaton to be used for shifting during encryption
+    /// * `transpose_automaton` - the initial automaton to be used for scrambling during
+    ///   encryption
+    ///
+    /// # Returns
+    /// The created `TalosEncryptor`.
+    #[must_use]
+    pub fn with_params(
+        inner: W,
+        geometry: &BlockGeometry,
+        params: &CipherParams,
+        shift_automaton: TalosAutomaton,
+        transpose_automaton: TalosAutomaton,
+    ) -> Self {
+        Self {
+            inner,
+            geometry: *geometry,
+            scrambler: params.scrambler,
+            iters_per_block: params.iters_per_block,
+            shift_automaton,
+            transpose_automaton,
+            block: Vec::with_capacity(geometry.block_bytes()),
+            pending: Vec::with_capacity(geometry.block_bytes()),
+        }
+    }
+
+    /// Encrypts the final partial block, filling it with zeros like
+    /// [`encrypt_message`](encrypt::encrypt_message), and flushes the inner writer.
+    ///
+    /// # Returns
+    /// The inner writer, or the error which occurred while writing to it.
+    pub fn finish(mut self) -> io::Result<W> {
+        if !self.block.is_empty() {
+            self.block.resize(self.geometry.block_bytes(), 0);
+            self.encrypt_block();
+        }
+        self.write_pending()?;
+        self.inner.flush()?;
+
+        Ok(self.inner)
+    }
+
+    /// Pads the plaintext with [`pad_message_with`](encrypt::pad_message_with), encrypts the
+    /// final blocks, and flushes the inner writer.
+    ///
+    /// # Arguments
+    /// * `padding` - the strategy used to fill the final block
+    /// * `rng` - the random number generator used by [`PaddingStrategy::Random`] and
+    ///   [`PaddingStrategy::Corpus`]
+    ///
+    /// # Returns
+    /// The inner writer, or the error which occurred while writing to it. Fails with
+    /// [`io::ErrorKind::InvalidInput`] if `padding` is [`PaddingStrategy::Pkcs7`] and a block
+    /// contains more than [`MAX_PKCS7_BLOCK_BYTES`](encrypt::MAX_PKCS7_BLOCK_BYTES) bytes.
+    pub fn finish_padded<R: Rng + ?Sized>(
+        mut self,
+        padding: &PaddingStrategy,
+        rng: &mut R,
+    ) -> io::Result<W> {
+        // padding only depends on the length of the final partial block
+        let padded =
+            encrypt::pad_message_with(mem::take(&mut self.block), padding, &self.geometry, rng)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+        for block in padded.chunks(self.geometry.block_bytes()) {
+            self.block = block.to_vec();
+            self.encrypt_block();
+        }
+        self.write_pending()?;
+        self.inner.flush()?;
+
+        Ok(self.inner)
+    }
+
+    /// Encrypts the buffered block and queues its ciphertext for the inner writer.
+    fn encrypt_block(&mut self) {
+        let ciphertext = encrypt::encrypt_block(
+            mem::take(&mut self.block),
+            &self.geometry,
+            self.scrambler,
+            self.iters_per_block,
+            &mut self.shift_automaton,
+            &mut self.transpose_automaton,
+        );
+
+        self.pending.extend(ciphertext);
+    }
+
+    /// Writes the queued ciphertext to the inner writer. Ciphertext which could not be written
+    /// stays queued.
+    fn write_pending(&mut self) -> io::Result<()> {
+        while !self.pending.is_empty() {
+            match self.inner.write(&self.pending) {
+                Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+                Ok(n_bytes) => {
+                    self.pending.drain(..n_bytes);
+                }
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for TalosEncryptor<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // fail before consuming `buf`, so that an error never loses plaintext
+        self.write_pending()?;
+
+        let n_bytes = buf
+            .len()
+            .min(self.geometry.block_bytes() - self.block.len());
+        self.block.extend_from_slice(&buf[..n_bytes]);
+        if self.block.len() == self.geometry.block_bytes() {
+            self.encrypt_block();
+            // `buf` has been consumed, so errors are reported by the next call instead
+            let _ = self.write_pending();
+        }
+
+        Ok(n_bytes)
+    }
+
+    /// Writes any queued ciphertext and flushes the inner writer. Plaintext which does not fill
+    /// a block stays buffered until [`TalosEncryptor::finish`] is called.
+    fn flush(&mut self) -> io::Result<()> {
+        self.write_pending()?;
+        self.inner.flush()
+    }
+}
+
+/// Adapter decrypting ciphertext read from an inner reader with the Talos algorithm.
+///
+/// Ciphertext is read and decrypted one block of shape `geometry` at a time, so at most one
+/// block is held in memory. The decrypted output includes any padding added to the plaintext.
+/// Reading fails with [`io::ErrorKind::UnexpectedEof`] if the ciphertext ends within a block.
+///
+/// The plaintext is the same as that of [`decrypt_message`](encrypt::decrypt_message) given the
+/// same automata.
+pub struct TalosDecryptor<R: Read> {
+    /// The reader providing the ciphertext.
+    inner: R,
+    /// The shape of the encryption blocks.
+    geometry: BlockGeometry,
+    /// The matrix scrambling algorithm to invert.
+    scrambler: Scrambler,
+    /// The number of generations to iterate both automata before each block.
+    iters_per_block: u32,
+    /// The automaton used for shifting.
+    shift_automaton: TalosAutomaton,
+    /// The automaton used for unscrambling.
+    transpose_automaton: TalosAutomaton,
+    /// The most recently decrypted block.
+    block: Vec<u8>,
+    /// The number of bytes of `block` already read.
+    pos: usize,
+}
+
+impl<R: Read> TalosDecryptor<R> {
+    /// Creates a new `TalosDecryptor` reading ciphertext from `inner`.
+    ///
+    /// <div class="warning">
+    /// *DOES NOT* perform the temporal seeding as defined in RFC-1 section 2.1.
+    /// Matrix initialization is performed by other methods. See
+    /// [`get_shift_transpose_automata_with_params`](encrypt::get_shift_transpose_automata_with_params).
+    /// </div>
+    ///
+    /// # Arguments
+    /// * `inner` - the reader to read ciphertext from
+    /// * `geometry` - the shape of the encryption blocks. Must match the shape of both automata
+    /// * `variant` - the protocol variant selecting the matrix scrambling algorithm
+    /// * `shift_automaton` - the initial automaton to be used for shifting during decryption
+    /// * `transpose_automaton` - the initial automaton to be used for unscrambling during
+    ///   decryption
+    ///
+    /// # Returns
+    /// The created `TalosDecryptor`.
+    #[must_use]
+    pub fn new(
+        inner: R,
+        geometry: &BlockGeometry,
+        variant: ProtocolVariant,
+        shift_automaton: TalosAutomaton,
+        transpose_automaton: TalosAutomaton,
+    ) -> Self {
+        Self::with_params(
+            inner,
+            geometry,
+            &CipherParams::for_variant(variant),
+            shift_automaton,
+            transpose_automaton,
+        )
+    }
+
+    /// Creates a new `TalosDecryptor` using the scrambling algorithm and iterations per block of
+    /// `params`.
+    ///
+    /// The output is the same as that of [`decrypt_message_with_params`](encrypt::decrypt_message_with_params)
+    /// given the same automata.
+    ///
+    /// # Arguments
+    /// * `inner` - the reader to read ciphertext from
+    /// * `geometry` - the shape of the encryption blocks. Must match the shape of both automata
+    /// * `params` - the cipher parameters the automata were prepared with
+    /// * `shift_automaton` - the initial automaton to be used for shifting during decryption
+    /// * `transpose_automaton` - the initial automaton to be used for unscrambling during
+    ///   decryption
+    ///
+    /// # Returns
+    /// The created `TalosDecryptor`.
+    #[must_use]
+    pub fn with_params(
+        inner: R,
+        geometry: &BlockGeometry,
+        params: &CipherParams,
+        shift_automaton: TalosAutomaton,
+        transpose_automaton: TalosAutomaton,
+    ) -> Self {
+        Self {
+            inner,
+            geometry: *geometry,
+            scrambler: params.scrambler,
+            iters_per_block: params.iters_per_block,
+            shift_automaton,
+            transpose_automaton,
+            block: Vec::new(),
+            pos: 0,
+        }
+    }
+
+    /// Returns the inner reader. Decrypted bytes which have not been read are discarded.
+    ///
+    /// # Returns
+    /// The inner reader.
+    #[must_use]
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Reads and decrypts the next block from the inner reader.
+    ///
+    /// # Returns
+    /// `false` if the inner reader was already exhausted, `true` otherwise.
+    fn read_block(&mut self) -> io::Result<bool> {
+        let block_bytes = self.geometry.block_bytes();
+        let mut ciphertext = vec![0_u8; block_bytes];
+        let n_read = read_full(&mut self.inner, &mut ciphertext)?;
+        if n_read == 0 {
+            return Ok(false);
+        }
+        if n_read < block_bytes {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "ciphertext ends within a block",
+            ));
+        }
+
+        self.block = encrypt::decrypt_block(
+            ciphertext,
+            &self.geometry,
+            self.scrambler,
+            self.iters_per_block,
+            &mut self.shift_automaton,
+            &mut self.transpose_automaton,
+        );
+        self.pos = 0;
+        Ok(true)
+    }
+}
+
+impl<R: Read> Read for TalosDecryptor<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pos == self.block.len() && !self.read_block()? {
+            return Ok(0);
+        }
+
+        let n_bytes = buf.len().min(self.block.len() - self.pos);
+        buf[..n_bytes].copy_from_slice(&self.block[self.pos..self.pos + n_bytes]);
+        self.pos += n_bytes;
+
+        Ok(n_bytes)
+    }
+}
+
+/// Reads from `input` until `buf` is full or `input` is exhausted.
+///
+/// # Arguments
+/// * `input` - the reader to read from
+/// * `buf` - the buffer to fill
+///
+/// # Returns
+/// The number of bytes read, which is less than `buf.len()` only if `input` was exhausted.
+pub(crate) fn read_full<R: Read + ?Sized>(input: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    let mut n_read = 0;
+    while n_read < buf.len() {
+        match input.read(&mut buf[n_read..]) {
+            Ok(0) => break,
+            Ok(n_bytes) => n_read += n_bytes,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+
+    Ok(n_read)
+}
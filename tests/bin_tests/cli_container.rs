@@ -0,0 +1,739 @@
+// 2025 Steven Chiacchira
+use assert_cmd::cargo;
+use assert_fs::{fixture::PathChild, TempDir};
+use std::fs;
+use talos::key::TalosKey;
+
+#[test]
+fn round_trip() {
+    let message_file = concat!(env!("CARGO_MANIFEST_DIR"), "/data/tests/text_01.txt");
+    let expected_message =
+        fs::read(message_file).expect("Could not find plaintext in data directory");
+
+    let file_dir = TempDir::new().unwrap();
+    let encrypted_file = file_dir.child("output.enc");
+    let decrypted_file = file_dir.child("output.txt");
+
+    let mut command = cargo::cargo_bin_cmd!("encrypt");
+    command
+        .arg("--key")
+        .arg("42")
+        .arg(message_file)
+        .arg(encrypted_file.path());
+    command.assert().success();
+
+    let mut command = cargo::cargo_bin_cmd!("decrypt");
+    command
+        .arg("--key")
+        .arg("42")
+        .arg(encrypted_file.path())
+        .arg(decrypted_file.path());
+    command
+        .assert()
+        .success()
+        .stderr(predicates::str::contains("Finished"));
+
+    let decrypted_message = fs::read(&decrypted_file).unwrap();
+    assert_eq!(expected_message, decrypted_message);
+    // the plaintext is staged in a file only readable by its owner
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(&decrypted_file).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    file_dir.close().unwrap();
+}
+
+#[test]
+fn in_place() {
+    let message_file = concat!(env!("CARGO_MANIFEST_DIR"), "/data/tests/text_01.txt");
+    let expected_message =
+        fs::read(message_file).expect("Could not find plaintext in data directory");
+
+    let file_dir = TempDir::new().unwrap();
+    let file = file_dir.child("message");
+    fs::write(&file, &expected_message).unwrap();
+
+    for raw in [false, true] {
+        let mut command = cargo::cargo_bin_cmd!("encrypt");
+        command
+            .arg("--key")
+            .arg("42")
+            .arg(file.path())
+            .arg(file.path());
+        if raw {
+            command.arg("--raw");
+        }
+        command.assert().success();
+        assert_ne!(fs::read(&file).unwrap(), expected_message);
+
+        let mut command = cargo::cargo_bin_cmd!("decrypt");
+        command
+            .arg("--key")
+            .arg("42")
+            .arg(file.path())
+            .arg(file.path());
+        if raw {
+            command.arg("--raw");
+        }
+        command.assert().success();
+
+        let decrypted_message = fs::read(&file).unwrap();
+        assert_eq!(
+            expected_message,
+            decrypted_message[..expected_message.len()]
+        );
+        fs::write(&file, &expected_message).unwrap();
+    }
+
+    // a failed run leaves the input in place and no temporary file behind
+    let mut command = cargo::cargo_bin_cmd!("encrypt");
+    command
+        .arg("--key")
+        .arg("42")
+        .arg(file.path())
+        .arg(file.path());
+    command.assert().success();
+    let mut tampered = fs::read(&file).unwrap();
+    *tampered.last_mut().unwrap() ^= 1;
+    fs::write(&file, &tampered).unwrap();
+
+    let mut command = cargo::cargo_bin_cmd!("decrypt");
+    command
+        .arg("--key")
+        .arg("42")
+        .arg(file.path())
+        .arg(file.path());
+    command.assert().failure();
+    assert_eq!(fs::read(&file).unwrap(), tampered);
+    assert_eq!(fs::read_dir(&file_dir).unwrap().count(), 1);
+
+    file_dir.close().unwrap();
+}
+
+#[test]
+fn padding_strategies() {
+    let message_file = concat!(env!("CARGO_MANIFEST_DIR"), "/data/tests/text_01.txt");
+    let expected_message =
+        fs::read(message_file).expect("Could not find plaintext in data directory");
+
+    for padding in ["pkcs7", "zeros", "random", "corpus"] {
+        let file_dir = TempDir::new().unwrap();
+        let encrypted_file = file_dir.child("output.enc");
+        let decrypted_file = file_dir.child("output.txt");
+
+        let mut command = cargo::cargo_bin_cmd!("encrypt");
+        command
+            .arg("--key")
+            .arg("42")
+            .arg("--padding")
+            .arg(padding)
+            .arg("--corpus")
+            .arg(message_file)
+            .arg(message_file)
+            .arg(encrypted_file.path());
+        command.assert().success();
+
+        let mut command = cargo::cargo_bin_cmd!("decrypt");
+        command
+            .arg("--key")
+            .arg("42")
+            .arg(encrypted_file.path())
+            .arg(decrypted_file.path());
+        command.assert().success();
+
+        let decrypted_message = fs::read(&decrypted_file).unwrap();
+        assert_eq!(expected_message, decrypted_message);
+
+        file_dir.close().unwrap();
+    }
+}
+
+#[test]
+fn corpus_required() {
+    let message_file = concat!(env!("CARGO_MANIFEST_DIR"), "/data/tests/text_01.txt");
+
+    let file_dir = TempDir::new().unwrap();
+    let encrypted_file = file_dir.child("output.enc");
+
+    let mut command = cargo::cargo_bin_cmd!("encrypt");
+    command
+        .arg("--key")
+        .arg("42")
+        .arg("--padding")
+        .arg("corpus")
+        .arg(message_file)
+        .arg(encrypted_file.path());
+    command
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("--corpus"));
+
+    file_dir.close().unwrap();
+}
+
+#[test]
+fn large_block_padding() {
+    let message_file = concat!(env!("CARGO_MANIFEST_DIR"), "/data/tests/text_01.txt");
+
+    let file_dir = TempDir::new().unwrap();
+    let encrypted_file = file_dir.child("output.enc");
+
+    // 64x64 blocks are too long for PKCS#7 padding, so they are padded with zeros by default
+    let mut command = cargo::cargo_bin_cmd!("encrypt");
+    command
+        .arg("--key")
+        .arg("42")
+        .arg("--block-dim")
+        .arg("64")
+        .arg(message_file)
+        .arg(encrypted_file.path());
+    command.assert().success();
+
+    let mut command = cargo::cargo_bin_cmd!("encrypt");
+    command
+        .arg("--key")
+        .arg("42")
+        .arg("--block-dim")
+        .arg("64")
+        .arg("--padding")
+        .arg("pkcs7")
+        .arg(message_file)
+        .arg(file_dir.child("pkcs7.enc").path());
+    command
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("BlockTooLarge"));
+
+    file_dir.close().unwrap();
+}
+
+#[test]
+fn wrong_key() {
+    let message_file = concat!(env!("CARGO_MANIFEST_DIR"), "/data/tests/text_01.txt");
+
+    let file_dir = TempDir::new().unwrap();
+    let encrypted_file = file_dir.child("output.enc");
+    let decrypted_file = file_dir.child("output.txt");
+
+    let mut command = cargo::cargo_bin_cmd!("encrypt");
+    command
+        .arg("--key")
+        .arg("42")
+        .arg(message_file)
+        .arg(encrypted_file.path());
+    command.assert().success();
+
+    let mut command = cargo::cargo_bin_cmd!("decrypt");
+    command
+        .arg("--key")
+        .arg("43")
+        .arg(encrypted_file.path())
+        .arg(decrypted_file.path());
+    command
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("WrongKey"));
+    // the wrong key is detected before any output is created
+    assert_eq!(fs::read_dir(file_dir.path()).unwrap().count(), 1);
+
+    // a tampered container is only detected by its authentication tag
+    let mut tampered = fs::read(&encrypted_file).unwrap();
+    let last = tampered.len() - 1;
+    tampered[last] ^= 1;
+    fs::write(&encrypted_file, tampered).unwrap();
+    let mut command = cargo::cargo_bin_cmd!("decrypt");
+    command
+        .arg("--key")
+        .arg("42")
+        .arg(encrypted_file.path())
+        .arg(decrypted_file.path());
+    command
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("AuthenticationFailed"));
+
+    assert!(!decrypted_file.path().exists());
+    // unverified plaintext is not left behind
+    assert_eq!(fs::read_dir(file_dir.path()).unwrap().count(), 1);
+
+    file_dir.close().unwrap();
+}
+
+#[test]
+fn unauthenticated() {
+    let message_file = concat!(env!("CARGO_MANIFEST_DIR"), "/data/tests/text_01.txt");
+    let expected_message =
+        fs::read(message_file).expect("Could not find plaintext in data directory");
+
+    let file_dir = TempDir::new().unwrap();
+    let encrypted_file = file_dir.child("output.enc");
+    let decrypted_file = file_dir.child("output.txt");
+
+    let mut command = cargo::cargo_bin_cmd!("encrypt");
+    command
+        .arg("--key")
+        .arg("42")
+        .arg("--no-auth")
+        .arg(message_file)
+        .arg(encrypted_file.path());
+    command.assert().success();
+
+    let mut command = cargo::cargo_bin_cmd!("decrypt");
+    command
+        .arg("--key")
+        .arg("42")
+        .arg(encrypted_file.path())
+        .arg(decrypted_file.path());
+    command
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Unauthenticated"));
+
+    let mut command = cargo::cargo_bin_cmd!("decrypt");
+    command
+        .arg("--key")
+        .arg("42")
+        .arg("--allow-unauthenticated")
+        .arg(encrypted_file.path())
+        .arg(decrypted_file.path());
+    command.assert().success();
+
+    let decrypted_message = fs::read(&decrypted_file).unwrap();
+    assert_eq!(expected_message, decrypted_message);
+
+    file_dir.close().unwrap();
+}
+
+#[test]
+fn unsupported_version() {
+    let message_file = concat!(env!("CARGO_MANIFEST_DIR"), "/data/tests/text_01.txt");
+
+    let file_dir = TempDir::new().unwrap();
+    let encrypted_file = file_dir.child("output.enc");
+    let decrypted_file = file_dir.child("output.txt");
+
+    let mut command = cargo::cargo_bin_cmd!("encrypt");
+    command
+        .arg("--key")
+        .arg("42")
+        .arg(message_file)
+        .arg(encrypted_file.path());
+    command.assert().success();
+
+    let mut container = fs::read(&encrypted_file).unwrap();
+    container[5] = u8::MAX;
+    fs::write(&encrypted_file, container).unwrap();
+
+    let mut command = cargo::cargo_bin_cmd!("decrypt");
+    command
+        .arg("--key")
+        .arg("42")
+        .arg(encrypted_file.path())
+        .arg(decrypted_file.path());
+    command
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("UnsupportedVersion(255)"));
+
+    assert!(!decrypted_file.path().exists());
+
+    file_dir.close().unwrap();
+}
+
+#[test]
+fn raw_ciphertext_rejected() {
+    let encrypted_file = concat!(env!("CARGO_MANIFEST_DIR"), "/data/tests/text_01_k0.enc");
+
+    let file_dir = TempDir::new().unwrap();
+    let decrypted_file = file_dir.child("output.txt");
+
+    let mut command = cargo::cargo_bin_cmd!("decrypt");
+    command
+        .arg("--key")
+        .arg("0")
+        .arg(encrypted_file)
+        .arg(decrypted_file.path());
+    command
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("BadMagic"));
+
+    file_dir.close().unwrap();
+}
+
+#[test]
+fn wide_keys() {
+    let message_file = concat!(env!("CARGO_MANIFEST_DIR"), "/data/tests/text_01.txt");
+    let expected_message =
+        fs::read(message_file).expect("Could not find plaintext in data directory");
+
+    for key_bits in ["128", "256"] {
+        let file_dir = TempDir::new().unwrap();
+        let encrypted_file = file_dir.child("output.enc");
+        let decrypted_file = file_dir.child("output.txt");
+
+        let mut command = cargo::cargo_bin_cmd!("encrypt");
+        command
+            .arg("--key")
+            .arg("Foo")
+            .arg("--key-bits")
+            .arg(key_bits)
+            .arg("--kdf-iterations")
+            .arg("1000")
+            .arg(message_file)
+            .arg(encrypted_file.path());
+        command.assert().success();
+
+        // the key width is read from the container header
+        let mut command = cargo::cargo_bin_cmd!("decrypt");
+        command
+            .arg("--key")
+            .arg("Foo")
+            .arg(encrypted_file.path())
+            .arg(decrypted_file.path());
+        command.assert().success();
+
+        let decrypted_message = fs::read(&decrypted_file).unwrap();
+        assert_eq!(expected_message, decrypted_message);
+
+        file_dir.close().unwrap();
+    }
+}
+
+#[test]
+fn rotating_variant() {
+    let message_file = concat!(env!("CARGO_MANIFEST_DIR"), "/data/tests/text_01.txt");
+    let expected_message =
+        fs::read(message_file).expect("Could not find plaintext in data directory");
+
+    let file_dir = TempDir::new().unwrap();
+    let encrypted_file = file_dir.child("output.enc");
+    let decrypted_file = file_dir.child("output.txt");
+
+    let mut command = cargo::cargo_bin_cmd!("encrypt");
+    command
+        .arg("--key")
+        .arg("42")
+        .arg("--variant")
+        .arg("rotating")
+        .arg(message_file)
+        .arg(encrypted_file.path());
+    command.assert().success();
+
+    // the protocol variant is read from the container header
+    let mut command = cargo::cargo_bin_cmd!("decrypt");
+    command
+        .arg("--key")
+        .arg("42")
+        .arg(encrypted_file.path())
+        .arg(decrypted_file.path());
+    command.assert().success();
+
+    let decrypted_message = fs::read(&decrypted_file).unwrap();
+    assert_eq!(expected_message, decrypted_message);
+
+    file_dir.close().unwrap();
+}
+
+#[test]
+fn numeric_wide_key() {
+    let message_file = concat!(env!("CARGO_MANIFEST_DIR"), "/data/tests/text_01.txt");
+
+    let file_dir = TempDir::new().unwrap();
+    let encrypted_file = file_dir.child("output.enc");
+
+    let mut command = cargo::cargo_bin_cmd!("encrypt");
+    command
+        .arg("--key")
+        .arg("42")
+        .arg("--key-bits")
+        .arg("128")
+        .arg(message_file)
+        .arg(encrypted_file.path());
+    command
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("NumericKeyTooNarrow"));
+    assert!(!encrypted_file.path().exists());
+
+    file_dir.close().unwrap();
+}
+
+#[test]
+fn stdin_stdout() {
+    let message_file = concat!(env!("CARGO_MANIFEST_DIR"), "/data/tests/text_01.txt");
+    let expected_message =
+        fs::read(message_file).expect("Could not find plaintext in data directory");
+    // not valid UTF-8, so that the output must be passed through unchanged
+    let binary_message: Vec<u8> = (0..=255).rev().chain(0..=255).collect();
+
+    for message in [expected_message, binary_message] {
+        let mut command = cargo::cargo_bin_cmd!("encrypt");
+        command.arg("--key").arg("42").arg("-").arg("-");
+        let output = command.write_stdin(message.clone()).output().unwrap();
+        assert!(output.status.success());
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains("Key fingerprint"));
+        assert!(stderr.contains("Finished"));
+        let ciphertext = output.stdout;
+
+        let mut command = cargo::cargo_bin_cmd!("decrypt");
+        command.arg("--key").arg("42").arg("-").arg("-");
+        let output = command.write_stdin(ciphertext).output().unwrap();
+        assert!(output.status.success());
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains("Key fingerprint"));
+        assert!(stderr.contains("Finished"));
+        assert_eq!(output.stdout, message);
+    }
+}
+
+#[test]
+fn stdin_to_file() {
+    let message_file = concat!(env!("CARGO_MANIFEST_DIR"), "/data/tests/text_01.txt");
+    let expected_message =
+        fs::read(message_file).expect("Could not find plaintext in data directory");
+
+    let file_dir = TempDir::new().unwrap();
+    let encrypted_file = file_dir.child("output.enc");
+
+    let mut command = cargo::cargo_bin_cmd!("encrypt");
+    command
+        .arg("--key")
+        .arg("42")
+        .arg("-")
+        .arg(encrypted_file.path())
+        .write_stdin(expected_message.clone());
+    command.assert().success().stdout("");
+
+    // a container with its length in the trailer can be read from a file
+    let mut command = cargo::cargo_bin_cmd!("decrypt");
+    command
+        .arg("--key")
+        .arg("42")
+        .arg(encrypted_file.path())
+        .arg("-");
+    command.assert().success().stdout(expected_message.clone());
+
+    let mut command = cargo::cargo_bin_cmd!("decrypt");
+    command
+        .arg("--key")
+        .arg("43")
+        .arg(encrypted_file.path())
+        .arg("-");
+    command
+        .assert()
+        .failure()
+        .stdout("")
+        .stderr(predicates::str::contains("WrongKey"));
+
+    // the tag of a file is verified before any plaintext is written to stdout
+    let mut tampered = fs::read(&encrypted_file).unwrap();
+    let middle = tampered.len() / 2;
+    tampered[middle] ^= 1;
+    fs::write(&encrypted_file, tampered).unwrap();
+    let mut command = cargo::cargo_bin_cmd!("decrypt");
+    command
+        .arg("--key")
+        .arg("42")
+        .arg(encrypted_file.path())
+        .arg("-");
+    command
+        .assert()
+        .failure()
+        .stdout("")
+        .stderr(predicates::str::contains("AuthenticationFailed"));
+
+    file_dir.close().unwrap();
+}
+
+#[test]
+fn passphrase_kdf() {
+    let message_file = concat!(env!("CARGO_MANIFEST_DIR"), "/data/tests/text_01.txt");
+    let expected_message =
+        fs::read(message_file).expect("Could not find plaintext in data directory");
+
+    let file_dir = TempDir::new().unwrap();
+    let mut keys = Vec::new();
+    for (name, kdf_args) in [
+        ("salted_1.enc", vec!["--kdf-iterations", "1000"]),
+        ("salted_2.enc", vec!["--kdf-iterations", "1000"]),
+        ("legacy.enc", vec!["--legacy-kdf"]),
+    ] {
+        let encrypted_file = file_dir.child(name);
+        let decrypted_file = file_dir.child("output.txt");
+
+        let mut command = cargo::cargo_bin_cmd!("encrypt");
+        command
+            .arg("--key")
+            .arg("Foo")
+            .arg("--emit-key")
+            .args(&kdf_args)
+            .arg(message_file)
+            .arg(encrypted_file.path());
+        let output = command.output().unwrap();
+        assert!(output.status.success());
+        keys.push(
+            String::from_utf8(output.stderr)
+                .unwrap()
+                .lines()
+                .next()
+                .unwrap()
+                .to_owned(),
+        );
+
+        // the salt and iteration count are read from the container header
+        let mut command = cargo::cargo_bin_cmd!("decrypt");
+        command
+            .arg("--key")
+            .arg("Foo")
+            .arg(encrypted_file.path())
+            .arg(decrypted_file.path());
+        command.assert().success();
+        assert_eq!(expected_message, fs::read(&decrypted_file).unwrap());
+    }
+
+    // identical passphrases give different keys with different salts
+    assert_ne!(keys[0], keys[1]);
+    // the legacy derivation matches the headerless ciphertexts of earlier versions
+    assert_eq!(keys[2], "Using key 935837212");
+
+    let mut command = cargo::cargo_bin_cmd!("encrypt");
+    command
+        .arg("--key")
+        .arg("Foo")
+        .arg("--kdf-iterations")
+        .arg("0")
+        .arg(message_file)
+        .arg(file_dir.child("zero.enc").path());
+    command.assert().failure();
+
+    file_dir.close().unwrap();
+}
+
+#[test]
+fn key_sources() {
+    let message_file = concat!(env!("CARGO_MANIFEST_DIR"), "/data/tests/text_01.txt");
+    let expected_message =
+        fs::read(message_file).expect("Could not find plaintext in data directory");
+
+    let file_dir = TempDir::new().unwrap();
+    let hex_key = "000102030405060708090a0b0c0d0e0f";
+    let hex_key_file = file_dir.child("key.hex");
+    fs::write(hex_key_file.path(), format!("{}\n", hex_key)).unwrap();
+    let raw_key_file = file_dir.child("key.bin");
+    fs::write(raw_key_file.path(), (0..16).collect::<Vec<u8>>()).unwrap();
+    let fingerprint = TalosKey::from_bytes(&(0..16).collect::<Vec<u8>>())
+        .unwrap()
+        .fingerprint();
+
+    let hex_key_file = hex_key_file.path().to_str().unwrap();
+    let raw_key_file = raw_key_file.path().to_str().unwrap();
+    // every source of the same key decrypts the others' ciphertexts
+    let sources = [
+        ["--key-hex", hex_key],
+        ["--key-file", hex_key_file],
+        ["--key-file", raw_key_file],
+    ];
+    for encrypt_source in sources {
+        let encrypted_file = file_dir.child("output.enc");
+        let mut command = cargo::cargo_bin_cmd!("encrypt");
+        command
+            .args(encrypt_source)
+            .arg("--key-bits")
+            .arg("128")
+            .arg(message_file)
+            .arg(encrypted_file.path());
+        command
+            .assert()
+            .success()
+            .stderr(predicates::str::contains(fingerprint.as_str()));
+
+        for decrypt_source in sources {
+            let decrypted_file = file_dir.child("output.txt");
+            let mut command = cargo::cargo_bin_cmd!("decrypt");
+            command
+                .args(decrypt_source)
+                .arg(encrypted_file.path())
+                .arg(decrypted_file.path());
+            command.assert().success();
+            assert_eq!(expected_message, fs::read(&decrypted_file).unwrap());
+        }
+    }
+
+    // passphrases from the environment are derived like passphrases given with --key
+    let encrypted_file = file_dir.child("env.enc");
+    let decrypted_file = file_dir.child("env.txt");
+    let mut command = cargo::cargo_bin_cmd!("encrypt");
+    command
+        .arg("--key-env")
+        .arg("TALOS_TEST_PASSPHRASE")
+        .env("TALOS_TEST_PASSPHRASE", "Foo")
+        .arg("--kdf-iterations")
+        .arg("1000")
+        .arg(message_file)
+        .arg(encrypted_file.path());
+    command.assert().success();
+
+    let mut command = cargo::cargo_bin_cmd!("decrypt");
+    command
+        .arg("--key")
+        .arg("Foo")
+        .arg(encrypted_file.path())
+        .arg(decrypted_file.path());
+    command.assert().success();
+    assert_eq!(expected_message, fs::read(&decrypted_file).unwrap());
+
+    file_dir.close().unwrap();
+}
+
+#[test]
+fn invalid_key_sources() {
+    let message_file = concat!(env!("CARGO_MANIFEST_DIR"), "/data/tests/text_01.txt");
+    let file_dir = TempDir::new().unwrap();
+    let encrypted_file = file_dir.child("output.enc");
+    let missing_file = file_dir.child("missing");
+
+    let typo_file = file_dir.child("typo.hex");
+    fs::write(typo_file.path(), "abc\n").unwrap();
+
+    let cases: [(&[&str], &str); 9] = [
+        (&["--key-hex", "0g"], "hexadecimal digits"),
+        // keys which look like hex are not silently used as passphrases
+        (&["-k", "0x1F"], "--key-hex"),
+        (&["-k", "00ff"], "--key-hex"),
+        // nor are key files of hex digits which cannot be decoded used as raw bytes
+        (
+            &["--key-file", typo_file.path().to_str().unwrap()],
+            "InvalidHex",
+        ),
+        (&["--key-hex", "2a00"], "KeyLengthMismatch"),
+        (
+            &["--key", "42", "--key-hex", "2a000000"],
+            "cannot be used with",
+        ),
+        (
+            &["--key-file", missing_file.path().to_str().unwrap()],
+            "UnreadableKeyFile",
+        ),
+        (&["--key-env", "TALOS_TEST_UNSET_VARIABLE"], "MissingEnvVar"),
+        (&["--key-file", message_file], "KeyLengthMismatch"),
+    ];
+    for (args, expected_error) in cases {
+        let mut command = cargo::cargo_bin_cmd!("encrypt");
+        command
+            .args(args)
+            .env_remove("TALOS_TEST_UNSET_VARIABLE")
+            .arg(message_file)
+            .arg(encrypted_file.path());
+        command
+            .assert()
+            .failure()
+            .stderr(predicates::str::contains(expected_error));
+    }
+
+    file_dir.close().unwrap();
+}
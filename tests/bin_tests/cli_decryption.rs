@@ -56,6 +56,7 @@ fn numeric_keys() {
 
         let mut command = cargo::cargo_bin_cmd!("decrypt");
         command
+            .arg("--raw")
             .arg("--key")
             .arg(key.to_string())
             .arg(encrypted_file)
@@ -88,6 +89,7 @@ fn str_keys() {
 
         let mut command = cargo::cargo_bin_cmd!("decrypt");
         command
+            .arg("--raw")
             .arg("--key")
             .arg(key)
             .arg(encrypted_file)
@@ -103,3 +105,59 @@ fn str_keys() {
         output_file_dir.close().unwrap();
     }
 }
+
+#[test]
+fn rfc1_variant() {
+    let expected_file = concat!(env!("CARGO_MANIFEST_DIR"), "/data/tests/text_01.txt");
+    let expected_message =
+        fs::read(expected_file).expect("Could not find plaintext in data directory");
+
+    for key in ["0", "Foo"] {
+        let encrypted_file = env!("CARGO_MANIFEST_DIR").to_owned()
+            + &format!("/data/tests/text_01_rfc1_k{}.enc", key);
+        let encrypted_file = path::Path::new(&encrypted_file);
+
+        let output_file_dir = TempDir::new().unwrap();
+        let output_file = output_file_dir.child("output.txt");
+
+        let mut command = cargo::cargo_bin_cmd!("decrypt");
+        command
+            .arg("--raw")
+            .arg("--variant")
+            .arg("rfc1")
+            .arg("--key")
+            .arg(key)
+            .arg(encrypted_file)
+            .arg(output_file.path());
+        command
+            .assert()
+            .success()
+            .stderr(predicates::str::contains("Finished"));
+
+        let decrypted_message = fs::read(&output_file).unwrap();
+        assert!(expected_message == decrypted_message[..expected_message.len()]);
+
+        output_file_dir.close().unwrap();
+    }
+}
+
+#[test]
+fn variant_requires_raw() {
+    let mut command = cargo::cargo_bin_cmd!("decrypt");
+
+    let message_file = concat!(env!("CARGO_MANIFEST_DIR"), "/data/tests/text_01_k0.enc");
+    let output_file_dir = TempDir::new().unwrap();
+    let output_file = output_file_dir.child("_.txt");
+
+    command
+        .arg("--key")
+        .arg("0")
+        .arg("--variant")
+        .arg("rfc1")
+        .arg(message_file)
+        .arg(output_file.path());
+
+    command.assert().failure().stderr(predicates::str::contains(
+        "the following required arguments were not provided",
+    ));
+}
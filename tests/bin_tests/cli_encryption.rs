@@ -27,18 +27,125 @@ fn no_such_file() {
 
 #[test]
 fn random_key() {
-    let mut command = cargo::cargo_bin_cmd!("encrypt");
-
     let message_file = concat!(env!("CARGO_MANIFEST_DIR"), "/data/tests/text_01.txt");
     let output_file_dir = TempDir::new().unwrap();
     let output_file = output_file_dir.child("_.enc");
 
+    // a generated key must be recorded somewhere
+    let mut command = cargo::cargo_bin_cmd!("encrypt");
     command.arg(message_file).arg(output_file.path());
+    command
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("UnrecordedKey"));
 
+    let mut command = cargo::cargo_bin_cmd!("encrypt");
+    command
+        .arg("--emit-key")
+        .arg(message_file)
+        .arg(output_file.path());
     command
         .assert()
         .success()
-        .stderr(predicates::str::contains("Using key"));
+        .stderr(predicates::str::contains("Using key"))
+        .stderr(predicates::str::contains("Key fingerprint"));
+}
+
+#[test]
+fn key_out() {
+    let message_file = concat!(env!("CARGO_MANIFEST_DIR"), "/data/tests/text_01.txt");
+    let expected_message =
+        fs::read(message_file).expect("Could not find plaintext in data directory");
+    let file_dir = TempDir::new().unwrap();
+    let key_file = file_dir.child("key.hex");
+    let encrypted_file = file_dir.child("output.enc");
+    let decrypted_file = file_dir.child("output.txt");
+
+    let mut command = cargo::cargo_bin_cmd!("encrypt");
+    command
+        .arg("--key-out")
+        .arg(key_file.path())
+        .arg("--key-bits")
+        .arg("256")
+        .arg(message_file)
+        .arg(encrypted_file.path());
+    let output = command.output().unwrap();
+    assert!(output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    // the key itself is not printed
+    let key = fs::read_to_string(&key_file).unwrap();
+    assert!(!stderr.contains(key.trim_end()));
+    assert!(stderr.contains("Key fingerprint"));
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(&key_file).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    let mut command = cargo::cargo_bin_cmd!("decrypt");
+    command
+        .arg("--key-file")
+        .arg(key_file.path())
+        .arg(encrypted_file.path())
+        .arg(decrypted_file.path());
+    let output = command.output().unwrap();
+    assert!(output.status.success());
+    // both tools print the same fingerprint for the same key
+    let fingerprint = |stderr: &str| {
+        stderr
+            .lines()
+            .find(|line| line.starts_with("Key fingerprint"))
+            .unwrap()
+            .to_owned()
+    };
+    assert_eq!(
+        fingerprint(&stderr),
+        fingerprint(&String::from_utf8(output.stderr).unwrap())
+    );
+    assert_eq!(expected_message, fs::read(&decrypted_file).unwrap());
+
+    // existing key files are never overwritten
+    let mut command = cargo::cargo_bin_cmd!("encrypt");
+    command
+        .arg("--key-out")
+        .arg(key_file.path())
+        .arg(message_file)
+        .arg(encrypted_file.path());
+    command
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("FileWriteError"));
+    assert_eq!(fs::read_to_string(&key_file).unwrap(), key);
+
+    // no key file is left behind if the output cannot be created or encryption fails
+    let unused_key_file = file_dir.child("unused.hex");
+    let mut command = cargo::cargo_bin_cmd!("encrypt");
+    command
+        .arg("--key-out")
+        .arg(unused_key_file.path())
+        .arg(message_file)
+        .arg(file_dir.child("missing/output.enc").path());
+    command
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("FileWriteError"));
+    assert!(!unused_key_file.exists());
+
+    let mut command = cargo::cargo_bin_cmd!("encrypt");
+    command
+        .arg("--key-out")
+        .arg(unused_key_file.path())
+        .arg(file_dir.path())
+        .arg(file_dir.child("unused.enc").path());
+    command
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("FileReadError"));
+    assert!(!unused_key_file.exists());
+    assert!(!file_dir.child("unused.enc").exists());
+
+    file_dir.close().unwrap();
 }
 
 #[test]
@@ -52,6 +159,7 @@ fn numeric_keys() {
 
         let mut command = cargo::cargo_bin_cmd!("encrypt");
         command
+            .arg("--raw")
             .arg("--key")
             .arg(key.to_string())
             .arg(message_file)
@@ -84,6 +192,7 @@ fn str_keys() {
 
         let mut command = cargo::cargo_bin_cmd!("encrypt");
         command
+            .arg("--raw")
             .arg("--key")
             .arg(key)
             .arg(message_file)
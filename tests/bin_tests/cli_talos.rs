@@ -0,0 +1,340 @@
+// 2025 Steven Chiacchira
+use assert_cmd::cargo;
+use assert_fs::{fixture::PathChild, TempDir};
+use predicates::prelude::*;
+use std::fs;
+use talos::key::{KeyArgument, KeyWidth};
+
+#[test]
+fn encrypt_decrypt_round_trip() {
+    let message_file = concat!(env!("CARGO_MANIFEST_DIR"), "/data/tests/text_01.txt");
+    let expected_message =
+        fs::read(message_file).expect("Could not find plaintext in data directory");
+
+    let file_dir = TempDir::new().unwrap();
+    let encrypted_file = file_dir.child("output.enc");
+    let decrypted_file = file_dir.child("output.txt");
+
+    let mut command = cargo::cargo_bin_cmd!("talos");
+    command
+        .arg("encrypt")
+        .arg("--key")
+        .arg("42")
+        .arg(message_file)
+        .arg(encrypted_file.path());
+    command.assert().success();
+
+    // the standalone binaries are interchangeable with the subcommands
+    let mut command = cargo::cargo_bin_cmd!("decrypt");
+    command
+        .arg("--key")
+        .arg("42")
+        .arg(encrypted_file.path())
+        .arg(decrypted_file.path());
+    command.assert().success();
+    assert_eq!(expected_message, fs::read(&decrypted_file).unwrap());
+
+    let mut command = cargo::cargo_bin_cmd!("talos");
+    command
+        .arg("decrypt")
+        .arg("--key")
+        .arg("43")
+        .arg(encrypted_file.path())
+        .arg("-");
+    command
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("WrongKey"));
+
+    file_dir.close().unwrap();
+}
+
+#[test]
+fn keygen() {
+    for (key_bits, n_chars) in [("128", 32), ("256", 64)] {
+        let mut command = cargo::cargo_bin_cmd!("talos");
+        command.arg("keygen").arg("--key-bits").arg(key_bits);
+        let output = command.output().unwrap();
+        assert!(output.status.success());
+
+        let key = String::from_utf8(output.stdout).unwrap();
+        let key = key.trim_end();
+        assert_eq!(key.len(), n_chars);
+        assert!(key.chars().all(|c| c.is_ascii_hexdigit()));
+    }
+
+    let mut command = cargo::cargo_bin_cmd!("talos");
+    command.arg("keygen");
+    let output = command.output().unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .trim_end()
+        .parse::<u32>()
+        .is_ok());
+
+    let file_dir = TempDir::new().unwrap();
+    let key_file = file_dir.child("key.hex");
+    let mut command = cargo::cargo_bin_cmd!("talos");
+    command
+        .arg("keygen")
+        .arg("--key-bits")
+        .arg("128")
+        .arg("--out")
+        .arg(key_file.path());
+    let output = command.output().unwrap();
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+
+    let key = KeyArgument::File(key_file.path().to_path_buf())
+        .get_key(KeyWidth::Bits128)
+        .unwrap();
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains(&format!("Key fingerprint {}", key.fingerprint())));
+
+    file_dir.close().unwrap();
+}
+
+#[test]
+fn inspect() {
+    let message_file = concat!(env!("CARGO_MANIFEST_DIR"), "/data/tests/text_01.txt");
+    let message_len = fs::metadata(message_file).unwrap().len();
+
+    let file_dir = TempDir::new().unwrap();
+    let encrypted_file = file_dir.child("output.enc");
+
+    let mut command = cargo::cargo_bin_cmd!("talos");
+    command
+        .arg("encrypt")
+        .arg("--key")
+        .arg("Foo")
+        .arg("--key-bits")
+        .arg("128")
+        .arg("--block-dim")
+        .arg("32")
+        .arg("--kdf-iterations")
+        .arg("1000")
+        .arg(message_file)
+        .arg(encrypted_file.path());
+    command.assert().success();
+
+    let mut command = cargo::cargo_bin_cmd!("talos");
+    command.arg("inspect").arg(encrypted_file.path());
+    command.assert().success().stdout(
+        predicates::str::contains("Protocol variant: Rfc1")
+            .and(predicates::str::contains("Block shape: 32x32"))
+            .and(predicates::str::contains("Key width: 128 bits"))
+            .and(predicates::str::contains(format!(
+                "Plaintext length: {} bytes",
+                message_len
+            )))
+            .and(predicates::str::contains("authenticated"))
+            .and(predicates::str::contains(
+                "Key derivation: PBKDF2-HMAC-SHA256, 1000 iterations",
+            )),
+    );
+
+    let mut command = cargo::cargo_bin_cmd!("talos");
+    command.arg("inspect").arg(message_file);
+    command
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("BadMagic"));
+
+    file_dir.close().unwrap();
+}
+
+#[test]
+fn analyze() {
+    let init_file = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/data/init_matrix/S_init_matrix.txt"
+    );
+
+    let mut command = cargo::cargo_bin_cmd!("talos");
+    command
+        .arg("analyze")
+        .arg("count")
+        .arg("--use-contiguous-seeds")
+        .arg("--generations")
+        .arg("3")
+        .arg("--init-file")
+        .arg(init_file);
+    let output = command.output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("# Rule: B23456/S234\n"));
+    assert_eq!(
+        stdout
+            .lines()
+            .filter(|line| line.starts_with("0\t0\t"))
+            .count(),
+        3
+    );
+
+    // the standalone binaries print the same analysis
+    let mut command = cargo::cargo_bin_cmd!("test_count");
+    command
+        .arg("--use-contiguous-seeds")
+        .arg("--generations")
+        .arg("3")
+        .arg("--init-file")
+        .arg(init_file);
+    command.assert().success().stdout(stdout);
+
+    for analysis in ["shift", "transpose"] {
+        let mut command = cargo::cargo_bin_cmd!("talos");
+        command
+            .arg("analyze")
+            .arg(analysis)
+            .arg("--init-file")
+            .arg(init_file);
+        command.assert().success();
+    }
+
+    // swap indices are read for every row and column of larger blocks
+    let init_file_32 = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/data/init_matrix/T_init_matrix_32x32.txt"
+    );
+    let mut command = cargo::cargo_bin_cmd!("talos");
+    command
+        .arg("analyze")
+        .arg("transpose")
+        .arg("--block-dim")
+        .arg("32")
+        .arg("--init-file")
+        .arg(init_file_32);
+    let output = command.output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("\tR31\t"));
+    assert!(stdout.contains("\tC31\t"));
+    assert_eq!(
+        stdout.lines().filter(|line| !line.starts_with('#')).count(),
+        65
+    );
+
+    // the initialization matrix must have the shape of the block
+    let mut command = cargo::cargo_bin_cmd!("talos");
+    command
+        .arg("analyze")
+        .arg("transpose")
+        .arg("--block-dim")
+        .arg("32")
+        .arg("--init-file")
+        .arg(init_file);
+    command
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("UnsupportedParameters"));
+
+    // living cells are averaged over every cell of larger blocks
+    let shift_init_file_32 = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/data/init_matrix/S_init_matrix_32x32.txt"
+    );
+    let mut command = cargo::cargo_bin_cmd!("talos");
+    command
+        .arg("analyze")
+        .arg("shift")
+        .arg("--generations")
+        .arg("5")
+        .arg("--block-dim")
+        .arg("32")
+        .arg("--init-file")
+        .arg(shift_init_file_32);
+    let output = command.output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let row = stdout.lines().last().unwrap();
+    let avg_alive: f64 = row.split('\t').nth(3).unwrap().parse().unwrap();
+    assert!((0.0..=1.0).contains(&avg_alive));
+
+    let mut command = cargo::cargo_bin_cmd!("talos");
+    command
+        .arg("analyze")
+        .arg("shift")
+        .arg("--block-dim")
+        .arg("32")
+        .arg("--init-file")
+        .arg(init_file);
+    command
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("UnsupportedParameters"));
+
+    let mut command = cargo::cargo_bin_cmd!("talos");
+    command
+        .arg("analyze")
+        .arg("count")
+        .arg("--generations")
+        .arg("1")
+        .arg("--rule")
+        .arg("b3/s23")
+        .arg("--init-file")
+        .arg(init_file);
+    command
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("# Rule: B3/S23\n"));
+
+    let mut command = cargo::cargo_bin_cmd!("talos");
+    command
+        .arg("analyze")
+        .arg("count")
+        .arg("--generations")
+        .arg("1")
+        .arg("--rule")
+        .arg("b2/s013v")
+        .arg("--init-file")
+        .arg(init_file);
+    command
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("# Rule: B2/S013V\n"));
+
+    let mut command = cargo::cargo_bin_cmd!("talos");
+    command
+        .arg("analyze")
+        .arg("count")
+        .arg("--rule")
+        .arg("B39/S23")
+        .arg("--init-file")
+        .arg(init_file);
+    command.assert().failure().stderr(predicates::str::contains(
+        "Neighbor count 9 is larger than the neighborhood",
+    ));
+
+    // a schedule without injections would never seed the key
+    let mut command = cargo::cargo_bin_cmd!("talos");
+    command
+        .arg("analyze")
+        .arg("shift")
+        .arg("--smear-steps")
+        .arg("0")
+        .arg("--init-file")
+        .arg(init_file);
+    command.assert().failure();
+
+    let mut command = cargo::cargo_bin_cmd!("talos");
+    command
+        .arg("analyze")
+        .arg("count")
+        .arg("--init-file")
+        .arg("nonexistent.txt");
+    command
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("FileReadError"));
+}
+
+#[test]
+fn bench() {
+    let mut command = cargo::cargo_bin_cmd!("talos");
+    command.arg("bench").arg("--bytes").arg("4096");
+    command.assert().success().stdout(
+        predicates::str::contains("\nencrypt\t").and(predicates::str::contains("\ndecrypt\t")),
+    );
+}
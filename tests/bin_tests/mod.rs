@@ -1,3 +1,5 @@
 // 2025 Steven Chiacchira
+mod cli_container;
 mod cli_decryption;
 mod cli_encryption;
+mod cli_talos;
@@ -0,0 +1,573 @@
+// 2025 Steven Chiacchira
+use super::rfc1_automata;
+use rand::{rngs::StdRng, SeedableRng};
+use std::fs;
+use talos::container::{
+    self, ContainerError, DecryptOptions, EncryptOptions, Header, FLAG_AUTHENTICATED, FLAG_KDF,
+    FLAG_KEY_CHECK, FLAG_PKCS7, HEADER_SIZE, KEY_CHECK_SIZE,
+};
+use talos::encrypt::{PaddingStrategy, ProtocolVariant, GEOMETRY_256, NONCE_SIZE};
+use talos::kdf::{KdfParams, SALT_SIZE};
+use talos::key::{KeyArgument, KeyWidth, TalosKey};
+use talos::mac::TAG_SIZE;
+
+const ALLOW_UNAUTHENTICATED: DecryptOptions = DecryptOptions {
+    require_authentication: false,
+};
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_container_round_trip() {
+    let message_file = concat!(env!("CARGO_MANIFEST_DIR"), "/data/tests/text_01.txt");
+    let message =
+        fs::read(message_file).expect("Could not find plaintext in data/tests directory.");
+
+    for key in 0..8 {
+        let ciphertext = container::encrypt(message.clone(), key);
+        let decrypted = container::decrypt(ciphertext, key).unwrap();
+
+        assert_eq!(message, decrypted);
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_container_matches_padded_ciphertext() {
+    let message_file = concat!(env!("CARGO_MANIFEST_DIR"), "/data/tests/text_01.txt");
+    let message =
+        fs::read(message_file).expect("Could not find plaintext in data/tests directory.");
+
+    for key in 0..3 {
+        let container = container::encrypt(message.clone(), key);
+        let header = Header::from_bytes(&container).unwrap();
+
+        let (mut s_automaton, mut t_automaton) = rfc1_automata(key, Some(&header.nonce.unwrap()));
+        let ciphertext = talos::encrypt::encrypt_message(
+            talos::encrypt::pad_message_256(message.clone()),
+            &GEOMETRY_256,
+            header.variant,
+            &mut s_automaton,
+            &mut t_automaton,
+        );
+
+        assert_eq!(header.variant, ProtocolVariant::Rfc1);
+        assert_eq!(header.plaintext_len, message.len() as u64);
+        assert_eq!(header.flags & FLAG_PKCS7, FLAG_PKCS7);
+        assert_eq!(
+            container[header.size()..container.len() - TAG_SIZE],
+            ciphertext
+        );
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_container_padding_strategies() {
+    let message_file = concat!(env!("CARGO_MANIFEST_DIR"), "/data/tests/text_01.txt");
+    let message =
+        fs::read(message_file).expect("Could not find plaintext in data/tests directory.");
+    let mut rng = StdRng::seed_from_u64(0);
+
+    for padding in [
+        PaddingStrategy::Pkcs7,
+        PaddingStrategy::Zeros,
+        PaddingStrategy::Random,
+        PaddingStrategy::from_corpus(&String::from_utf8(message.clone()).unwrap()),
+    ] {
+        let options = EncryptOptions {
+            padding,
+            ..Default::default()
+        };
+        let ciphertext = container::encrypt_with_options(
+            message.clone(),
+            &TalosKey::Bits32(3),
+            &options,
+            &mut rng,
+        )
+        .unwrap();
+        let decrypted = container::decrypt(ciphertext, 3).unwrap();
+
+        assert_eq!(message, decrypted);
+    }
+}
+
+#[test]
+fn test_container_empty_message() {
+    let ciphertext = container::encrypt(Vec::new(), 7);
+    assert_eq!(
+        ciphertext.len(),
+        HEADER_SIZE + NONCE_SIZE + KEY_CHECK_SIZE + talos::encrypt::BLOCK_BYTES + TAG_SIZE
+    );
+
+    let decrypted = container::decrypt(ciphertext, 7).unwrap();
+    assert!(decrypted.is_empty());
+}
+
+#[test]
+fn test_container_truncated() {
+    let mut ciphertext = container::encrypt(vec![1, 2, 3, 4], 7);
+    ciphertext.pop();
+
+    assert!(matches!(
+        container::decrypt(ciphertext, 7),
+        Err(ContainerError::Truncated())
+    ));
+}
+
+#[test]
+fn test_container_unsupported_parameters() {
+    let mut header = Header::from_bytes(&container::encrypt(vec![1, 2, 3, 4], 7)).unwrap();
+    header.iters_per_block += 1;
+
+    let mut ciphertext = header.to_bytes();
+    ciphertext.extend([0; 32]);
+
+    assert!(matches!(
+        container::decrypt(ciphertext, 7),
+        Err(ContainerError::UnsupportedParameters())
+    ));
+}
+
+#[test]
+fn test_container_invalid_geometry() {
+    let container = container::encrypt(vec![1, 2, 3, 4], 7);
+
+    for (n_rows, n_cols) in [(0, 0), (16, 0), (16, 32), (12, 12)] {
+        let mut header = Header::from_bytes(&container).unwrap();
+        header.n_rows = n_rows;
+        header.n_cols = n_cols;
+        assert!(matches!(
+            header.ciphertext_len(),
+            Err(ContainerError::UnsupportedParameters())
+        ));
+
+        let mut ciphertext = header.to_bytes();
+        ciphertext.extend(&container[header.size()..]);
+        assert!(matches!(
+            Header::from_bytes(&ciphertext),
+            Err(ContainerError::UnsupportedParameters())
+        ));
+        assert!(matches!(
+            Header::read(&mut ciphertext.as_slice()),
+            Err(ContainerError::UnsupportedParameters())
+        ));
+    }
+}
+
+#[test]
+fn test_container_plaintext_len_overflow() {
+    let container = container::encrypt(vec![1, 2, 3, 4], 7);
+    let mut header = Header::from_bytes(&container).unwrap();
+    header.plaintext_len = u64::MAX - 3;
+    assert!(matches!(
+        header.ciphertext_len(),
+        Err(ContainerError::InvalidLength())
+    ));
+
+    let mut ciphertext = header.to_bytes();
+    ciphertext.extend(&container[header.size()..]);
+    assert!(matches!(
+        container::decrypt(ciphertext.clone(), 7),
+        Err(ContainerError::InvalidLength())
+    ));
+
+    let header = Header::read(&mut ciphertext.as_slice()).unwrap();
+    assert!(matches!(
+        container::decrypt_stream(
+            &header,
+            &ciphertext[header.size()..],
+            Vec::new(),
+            &TalosKey::Bits32(7),
+            &DecryptOptions::default(),
+        ),
+        Err(ContainerError::InvalidLength())
+    ));
+}
+
+#[test]
+fn test_container_length_mismatch() {
+    let options = EncryptOptions {
+        authenticate: false,
+        ..Default::default()
+    };
+    let container = container::encrypt_with_options(
+        vec![1, 2, 3, 4],
+        &TalosKey::Bits32(7),
+        &options,
+        &mut rand::rng(),
+    )
+    .unwrap();
+    let mut header = Header::from_bytes(&container).unwrap();
+    header.plaintext_len = 5;
+
+    let mut ciphertext = header.to_bytes();
+    ciphertext.extend(&container[header.size()..]);
+
+    assert!(matches!(
+        container::decrypt_with_options(ciphertext, &TalosKey::Bits32(7), &ALLOW_UNAUTHENTICATED),
+        Err(ContainerError::InvalidLength())
+    ));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_container_nonce_unique() {
+    let message_file = concat!(env!("CARGO_MANIFEST_DIR"), "/data/tests/text_01.txt");
+    let message =
+        fs::read(message_file).expect("Could not find plaintext in data/tests directory.");
+
+    let mut set: std::collections::HashSet<std::vec::Vec<u8>> = std::collections::HashSet::new();
+
+    for _ in 0..8 {
+        let container = container::encrypt(message.clone(), 7);
+        let header = Header::from_bytes(&container).unwrap();
+        let ciphertext = container[header.size()..].to_vec();
+
+        assert!(!set.contains(&ciphertext));
+        set.insert(ciphertext);
+        assert_eq!(container::decrypt(container, 7).unwrap(), message);
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_nonce_changes_keystream() {
+    let message = vec![0; 4 * talos::encrypt::BLOCK_BYTES];
+
+    let mut set: std::collections::HashSet<std::vec::Vec<u8>> = std::collections::HashSet::new();
+
+    for nonce_byte in 0..8 {
+        let mut nonce = [0; NONCE_SIZE];
+        nonce[nonce_byte] = 1;
+        let (mut s_automaton, mut t_automaton) = rfc1_automata(7, Some(&nonce));
+        let ciphertext = talos::encrypt::encrypt_message_256(
+            message.clone(),
+            &mut s_automaton,
+            &mut t_automaton,
+        );
+
+        assert!(!set.contains(&ciphertext));
+        set.insert(ciphertext);
+    }
+}
+
+#[test]
+fn test_container_wrong_key() {
+    let ciphertext = container::encrypt(vec![1, 2, 3, 4], 7);
+
+    for key in [0, 6, 8, u32::MAX] {
+        assert!(matches!(
+            container::decrypt(ciphertext.clone(), key),
+            Err(ContainerError::WrongKey())
+        ));
+    }
+
+    // without a key check value, the wrong key is only detected by the authentication tag
+    let options = EncryptOptions {
+        key_check: false,
+        ..EncryptOptions::default()
+    };
+    let ciphertext = container::encrypt_with_options(
+        vec![1, 2, 3, 4],
+        &TalosKey::Bits32(7),
+        &options,
+        &mut rand::rng(),
+    )
+    .unwrap();
+    let header = Header::from_bytes(&ciphertext).unwrap();
+    assert_eq!(header.flags & FLAG_KEY_CHECK, 0);
+    assert_eq!(header.key_check, None);
+    assert!(matches!(
+        container::decrypt(ciphertext, 8),
+        Err(ContainerError::AuthenticationFailed())
+    ));
+}
+
+#[test]
+fn test_container_key_check() {
+    let key = TalosKey::Bits128([3; 16]);
+    let mut rng = StdRng::seed_from_u64(19);
+    let ciphertext = container::encrypt_with_options(
+        vec![1, 2, 3, 4],
+        &key,
+        &EncryptOptions::default(),
+        &mut rng,
+    )
+    .unwrap();
+    let header = Header::from_bytes(&ciphertext).unwrap();
+    assert_ne!(header.flags & FLAG_KEY_CHECK, 0);
+    assert_eq!(header.key_check, Some(header.key_check_value(&key)));
+    assert!(header.check_key(&key).is_ok());
+    assert!(matches!(
+        header.check_key(&TalosKey::Bits128([4; 16])),
+        Err(ContainerError::WrongKey())
+    ));
+    assert!(matches!(
+        header.check_key(&TalosKey::Bits32(3)),
+        Err(ContainerError::KeyWidthMismatch(KeyWidth::Bits128))
+    ));
+
+    // the check value depends on the nonce, so it differs between containers
+    let other = container::encrypt_with_options(
+        vec![1, 2, 3, 4],
+        &key,
+        &EncryptOptions::default(),
+        &mut rng,
+    )
+    .unwrap();
+    assert_ne!(
+        Header::from_bytes(&other).unwrap().key_check,
+        header.key_check
+    );
+
+    // the wrong key is rejected before anything is written
+    let mut output = Vec::new();
+    assert!(matches!(
+        container::decrypt_stream(
+            &header,
+            &ciphertext[header.size()..],
+            &mut output,
+            &TalosKey::Bits128([4; 16]),
+            &DecryptOptions::default()
+        ),
+        Err(ContainerError::WrongKey())
+    ));
+    assert!(output.is_empty());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_container_tampered() {
+    let ciphertext = container::encrypt((0..100).collect(), 7);
+
+    // flip one bit in the header, ciphertext, and tag
+    for position in [
+        7,
+        9,
+        17,
+        HEADER_SIZE,
+        HEADER_SIZE + 50,
+        ciphertext.len() - 1,
+    ] {
+        let mut tampered = ciphertext.clone();
+        tampered[position] ^= 1;
+
+        assert!(container::decrypt(tampered, 7).is_err());
+    }
+
+    let mut tampered = ciphertext.clone();
+    tampered[HEADER_SIZE + 50] ^= 1;
+    assert!(matches!(
+        container::decrypt(tampered, 7),
+        Err(ContainerError::AuthenticationFailed())
+    ));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_container_verify_stream() {
+    let key = TalosKey::Bits32(7);
+    let ciphertext = container::encrypt((0..100).collect(), 7);
+    let verify = |container: &[u8], key: &TalosKey| {
+        let mut input = container;
+        let header = Header::read(&mut input).unwrap();
+        container::verify_stream(&header, input, key)
+    };
+
+    assert!(verify(&ciphertext, &key).is_ok());
+    for position in [HEADER_SIZE, HEADER_SIZE + 50, ciphertext.len() - 1] {
+        let mut tampered = ciphertext.clone();
+        tampered[position] ^= 1;
+        assert!(matches!(
+            verify(&tampered, &key),
+            Err(ContainerError::AuthenticationFailed())
+        ));
+    }
+    let mut input = &ciphertext[..];
+    let header = Header::read(&mut input).unwrap();
+    assert!(matches!(
+        container::verify_stream(&header, &input[..TAG_SIZE - 1], &key),
+        Err(ContainerError::Truncated())
+    ));
+
+    let options = EncryptOptions {
+        authenticate: false,
+        ..Default::default()
+    };
+    let unauthenticated =
+        container::encrypt_with_options(vec![1, 2, 3, 4], &key, &options, &mut rand::rng())
+            .unwrap();
+    assert!(matches!(
+        verify(&unauthenticated, &key),
+        Err(ContainerError::Unauthenticated())
+    ));
+}
+
+#[test]
+fn test_container_unauthenticated() {
+    let message = vec![1, 2, 3, 4];
+    let options = EncryptOptions {
+        authenticate: false,
+        ..Default::default()
+    };
+    let ciphertext = container::encrypt_with_options(
+        message.clone(),
+        &TalosKey::Bits32(7),
+        &options,
+        &mut rand::rng(),
+    )
+    .unwrap();
+
+    assert!(matches!(
+        container::decrypt(ciphertext.clone(), 7),
+        Err(ContainerError::Unauthenticated())
+    ));
+    assert_eq!(
+        container::decrypt_with_options(ciphertext, &TalosKey::Bits32(7), &ALLOW_UNAUTHENTICATED)
+            .unwrap(),
+        message
+    );
+}
+
+#[test]
+fn test_container_stripped_tag() {
+    // an attacker cannot downgrade an authenticated container by clearing its flag
+    let mut ciphertext = container::encrypt(vec![1, 2, 3, 4], 7);
+    ciphertext.truncate(ciphertext.len() - TAG_SIZE);
+    ciphertext[7] &= !(FLAG_AUTHENTICATED as u8);
+
+    assert!(matches!(
+        container::decrypt(ciphertext, 7),
+        Err(ContainerError::Unauthenticated())
+    ));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_container_wide_key_round_trip() {
+    let message_file = concat!(env!("CARGO_MANIFEST_DIR"), "/data/tests/text_01.txt");
+    let message =
+        fs::read(message_file).expect("Could not find plaintext in data/tests directory.");
+
+    for width in [KeyWidth::Bits128, KeyWidth::Bits256] {
+        let key = KeyArgument::None.get_key(width).unwrap();
+        let ciphertext = container::encrypt_with_options(
+            message.clone(),
+            &key,
+            &EncryptOptions::default(),
+            &mut rand::rng(),
+        )
+        .unwrap();
+        assert_eq!(Header::from_bytes(&ciphertext).unwrap().key_width, width);
+
+        let decrypted =
+            container::decrypt_with_options(ciphertext, &key, &DecryptOptions::default()).unwrap();
+        assert_eq!(message, decrypted);
+    }
+}
+
+#[test]
+fn test_container_key_width_mismatch() {
+    let key = KeyArgument::String("Foo".to_string())
+        .get_key(KeyWidth::Bits128)
+        .unwrap();
+    let ciphertext = container::encrypt_with_options(
+        vec![1, 2, 3, 4],
+        &key,
+        &EncryptOptions::default(),
+        &mut rand::rng(),
+    )
+    .unwrap();
+
+    assert!(matches!(
+        container::decrypt(ciphertext.clone(), 7),
+        Err(ContainerError::KeyWidthMismatch(KeyWidth::Bits128))
+    ));
+
+    let narrow_ciphertext = container::encrypt(vec![1, 2, 3, 4], 7);
+    assert!(matches!(
+        container::decrypt_with_options(narrow_ciphertext, &key, &DecryptOptions::default()),
+        Err(ContainerError::KeyWidthMismatch(KeyWidth::Bits32))
+    ));
+}
+
+#[test]
+fn test_container_wide_key_bits_matter() {
+    // flipping any single bit of a wide key must change the keystream
+    let key = [0x5a; 32];
+    let ciphertext = container::encrypt_with_options(
+        vec![0; 4],
+        &TalosKey::Bits256(key),
+        &EncryptOptions::default(),
+        &mut rand::rng(),
+    )
+    .unwrap();
+
+    for bit in [0, 31, 32, 127, 128, 255] {
+        let mut wrong_key = key;
+        wrong_key[bit / 8] ^= 1 << (bit % 8);
+
+        assert!(matches!(
+            container::decrypt_with_options(
+                ciphertext.clone(),
+                &TalosKey::Bits256(wrong_key),
+                &DecryptOptions::default()
+            ),
+            Err(ContainerError::WrongKey())
+        ));
+    }
+}
+
+#[test]
+fn test_container_kdf_round_trip() {
+    let mut rng = StdRng::seed_from_u64(18);
+    let params = KdfParams::new(100, &mut rng);
+    let key = KeyArgument::String("Foo".to_string())
+        .get_key_with_kdf(KeyWidth::Bits128, &params)
+        .unwrap();
+    assert_ne!(
+        key,
+        KeyArgument::String("Foo".to_string())
+            .get_key(KeyWidth::Bits128)
+            .unwrap()
+    );
+
+    let options = EncryptOptions {
+        kdf: Some(params.clone()),
+        ..EncryptOptions::default()
+    };
+    let ciphertext =
+        container::encrypt_with_options(vec![1, 2, 3, 4], &key, &options, &mut rng).unwrap();
+    let header = Header::from_bytes(&ciphertext).unwrap();
+    assert_ne!(header.flags & FLAG_KDF, 0);
+    assert_eq!(header.kdf, Some(params.clone()));
+    assert_eq!(
+        header.size(),
+        HEADER_SIZE + NONCE_SIZE + 1 + SALT_SIZE + size_of::<u32>() + KEY_CHECK_SIZE
+    );
+
+    // the key is derived again from the parameters in the header
+    let key = KeyArgument::String("Foo".to_string())
+        .get_key_with_kdf(header.key_width, header.kdf.as_ref().unwrap())
+        .unwrap();
+    assert_eq!(
+        container::decrypt_with_options(ciphertext.clone(), &key, &DecryptOptions::default())
+            .unwrap(),
+        vec![1, 2, 3, 4]
+    );
+
+    // the parameters are authenticated along with the rest of the header
+    let mut tampered = ciphertext;
+    tampered[HEADER_SIZE + NONCE_SIZE + 1] ^= 1;
+    assert!(matches!(
+        container::decrypt_with_options(tampered.clone(), &key, &DecryptOptions::default()),
+        Err(ContainerError::AuthenticationFailed())
+    ));
+
+    let iterations_offset = HEADER_SIZE + NONCE_SIZE + 1 + SALT_SIZE;
+    tampered[iterations_offset..iterations_offset + 4].fill(0);
+    assert!(matches!(
+        Header::from_bytes(&tampered),
+        Err(ContainerError::UnsupportedParameters())
+    ));
+}
@@ -1,5 +1,11 @@
 // 2025 Steven Chiacchira
+use super::rfc1_automata;
+use rand::{rngs::StdRng, SeedableRng};
 use std::fs;
+use talos::encrypt::{
+    pad_message_256, pad_message_with_256, strip_padding_256, unpad_message_256, PaddingError,
+    PaddingStrategy, BLOCK_BYTES,
+};
 
 #[test]
 #[cfg_attr(miri, ignore)]
@@ -10,14 +16,14 @@ fn test_encrypt_decrypt_equal() {
     let message_size = message.len();
 
     for key in 0..32 {
-        let (mut s_automaton, mut t_automaton) = talos::encrypt::get_shift_transpose_automata(key);
+        let (mut s_automaton, mut t_automaton) = rfc1_automata(key, None);
         let ciphertext = talos::encrypt::encrypt_message_256(
             message.clone(),
             &mut s_automaton,
             &mut t_automaton,
         );
 
-        let (mut s_automaton, mut t_automaton) = talos::encrypt::get_shift_transpose_automata(key);
+        let (mut s_automaton, mut t_automaton) = rfc1_automata(key, None);
         let decrypted =
             talos::encrypt::decrypt_message_256(ciphertext, &mut s_automaton, &mut t_automaton);
 
@@ -40,7 +46,7 @@ fn test_decrypt_breaking() {
         let ciphertext =
             fs::read(encrypted_file).expect("Could not find ciphertext in data/tests directory.");
 
-        let (mut s_automaton, mut t_automaton) = talos::encrypt::get_shift_transpose_automata(key);
+        let (mut s_automaton, mut t_automaton) = rfc1_automata(key, None);
         let decrypted =
             talos::encrypt::decrypt_message_256(ciphertext, &mut s_automaton, &mut t_automaton);
 
@@ -56,7 +62,7 @@ fn test_encrypt_breaking() {
         fs::read(message_file).expect("Could not find plaintext in data/tests directory.");
 
     for key in 0..3 {
-        let (mut s_automaton, mut t_automaton) = talos::encrypt::get_shift_transpose_automata(key);
+        let (mut s_automaton, mut t_automaton) = rfc1_automata(key, None);
         let encrypted_message = talos::encrypt::encrypt_message_256(
             message.clone(),
             &mut s_automaton,
@@ -82,7 +88,7 @@ fn test_encrypt_is_unique() {
     let mut set: std::collections::HashSet<std::vec::Vec<u8>> = std::collections::HashSet::new();
 
     for key in 0..32 {
-        let (mut s_automaton, mut t_automaton) = talos::encrypt::get_shift_transpose_automata(key);
+        let (mut s_automaton, mut t_automaton) = rfc1_automata(key, None);
         let encrypted_message = talos::encrypt::encrypt_message_256(
             message.clone(),
             &mut s_automaton,
@@ -109,7 +115,7 @@ fn test_decrypt_is_unique() {
     let mut set: std::collections::HashSet<std::vec::Vec<u8>> = std::collections::HashSet::new();
 
     for key in 1..32 {
-        let (mut s_automaton, mut t_automaton) = talos::encrypt::get_shift_transpose_automata(key);
+        let (mut s_automaton, mut t_automaton) = rfc1_automata(key, None);
         let plaintext = talos::encrypt::decrypt_message_256(
             ciphertext.clone(),
             &mut s_automaton,
@@ -121,3 +127,116 @@ fn test_decrypt_is_unique() {
         set.insert(plaintext);
     }
 }
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_encrypt_decrypt_padded_equal() {
+    let message_file = concat!(env!("CARGO_MANIFEST_DIR"), "/data/tests/text_01.txt");
+    let message =
+        fs::read(message_file).expect("Could not find plaintext in data/tests directory.");
+
+    for key in 0..32 {
+        let (mut s_automaton, mut t_automaton) = rfc1_automata(key, None);
+        let ciphertext = talos::encrypt::encrypt_padded_message_256(
+            message.clone(),
+            &mut s_automaton,
+            &mut t_automaton,
+        );
+
+        let (mut s_automaton, mut t_automaton) = rfc1_automata(key, None);
+        let decrypted = talos::encrypt::decrypt_padded_message_256(
+            ciphertext,
+            &mut s_automaton,
+            &mut t_automaton,
+        )
+        .unwrap();
+
+        assert_eq!(message, decrypted);
+    }
+}
+
+#[test]
+fn test_pad_unpad() {
+    for len in 0..(3 * BLOCK_BYTES) {
+        let message: Vec<u8> = (0..len).map(|i| i as u8).collect();
+        let padded = pad_message_256(message.clone());
+
+        assert_eq!(padded.len() % BLOCK_BYTES, 0);
+        assert!(padded.len() > message.len());
+        assert!(padded.len() <= message.len() + BLOCK_BYTES);
+        assert_eq!(unpad_message_256(padded).unwrap(), message);
+    }
+}
+
+#[test]
+fn test_unpad_malformed() {
+    let mut zero_padding = vec![7; BLOCK_BYTES];
+    zero_padding[BLOCK_BYTES - 1] = 0;
+
+    let mut too_long_padding = vec![0; BLOCK_BYTES];
+    too_long_padding[BLOCK_BYTES - 1] = BLOCK_BYTES as u8 + 1;
+
+    let mut inconsistent_padding = vec![3; BLOCK_BYTES];
+    inconsistent_padding[BLOCK_BYTES - 2] = 2;
+
+    for message in [zero_padding, too_long_padding, inconsistent_padding] {
+        assert!(matches!(
+            unpad_message_256(message),
+            Err(PaddingError::MalformedPadding())
+        ));
+    }
+
+    assert!(matches!(
+        unpad_message_256(Vec::new()),
+        Err(PaddingError::InvalidLength())
+    ));
+    assert!(matches!(
+        unpad_message_256(vec![1; BLOCK_BYTES + 1]),
+        Err(PaddingError::InvalidLength())
+    ));
+}
+
+#[test]
+fn test_pad_with_strategies() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let corpus = PaddingStrategy::from_corpus("lorem ipsum dolor");
+
+    for strategy in [
+        PaddingStrategy::Pkcs7,
+        PaddingStrategy::Zeros,
+        PaddingStrategy::Random,
+        corpus.clone(),
+    ] {
+        for len in 0..(3 * BLOCK_BYTES) {
+            let message: Vec<u8> = (0..len).map(|i| i as u8).collect();
+            let padded = pad_message_with_256(message.clone(), &strategy, &mut rng);
+
+            assert_eq!(padded.len() % BLOCK_BYTES, 0);
+            assert_eq!(padded[..len], message);
+            if strategy != PaddingStrategy::Pkcs7 {
+                assert_eq!(strip_padding_256(padded, len).unwrap(), message);
+            }
+        }
+    }
+
+    let padded = pad_message_with_256(vec![b'x'], &corpus, &mut rng);
+    let words = String::from_utf8(padded[1..].to_vec()).unwrap();
+    assert!(words
+        .split_whitespace()
+        .all(|w| "lorem ipsum dolor".contains(w)));
+
+    let padded = pad_message_with_256(vec![0], &PaddingStrategy::Random, &mut rng);
+    assert!(padded[1..].iter().any(|b| *b != 0));
+}
+
+#[test]
+fn test_strip_padding_invalid_length() {
+    assert!(matches!(
+        strip_padding_256(vec![0; BLOCK_BYTES], BLOCK_BYTES + 1),
+        Err(PaddingError::InvalidLength())
+    ));
+    assert!(matches!(
+        strip_padding_256(vec![0; 2 * BLOCK_BYTES], 3),
+        Err(PaddingError::InvalidLength())
+    ));
+}
@@ -0,0 +1,185 @@
+// 2025 Steven Chiacchira
+use super::geometry_automata;
+use std::borrow::Cow;
+use std::fs;
+use talos::container::{self, ContainerError, DecryptOptions, EncryptOptions, Header, FLAG_PKCS7};
+use talos::encrypt::{
+    self, BlockGeometry, CipherParams, GeometryError, PaddingError, PaddingStrategy,
+    ProtocolVariant, GEOMETRY_256, S_INIT_MATRIX, S_INIT_MATRIX_32X32, T_INIT_MATRIX,
+    T_INIT_MATRIX_32X32,
+};
+use talos::key::TalosKey;
+
+#[test]
+fn test_geometry_dimensions() {
+    assert_eq!(GEOMETRY_256.block_size(), encrypt::BLOCK_SIZE);
+    assert_eq!(GEOMETRY_256.block_bytes(), encrypt::BLOCK_BYTES);
+    assert_eq!(GEOMETRY_256.index_bits(), 4);
+    assert_eq!(GEOMETRY_256.index_stride(), 4);
+
+    let geometry = BlockGeometry::new(64).unwrap();
+    assert_eq!(geometry.block_bytes(), 512);
+    assert_eq!(geometry.index_bits(), 6);
+    assert_eq!(geometry.index_stride(), 10);
+
+    for dim in [0, 2, 12, 48, 1 << 16] {
+        assert!(matches!(
+            BlockGeometry::new(dim),
+            Err(GeometryError::UnsupportedDimension(d)) if d == dim
+        ));
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_geometry_256_matches_rfc0() {
+    let message_file = concat!(env!("CARGO_MANIFEST_DIR"), "/data/tests/text_01.txt");
+    let message =
+        fs::read(message_file).expect("Could not find plaintext in data/tests directory.");
+    let expected_file = concat!(env!("CARGO_MANIFEST_DIR"), "/data/tests/text_01_k1.enc");
+    let expected = fs::read(expected_file).expect("Could not find ciphertext in data/tests.");
+
+    let params = CipherParams {
+        s_init_matrix: Cow::Borrowed(S_INIT_MATRIX),
+        t_init_matrix: Cow::Borrowed(T_INIT_MATRIX),
+        ..CipherParams::rfc1()
+    };
+    let (mut s_automaton, mut t_automaton) = encrypt::get_shift_transpose_automata_with_params(
+        &params,
+        &GEOMETRY_256,
+        &TalosKey::Bits32(1),
+        None,
+    )
+    .unwrap();
+    let ciphertext = encrypt::encrypt_message(
+        message,
+        &GEOMETRY_256,
+        ProtocolVariant::LegacyRfc1,
+        &mut s_automaton,
+        &mut t_automaton,
+    );
+
+    assert_eq!(ciphertext, expected);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_encrypt_decrypt_larger_blocks() {
+    let message_file = concat!(env!("CARGO_MANIFEST_DIR"), "/data/tests/text_01.txt");
+    let message =
+        fs::read(message_file).expect("Could not find plaintext in data/tests directory.");
+
+    for dim in [32, 64] {
+        let geometry = BlockGeometry::new(dim).unwrap();
+        for key in [TalosKey::Bits32(7), TalosKey::Bits256([3; 32])] {
+            let (mut s_automaton, mut t_automaton) =
+                geometry_automata(&geometry, &key, None).unwrap();
+            let ciphertext = encrypt::encrypt_message(
+                message.clone(),
+                &geometry,
+                ProtocolVariant::Rfc1,
+                &mut s_automaton,
+                &mut t_automaton,
+            );
+            assert_eq!(ciphertext.len() % geometry.block_bytes(), 0);
+            assert_ne!(ciphertext[..message.len()], message);
+
+            let (mut s_automaton, mut t_automaton) =
+                geometry_automata(&geometry, &key, None).unwrap();
+            let decrypted = encrypt::decrypt_message(
+                ciphertext,
+                &geometry,
+                ProtocolVariant::Rfc1,
+                &mut s_automaton,
+                &mut t_automaton,
+            );
+
+            assert_eq!(message, decrypted[..message.len()]);
+        }
+    }
+}
+
+#[test]
+fn test_init_matrix_mismatch() {
+    let geometry = BlockGeometry::new(32).unwrap();
+    let key = TalosKey::Bits32(7);
+
+    assert!(matches!(
+        encrypt::get_shift_transpose_automata_with_params(
+            &CipherParams {
+                s_init_matrix: Cow::Borrowed(S_INIT_MATRIX),
+                t_init_matrix: Cow::Borrowed(T_INIT_MATRIX_32X32),
+                ..CipherParams::rfc1()
+            },
+            &geometry,
+            &key,
+            None
+        ),
+        Err(GeometryError::MatrixShapeMismatch())
+    ));
+    assert!(matches!(
+        encrypt::get_shift_transpose_automata_with_params(
+            &CipherParams {
+                s_init_matrix: Cow::Borrowed(S_INIT_MATRIX_32X32),
+                t_init_matrix: Cow::Owned(".#".repeat(16).repeat(32)),
+                ..CipherParams::rfc1()
+            },
+            &geometry,
+            &key,
+            None
+        ),
+        Err(GeometryError::NoKeyCells())
+    ));
+    assert!(matches!(
+        geometry_automata(&BlockGeometry::new(8).unwrap(), &key, None),
+        Err(GeometryError::NoDefaultMatrices())
+    ));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_container_larger_blocks() {
+    let message_file = concat!(env!("CARGO_MANIFEST_DIR"), "/data/tests/text_01.txt");
+    let message =
+        fs::read(message_file).expect("Could not find plaintext in data/tests directory.");
+    let key = TalosKey::Bits32(7);
+
+    for (dim, padding) in [(32, PaddingStrategy::Pkcs7), (64, PaddingStrategy::Zeros)] {
+        let options = EncryptOptions {
+            geometry: BlockGeometry::new(dim).unwrap(),
+            padding,
+            ..Default::default()
+        };
+        let ciphertext =
+            container::encrypt_with_options(message.clone(), &key, &options, &mut rand::rng())
+                .unwrap();
+        let header = Header::from_bytes(&ciphertext).unwrap();
+        assert_eq!(header.geometry(), Some(options.geometry));
+        assert_eq!(header.flags & FLAG_PKCS7 != 0, dim == 32);
+
+        let decrypted =
+            container::decrypt_with_options(ciphertext, &key, &DecryptOptions::default()).unwrap();
+        assert_eq!(message, decrypted);
+    }
+
+    // 64x64 blocks are too long for PKCS#7 padding
+    let options = EncryptOptions {
+        geometry: BlockGeometry::new(64).unwrap(),
+        padding: PaddingStrategy::Pkcs7,
+        ..Default::default()
+    };
+    assert!(matches!(
+        container::encrypt_with_options(message.clone(), &key, &options, &mut rand::rng()),
+        Err(ContainerError::InvalidPadding(PaddingError::BlockTooLarge()))
+    ));
+    assert!(matches!(
+        encrypt::pad_message(message.clone(), &options.geometry),
+        Err(PaddingError::BlockTooLarge())
+    ));
+
+    let options = EncryptOptions {
+        geometry: BlockGeometry::new(8).unwrap(),
+        ..Default::default()
+    };
+    assert!(container::encrypt_with_options(message, &key, &options, &mut rand::rng()).is_err());
+}
@@ -0,0 +1,155 @@
+// 2025 Steven Chiacchira
+use assert_fs::{fixture::PathChild, TempDir};
+use rand::{rngs::StdRng, SeedableRng};
+use std::fs;
+use talos::key::{self, KeyArgument, KeyError, KeyWidth, TalosKey, FINGERPRINT_SIZE};
+
+#[test]
+fn test_hex_keys() {
+    let key = KeyArgument::from_hex("2a000000").unwrap();
+    assert_eq!(key.get_key(KeyWidth::Bits32).unwrap(), TalosKey::Bits32(42));
+
+    let wide_key = TalosKey::Bits128([0xa5; 16]);
+    let key = KeyArgument::from_hex(&format!(" {}\n", wide_key)).unwrap();
+    assert_eq!(key.get_key(KeyWidth::Bits128).unwrap(), wide_key);
+
+    for hex in ["", "2a0", "2a00000g", "0x2a000000"] {
+        assert!(matches!(
+            KeyArgument::from_hex(hex),
+            Err(KeyError::InvalidHex())
+        ));
+    }
+
+    let key = KeyArgument::from_hex("2a000000").unwrap();
+    assert!(matches!(
+        key.get_key(KeyWidth::Bits128),
+        Err(KeyError::KeyLengthMismatch(KeyWidth::Bits128, 4))
+    ));
+
+    // keys which look like hex must be given with `from_hex`
+    for key in ["0x1F", "0X1f", "00ff", "deadbeef", "0xg"] {
+        assert!(matches!(
+            key.parse::<KeyArgument>(),
+            Err(KeyError::AmbiguousHex())
+        ));
+    }
+    for key in ["Foo", "Bar", "0042", "hex keys", "cafe passphrase"] {
+        assert!(key.parse::<KeyArgument>().is_ok());
+    }
+}
+
+#[test]
+fn test_key_files() {
+    let file_dir = TempDir::new().unwrap();
+    let key = TalosKey::Bits256(core::array::from_fn(|i| (i * 37) as u8));
+
+    let raw_file = file_dir.child("key.bin");
+    fs::write(raw_file.path(), key.to_bytes()).unwrap();
+    let hex_file = file_dir.child("key.hex");
+    fs::write(hex_file.path(), format!("{}\n", key)).unwrap();
+
+    for path in [raw_file.path(), hex_file.path()] {
+        let argument = KeyArgument::File(path.to_path_buf());
+        assert_eq!(argument.get_key(KeyWidth::Bits256).unwrap(), key);
+
+        let argument = KeyArgument::File(path.to_path_buf());
+        assert!(matches!(
+            argument.get_key(KeyWidth::Bits32),
+            Err(KeyError::KeyLengthMismatch(KeyWidth::Bits32, 32))
+        ));
+    }
+
+    // a typo in a hex key file is not read as raw key bytes
+    let typo_file = file_dir.child("typo.hex");
+    fs::write(typo_file.path(), "abc\n").unwrap();
+    let argument = KeyArgument::File(typo_file.path().to_path_buf());
+    assert!(matches!(
+        argument.get_key(KeyWidth::Bits32),
+        Err(KeyError::InvalidHex())
+    ));
+
+    let argument = KeyArgument::File(file_dir.child("missing").path().to_path_buf());
+    assert!(matches!(
+        argument.get_key(KeyWidth::Bits256),
+        Err(KeyError::UnreadableKeyFile(_, _))
+    ));
+
+    file_dir.close().unwrap();
+}
+
+#[test]
+fn test_env_keys() {
+    // no other test reads or writes this variable
+    std::env::set_var("TALOS_LIB_TEST_PASSPHRASE", "Foo");
+    let key = KeyArgument::Env("TALOS_LIB_TEST_PASSPHRASE".to_string());
+    assert!(key.is_passphrase());
+    assert_eq!(
+        key.get_key(KeyWidth::Bits32).unwrap(),
+        KeyArgument::String("Foo".to_string())
+            .get_key(KeyWidth::Bits32)
+            .unwrap()
+    );
+
+    let key = KeyArgument::Env("TALOS_LIB_TEST_UNSET_VARIABLE".to_string());
+    assert!(matches!(
+        key.get_key(KeyWidth::Bits32),
+        Err(KeyError::MissingEnvVar(_))
+    ));
+}
+
+#[test]
+fn test_fingerprints() {
+    let key = TalosKey::Bits32(42);
+    assert_eq!(key.to_hex(), "2a000000");
+    // the first bytes of the SHA-256 hash of the key bytes 2a000000
+    assert_eq!(key.fingerprint(), "e8a4b2ee7ede79a3");
+    assert_eq!(key.fingerprint().len(), 2 * FINGERPRINT_SIZE);
+    assert_ne!(key.fingerprint(), TalosKey::Bits32(43).fingerprint());
+
+    let wide_key = TalosKey::Bits128([0xa5; 16]);
+    assert_eq!(wide_key.to_hex(), wide_key.to_string());
+    assert!(!wide_key.fingerprint().contains(&wide_key.to_hex()[..8]));
+}
+
+#[test]
+fn test_wipe() {
+    let mut key = TalosKey::Bits256([0xa5; 32]);
+    key.wipe();
+    assert_eq!(key, TalosKey::Bits256([0; 32]));
+
+    let mut key = TalosKey::Bits32(42);
+    key.wipe();
+    assert_eq!(key, TalosKey::Bits32(0));
+
+    let mut argument = KeyArgument::Hex(vec![1, 2, 3, 4]);
+    argument.wipe();
+    assert!(matches!(&argument, KeyArgument::Hex(bytes) if bytes.iter().all(|b| *b == 0)));
+
+    let mut argument = KeyArgument::String("Foo".to_string());
+    argument.wipe();
+    assert!(matches!(&argument, KeyArgument::String(passphrase) if passphrase.is_empty()));
+}
+
+#[test]
+fn test_generate_key() {
+    for width in [KeyWidth::Bits32, KeyWidth::Bits128, KeyWidth::Bits256] {
+        assert_eq!(key::generate_key(width).width(), width);
+
+        // an injected generator makes key generation deterministic
+        let key_1 = key::generate_key_with_rng(width, &mut StdRng::seed_from_u64(20));
+        let key_2 = key::generate_key_with_rng(width, &mut StdRng::seed_from_u64(20));
+        let key_3 = key::generate_key_with_rng(width, &mut StdRng::seed_from_u64(21));
+        assert_eq!(key_1.width(), width);
+        assert_eq!(key_1, key_2);
+        assert_ne!(key_1, key_3);
+    }
+
+    // keys, nonces and salts are drawn from the start of the generator's output
+    let key = key::generate_key_with_rng(KeyWidth::Bits128, &mut StdRng::seed_from_u64(20));
+    let bytes: [u8; 16] = key::random_bytes(&mut StdRng::seed_from_u64(20));
+    assert_eq!(*key.to_bytes(), bytes);
+    assert_ne!(
+        key::random_bytes::<16, _>(&mut key::os_rng()),
+        key::random_bytes::<16, _>(&mut key::os_rng())
+    );
+}
@@ -1,2 +1,37 @@
 // 2025 Steven Chiacchira
+use talos::encrypt::{
+    self, BlockGeometry, CipherParams, GeometryError, ProtocolVariant, TalosAutomaton,
+    GEOMETRY_256, NONCE_SIZE,
+};
+use talos::key::TalosKey;
+
+mod container;
 mod encryption_decryption;
+mod geometry;
+mod key;
+mod params;
+mod scrambling;
+mod seeding;
+mod stream;
+
+/// Prepares the automata of RFC-1 for the 32 bit key `seed`, seeded with `nonce` if given.
+fn rfc1_automata(seed: u32, nonce: Option<&[u8; NONCE_SIZE]>) -> (TalosAutomaton, TalosAutomaton) {
+    encrypt::get_shift_transpose_automata_with_params(
+        &CipherParams::rfc1(),
+        &GEOMETRY_256,
+        &TalosKey::Bits32(seed),
+        nonce,
+    )
+    .unwrap()
+}
+
+/// Prepares the automata of the built-in initialization matrices for `geometry` and the width of
+/// `key`, seeded with `nonce` if given.
+fn geometry_automata(
+    geometry: &BlockGeometry,
+    key: &TalosKey,
+    nonce: Option<&[u8; NONCE_SIZE]>,
+) -> Result<(TalosAutomaton, TalosAutomaton), GeometryError> {
+    let params = CipherParams::for_geometry(geometry, key.width(), ProtocolVariant::Rfc1)?;
+    encrypt::get_shift_transpose_automata_with_params(&params, geometry, key, nonce)
+}
@@ -0,0 +1,200 @@
+// 2025 Steven Chiacchira
+use super::rfc1_automata;
+use std::borrow::Cow;
+use std::fs;
+use std::io::{Read, Write};
+use std::num::NonZeroUsize;
+use talos::automata::AutomatonRule;
+use talos::container::{self, ContainerError, DecryptOptions, EncryptOptions};
+use talos::encrypt::{
+    self, BlockGeometry, CipherParams, ProtocolVariant, Scrambler, GEOMETRY_256,
+    S_INIT_MATRIX_32X32, T_INIT_MATRIX_32X32,
+};
+use talos::key::{KeyArgument, KeyWidth, TalosKey};
+use talos::seeding::SeedingSchedule;
+use talos::stream::{TalosDecryptor, TalosEncryptor};
+
+fn custom_params() -> CipherParams {
+    CipherParams {
+        // Conway's Game of Life
+        rule: "B3/S23".parse::<AutomatonRule>().unwrap(),
+        s_init_matrix: Cow::Borrowed(encrypt::T_INIT_MATRIX),
+        t_init_matrix: Cow::Borrowed(encrypt::S_INIT_MATRIX),
+        iters_per_block: 7,
+        schedule: SeedingSchedule::smeared(NonZeroUsize::new(4).unwrap(), 3),
+        scrambler: Scrambler::SwapRotate,
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_rfc1_params_known_answers() {
+    let message_file = concat!(env!("CARGO_MANIFEST_DIR"), "/data/tests/text_01.txt");
+    let message =
+        fs::read(message_file).expect("Could not find plaintext in data/tests directory.");
+
+    for (params, prefix) in [
+        (
+            CipherParams::for_variant(ProtocolVariant::LegacyRfc1),
+            "text_01_k",
+        ),
+        (CipherParams::rfc1(), "text_01_rfc1_k"),
+    ] {
+        for key in ["0", "1", "2", "Foo", "Bar"] {
+            let encrypted_file = env!("CARGO_MANIFEST_DIR").to_owned()
+                + &format!("/data/tests/{}{}.enc", prefix, key);
+            let expected = fs::read(encrypted_file)
+                .expect("Could not find ciphertext in data/tests directory.");
+            let key = TalosKey::Bits32(key.parse::<KeyArgument>().unwrap().get());
+
+            let (mut s_automaton, mut t_automaton) =
+                encrypt::get_shift_transpose_automata_with_params(
+                    &params,
+                    &GEOMETRY_256,
+                    &key,
+                    None,
+                )
+                .unwrap();
+            let ciphertext = encrypt::encrypt_message_with_params(
+                message.clone(),
+                &GEOMETRY_256,
+                &params,
+                &mut s_automaton,
+                &mut t_automaton,
+            );
+            assert_eq!(ciphertext, expected);
+        }
+    }
+}
+
+#[test]
+fn test_params_for_geometry() {
+    let geometry = BlockGeometry::new(32).unwrap();
+    let variant = ProtocolVariant::RotatingScramble;
+
+    assert_eq!(
+        CipherParams::for_geometry(&geometry, KeyWidth::Bits128, variant).unwrap(),
+        CipherParams {
+            s_init_matrix: Cow::Borrowed(S_INIT_MATRIX_32X32),
+            t_init_matrix: Cow::Borrowed(T_INIT_MATRIX_32X32),
+            ..CipherParams::for_variant(variant)
+        }
+    );
+    assert_eq!(
+        CipherParams::for_geometry(&GEOMETRY_256, KeyWidth::Bits32, ProtocolVariant::Rfc1).unwrap(),
+        CipherParams::rfc1()
+    );
+    assert!(CipherParams::for_geometry(
+        &BlockGeometry::new(8).unwrap(),
+        KeyWidth::Bits32,
+        ProtocolVariant::Rfc1
+    )
+    .is_err());
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_deprecated_constructor_matches_rfc1() {
+    for seed in [0, 1, 0xdead_beef] {
+        let (s_deprecated, t_deprecated) = encrypt::get_shift_transpose_automata(seed);
+        let (s_rfc1, t_rfc1) = rfc1_automata(seed, None);
+
+        assert_eq!(
+            s_deprecated.get_state().get_storage(),
+            s_rfc1.get_state().get_storage()
+        );
+        assert_eq!(
+            t_deprecated.get_state().get_storage(),
+            t_rfc1.get_state().get_storage()
+        );
+    }
+}
+
+#[test]
+fn test_custom_params_round_trip() {
+    let message: Vec<u8> = (0..200).map(|i| (i * 7 % 256) as u8).collect();
+    let key = TalosKey::Bits32(0xdead_beef);
+    let params = custom_params();
+
+    let (mut s_automaton, mut t_automaton) =
+        encrypt::get_shift_transpose_automata_with_params(&params, &GEOMETRY_256, &key, None)
+            .unwrap();
+    let ciphertext = encrypt::encrypt_message_with_params(
+        message.clone(),
+        &GEOMETRY_256,
+        &params,
+        &mut s_automaton,
+        &mut t_automaton,
+    );
+
+    let (mut s_automaton, mut t_automaton) = rfc1_automata(0xdead_beef, None);
+    let rfc1_ciphertext = encrypt::encrypt_message(
+        message.clone(),
+        &GEOMETRY_256,
+        ProtocolVariant::Rfc1,
+        &mut s_automaton,
+        &mut t_automaton,
+    );
+    assert_ne!(ciphertext, rfc1_ciphertext);
+
+    let (mut s_automaton, mut t_automaton) =
+        encrypt::get_shift_transpose_automata_with_params(&params, &GEOMETRY_256, &key, None)
+            .unwrap();
+    let decrypted = encrypt::decrypt_message_with_params(
+        ciphertext.clone(),
+        &GEOMETRY_256,
+        &params,
+        &mut s_automaton,
+        &mut t_automaton,
+    );
+    assert_eq!(message, decrypted[..message.len()]);
+
+    let (s_automaton, t_automaton) =
+        encrypt::get_shift_transpose_automata_with_params(&params, &GEOMETRY_256, &key, None)
+            .unwrap();
+    let mut encryptor =
+        TalosEncryptor::with_params(Vec::new(), &GEOMETRY_256, &params, s_automaton, t_automaton);
+    encryptor.write_all(&message).unwrap();
+    let streamed = encryptor.finish().unwrap();
+    assert_eq!(streamed, ciphertext);
+
+    let (s_automaton, t_automaton) =
+        encrypt::get_shift_transpose_automata_with_params(&params, &GEOMETRY_256, &key, None)
+            .unwrap();
+    let mut decryptor = TalosDecryptor::with_params(
+        streamed.as_slice(),
+        &GEOMETRY_256,
+        &params,
+        s_automaton,
+        t_automaton,
+    );
+    let mut decrypted = Vec::new();
+    decryptor.read_to_end(&mut decrypted).unwrap();
+    assert_eq!(message, decrypted[..message.len()]);
+}
+
+#[test]
+fn test_container_params() {
+    let message = b"Containers record the parameters they were encrypted with.".to_vec();
+    let key = TalosKey::Bits32(7);
+
+    // the header cannot record custom parameters, so they could never be decrypted
+    let options = EncryptOptions {
+        params: Some(custom_params()),
+        ..EncryptOptions::default()
+    };
+    assert!(matches!(
+        container::encrypt_with_options(message.clone(), &key, &options, &mut rand::rng()),
+        Err(ContainerError::UnsupportedParameters())
+    ));
+
+    let options = EncryptOptions {
+        params: Some(CipherParams::rfc1()),
+        ..EncryptOptions::default()
+    };
+    let ciphertext =
+        container::encrypt_with_options(message.clone(), &key, &options, &mut rand::rng()).unwrap();
+    let decrypted =
+        container::decrypt_with_options(ciphertext, &key, &DecryptOptions::default()).unwrap();
+    assert_eq!(message, decrypted);
+}
@@ -0,0 +1,174 @@
+// 2025 Steven Chiacchira
+use super::{geometry_automata, rfc1_automata};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::fs;
+use talos::container::{self, DecryptOptions, EncryptOptions, Header};
+use talos::encrypt::{self, BlockGeometry, ProtocolVariant, Scrambler, GEOMETRY_256};
+use talos::key::{KeyArgument, TalosKey};
+
+/// Every protocol variant, in order of identifier.
+const VARIANTS: [ProtocolVariant; 4] = [
+    ProtocolVariant::LegacyRfc1,
+    ProtocolVariant::LegacyRotatingScramble,
+    ProtocolVariant::Rfc1,
+    ProtocolVariant::RotatingScramble,
+];
+
+#[test]
+fn test_variant_scramblers() {
+    assert_eq!(
+        ProtocolVariant::LegacyRfc1.scrambler(),
+        Scrambler::LegacySwap
+    );
+    assert_eq!(ProtocolVariant::Rfc1.scrambler(), Scrambler::Swap);
+    assert_eq!(
+        ProtocolVariant::RotatingScramble.scrambler(),
+        Scrambler::SwapRotate
+    );
+    assert_eq!(
+        ProtocolVariant::LegacyRotatingScramble.scrambler(),
+        Scrambler::LegacySwapRotate
+    );
+    for (id, variant) in VARIANTS.into_iter().enumerate() {
+        assert_eq!(variant.id() as usize, id);
+        assert_eq!(ProtocolVariant::from_id(variant.id()), Some(variant));
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_scramble_round_trip() {
+    let mut rng = StdRng::seed_from_u64(9);
+
+    for (dim, variant) in [16, 32, 64]
+        .into_iter()
+        .flat_map(|dim| VARIANTS.map(|variant| (dim, variant)))
+    {
+        let geometry = BlockGeometry::new(dim).unwrap();
+        for _ in 0..4 {
+            let key = TalosKey::Bits32(rng.random());
+            let mut message = vec![0_u8; 3 * geometry.block_bytes()];
+            rng.fill(&mut message[..]);
+
+            let (mut s_automaton, mut t_automaton) =
+                geometry_automata(&geometry, &key, None).unwrap();
+            let ciphertext = encrypt::encrypt_message(
+                message.clone(),
+                &geometry,
+                variant,
+                &mut s_automaton,
+                &mut t_automaton,
+            );
+
+            let (mut s_automaton, mut t_automaton) =
+                geometry_automata(&geometry, &key, None).unwrap();
+            let decrypted = encrypt::decrypt_message(
+                ciphertext,
+                &geometry,
+                variant,
+                &mut s_automaton,
+                &mut t_automaton,
+            );
+
+            assert_eq!(message, decrypted);
+        }
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_scramblers_differ() {
+    let message_file = concat!(env!("CARGO_MANIFEST_DIR"), "/data/tests/text_01.txt");
+    let message =
+        fs::read(message_file).expect("Could not find plaintext in data/tests directory.");
+    let geometry = BlockGeometry::new(16).unwrap();
+
+    let ciphertexts: Vec<Vec<u8>> = VARIANTS
+        .into_iter()
+        .map(|variant| {
+            let (mut s_automaton, mut t_automaton) = rfc1_automata(5, None);
+            encrypt::encrypt_message(
+                message.clone(),
+                &geometry,
+                variant,
+                &mut s_automaton,
+                &mut t_automaton,
+            )
+        })
+        .collect();
+
+    for i in 0..ciphertexts.len() {
+        for j in i + 1..ciphertexts.len() {
+            assert_ne!(ciphertexts[i], ciphertexts[j]);
+        }
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_container_rotating_scramble() {
+    let mut rng = StdRng::seed_from_u64(10);
+    let message = b"Rotations mix bits across the rows and columns of a block.".to_vec();
+    let options = EncryptOptions {
+        variant: ProtocolVariant::RotatingScramble,
+        ..EncryptOptions::default()
+    };
+
+    for _ in 0..4 {
+        let key = TalosKey::Bits256(rng.random());
+        let container =
+            container::encrypt_with_options(message.clone(), &key, &options, &mut rng).unwrap();
+        let header = Header::from_bytes(&container).unwrap();
+        assert_eq!(header.variant, ProtocolVariant::RotatingScramble);
+
+        let decrypted =
+            container::decrypt_with_options(container, &key, &DecryptOptions::default()).unwrap();
+        assert_eq!(message, decrypted);
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_variant_known_answers() {
+    let message_file = concat!(env!("CARGO_MANIFEST_DIR"), "/data/tests/text_01.txt");
+    let message =
+        fs::read(message_file).expect("Could not find plaintext in data/tests directory.");
+
+    for (variant, prefix) in [
+        (ProtocolVariant::LegacyRfc1, "text_01_k"),
+        (ProtocolVariant::Rfc1, "text_01_rfc1_k"),
+        (
+            ProtocolVariant::LegacyRotatingScramble,
+            "text_01_rotating_legacy_k",
+        ),
+        (ProtocolVariant::RotatingScramble, "text_01_rotating_k"),
+    ] {
+        for key in ["0", "1", "2", "Foo", "Bar"] {
+            let encrypted_file = env!("CARGO_MANIFEST_DIR").to_owned()
+                + &format!("/data/tests/{}{}.enc", prefix, key);
+            let expected = fs::read(encrypted_file)
+                .expect("Could not find ciphertext in data/tests directory.");
+            let seed = key.parse::<KeyArgument>().unwrap().get();
+
+            let (mut s_automaton, mut t_automaton) = rfc1_automata(seed, None);
+            let ciphertext = encrypt::encrypt_message(
+                message.clone(),
+                &GEOMETRY_256,
+                variant,
+                &mut s_automaton,
+                &mut t_automaton,
+            );
+            assert_eq!(ciphertext, expected);
+
+            let (mut s_automaton, mut t_automaton) = rfc1_automata(seed, None);
+            let decrypted = encrypt::decrypt_message(
+                ciphertext,
+                &GEOMETRY_256,
+                variant,
+                &mut s_automaton,
+                &mut t_automaton,
+            );
+            assert_eq!(message, decrypted[..message.len()]);
+        }
+    }
+}
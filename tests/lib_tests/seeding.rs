@@ -0,0 +1,110 @@
+// 2025 Steven Chiacchira
+use super::rfc1_automata;
+use std::fs;
+use std::num::NonZeroUsize;
+use talos::encrypt::{self, CipherParams, ProtocolVariant, TalosAutomaton, GEOMETRY_256};
+use talos::key::TalosKey;
+use talos::seeding::{KeySlots, SeedingSchedule, SeedingStep};
+
+/// Prepares the automata of `params` for the 32 bit key `seed`.
+fn schedule_automata(seed: u32, params: &CipherParams) -> (TalosAutomaton, TalosAutomaton) {
+    encrypt::get_shift_transpose_automata_with_params(
+        params,
+        &GEOMETRY_256,
+        &TalosKey::Bits32(seed),
+        None,
+    )
+    .unwrap()
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_rfc1_schedule_matches_protocol() {
+    let message_file = concat!(env!("CARGO_MANIFEST_DIR"), "/data/tests/text_01.txt");
+    let message =
+        fs::read(message_file).expect("Could not find plaintext in data/tests directory.");
+    let expected_file = concat!(env!("CARGO_MANIFEST_DIR"), "/data/tests/text_01_k2.enc");
+    let expected = fs::read(expected_file).expect("Could not find ciphertext in data/tests.");
+
+    let params = CipherParams {
+        schedule: SeedingSchedule::rfc1(),
+        ..CipherParams::rfc1()
+    };
+    let (mut s_automaton, mut t_automaton) = schedule_automata(2, &params);
+    let ciphertext = encrypt::encrypt_message(
+        message,
+        &GEOMETRY_256,
+        ProtocolVariant::LegacyRfc1,
+        &mut s_automaton,
+        &mut t_automaton,
+    );
+
+    assert_eq!(ciphertext, expected);
+}
+
+#[test]
+fn test_smeared_schedule_changes_automata() {
+    let (rfc1_s, rfc1_t) = rfc1_automata(7, None);
+    let params = CipherParams {
+        schedule: SeedingSchedule::smeared(NonZeroUsize::new(32).unwrap(), 8),
+        ..CipherParams::rfc1()
+    };
+    let (smeared_s, smeared_t) = schedule_automata(7, &params);
+
+    assert_ne!(
+        rfc1_s.get_state().get_storage(),
+        smeared_s.get_state().get_storage()
+    );
+    assert_ne!(
+        rfc1_t.get_state().get_storage(),
+        smeared_t.get_state().get_storage()
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_encrypt_decrypt_custom_schedule() {
+    let message_file = concat!(env!("CARGO_MANIFEST_DIR"), "/data/tests/text_01.txt");
+    let message =
+        fs::read(message_file).expect("Could not find plaintext in data/tests directory.");
+    let schedule = SeedingSchedule {
+        steps: vec![
+            SeedingStep {
+                generations: 3,
+                slots: KeySlots::Every {
+                    offset: 0,
+                    step: NonZeroUsize::new(2).unwrap(),
+                },
+            },
+            SeedingStep {
+                generations: 5,
+                slots: KeySlots::Only(vec![1, 3, 5]),
+            },
+        ],
+        final_generations: 4,
+    };
+    let params = CipherParams {
+        schedule,
+        ..CipherParams::rfc1()
+    };
+
+    let (mut s_automaton, mut t_automaton) = schedule_automata(11, &params);
+    let ciphertext = encrypt::encrypt_message(
+        message.clone(),
+        &GEOMETRY_256,
+        ProtocolVariant::Rfc1,
+        &mut s_automaton,
+        &mut t_automaton,
+    );
+
+    let (mut s_automaton, mut t_automaton) = schedule_automata(11, &params);
+    let decrypted = encrypt::decrypt_message(
+        ciphertext,
+        &GEOMETRY_256,
+        ProtocolVariant::Rfc1,
+        &mut s_automaton,
+        &mut t_automaton,
+    );
+
+    assert_eq!(message, decrypted[..message.len()]);
+}
@@ -0,0 +1,469 @@
+// 2025 Steven Chiacchira
+use super::{geometry_automata, rfc1_automata};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::fs;
+use std::io::{self, Read, Write};
+use talos::container::{
+    self, ContainerError, DecryptOptions, EncryptOptions, Header, FLAG_LENGTH_TRAILER,
+    LENGTH_TRAILER_SIZE,
+};
+use talos::encrypt::{self, BlockGeometry, PaddingStrategy, ProtocolVariant, GEOMETRY_256};
+use talos::key::TalosKey;
+use talos::stream::{TalosDecryptor, TalosEncryptor};
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_encryptor_matches_encrypt_message() {
+    let mut rng = StdRng::seed_from_u64(11);
+
+    for len in [0, 1, 31, 32, 33, 100] {
+        let mut message = vec![0_u8; len];
+        rng.fill(&mut message[..]);
+
+        let (s_automaton, t_automaton) = rfc1_automata(3, None);
+        let mut encryptor = TalosEncryptor::new(
+            Vec::new(),
+            &GEOMETRY_256,
+            ProtocolVariant::Rfc1,
+            s_automaton,
+            t_automaton,
+        );
+        for chunk in message.chunks(7) {
+            encryptor.write_all(chunk).unwrap();
+        }
+        let ciphertext = encryptor.finish().unwrap();
+
+        let (mut s_automaton, mut t_automaton) = rfc1_automata(3, None);
+        let expected = encrypt::encrypt_message(
+            message,
+            &GEOMETRY_256,
+            ProtocolVariant::Rfc1,
+            &mut s_automaton,
+            &mut t_automaton,
+        );
+
+        assert_eq!(ciphertext, expected);
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_encryptor_padding_matches_pad_message_with() {
+    let message_file = concat!(env!("CARGO_MANIFEST_DIR"), "/data/tests/text_01.txt");
+    let message =
+        fs::read(message_file).expect("Could not find plaintext in data/tests directory.");
+
+    for padding in [
+        PaddingStrategy::Pkcs7,
+        PaddingStrategy::Zeros,
+        PaddingStrategy::Random,
+        PaddingStrategy::from_corpus("streams of words"),
+    ] {
+        let (s_automaton, t_automaton) = rfc1_automata(4, None);
+        let mut encryptor = TalosEncryptor::new(
+            Vec::new(),
+            &GEOMETRY_256,
+            ProtocolVariant::Rfc1,
+            s_automaton,
+            t_automaton,
+        );
+        encryptor.write_all(&message).unwrap();
+        let ciphertext = encryptor
+            .finish_padded(&padding, &mut StdRng::seed_from_u64(12))
+            .unwrap();
+
+        let (mut s_automaton, mut t_automaton) = rfc1_automata(4, None);
+        let padded = encrypt::pad_message_with(
+            message.clone(),
+            &padding,
+            &GEOMETRY_256,
+            &mut StdRng::seed_from_u64(12),
+        )
+        .unwrap();
+        let expected = encrypt::encrypt_message(
+            padded,
+            &GEOMETRY_256,
+            ProtocolVariant::Rfc1,
+            &mut s_automaton,
+            &mut t_automaton,
+        );
+
+        assert_eq!(ciphertext, expected);
+    }
+
+    // 64x64 blocks are too long for PKCS#7 padding
+    let geometry = BlockGeometry::new(64).unwrap();
+    let (s_automaton, t_automaton) =
+        geometry_automata(&geometry, &TalosKey::Bits32(4), None).unwrap();
+    let mut encryptor = TalosEncryptor::new(
+        Vec::new(),
+        &geometry,
+        ProtocolVariant::Rfc1,
+        s_automaton,
+        t_automaton,
+    );
+    encryptor.write_all(&message).unwrap();
+    let err = encryptor
+        .finish_padded(&PaddingStrategy::Pkcs7, &mut StdRng::seed_from_u64(12))
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
+
+/// Writer which fails every other call to `write`, and otherwise accepts at most 5 bytes.
+struct FlakyWriter {
+    output: Vec<u8>,
+    n_calls: usize,
+}
+
+impl Write for FlakyWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.n_calls += 1;
+        if self.n_calls % 2 == 1 {
+            return Err(io::Error::other("flaky"));
+        }
+        let n_bytes = buf.len().min(5);
+        self.output.extend_from_slice(&buf[..n_bytes]);
+
+        Ok(n_bytes)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_encryptor_retries_failed_writes() {
+    let mut rng = StdRng::seed_from_u64(13);
+    // whole blocks, so that finish has nothing left to write
+    let mut message = vec![0_u8; 3 * GEOMETRY_256.block_bytes()];
+    rng.fill(&mut message[..]);
+
+    let (s_automaton, t_automaton) = rfc1_automata(3, None);
+    let inner = FlakyWriter {
+        output: Vec::new(),
+        n_calls: 0,
+    };
+    let mut encryptor = TalosEncryptor::new(
+        inner,
+        &GEOMETRY_256,
+        ProtocolVariant::Rfc1,
+        s_automaton,
+        t_automaton,
+    );
+    let mut remaining = &message[..];
+    while !remaining.is_empty() {
+        // a failed write consumes no plaintext, so it can be retried as is
+        if let Ok(n_bytes) = encryptor.write(&remaining[..remaining.len().min(7)]) {
+            remaining = &remaining[n_bytes..];
+        }
+    }
+    while encryptor.flush().is_err() {}
+    let ciphertext = encryptor.finish().unwrap().output;
+
+    let (mut s_automaton, mut t_automaton) = rfc1_automata(3, None);
+    let expected = encrypt::encrypt_message(
+        message,
+        &GEOMETRY_256,
+        ProtocolVariant::Rfc1,
+        &mut s_automaton,
+        &mut t_automaton,
+    );
+
+    assert_eq!(ciphertext, expected);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_decryptor_round_trip() {
+    let mut rng = StdRng::seed_from_u64(13);
+    let geometry = BlockGeometry::new(32).unwrap();
+    let key = TalosKey::Bits128(rng.random());
+    let mut message = vec![0_u8; 5 * geometry.block_bytes()];
+    rng.fill(&mut message[..]);
+
+    let (mut s_automaton, mut t_automaton) = geometry_automata(&geometry, &key, None).unwrap();
+    let ciphertext = encrypt::encrypt_message(
+        message.clone(),
+        &geometry,
+        ProtocolVariant::RotatingScramble,
+        &mut s_automaton,
+        &mut t_automaton,
+    );
+
+    let (s_automaton, t_automaton) = geometry_automata(&geometry, &key, None).unwrap();
+    let mut decryptor = TalosDecryptor::new(
+        ciphertext.as_slice(),
+        &geometry,
+        ProtocolVariant::RotatingScramble,
+        s_automaton,
+        t_automaton,
+    );
+    let mut decrypted = Vec::new();
+    let mut buf = [0_u8; 13];
+    loop {
+        let n_bytes = decryptor.read(&mut buf).unwrap();
+        if n_bytes == 0 {
+            break;
+        }
+        decrypted.extend_from_slice(&buf[..n_bytes]);
+    }
+
+    assert_eq!(message, decrypted);
+}
+
+#[test]
+fn test_decryptor_partial_block() {
+    let (s_automaton, t_automaton) = rfc1_automata(5, None);
+    let ciphertext = [0_u8; 40];
+    let mut decryptor = TalosDecryptor::new(
+        &ciphertext[..],
+        &GEOMETRY_256,
+        ProtocolVariant::Rfc1,
+        s_automaton,
+        t_automaton,
+    );
+
+    let err = decryptor.read_to_end(&mut Vec::new()).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_encrypt_stream_matches_encrypt_with_options() {
+    let message_file = concat!(env!("CARGO_MANIFEST_DIR"), "/data/tests/text_01.txt");
+    let message =
+        fs::read(message_file).expect("Could not find plaintext in data/tests directory.");
+    let key = TalosKey::Bits32(6);
+    let options = EncryptOptions::default();
+
+    let container = container::encrypt_stream(
+        message.as_slice(),
+        Some(message.len() as u64),
+        Vec::new(),
+        &key,
+        &options,
+        &mut StdRng::seed_from_u64(14),
+    )
+    .unwrap();
+    let expected = container::encrypt_with_options(
+        message.clone(),
+        &key,
+        &options,
+        &mut StdRng::seed_from_u64(14),
+    )
+    .unwrap();
+    assert_eq!(container, expected);
+
+    assert!(matches!(
+        container::encrypt_stream(
+            message.as_slice(),
+            Some(message.len() as u64 + 1),
+            Vec::new(),
+            &key,
+            &options,
+            &mut StdRng::seed_from_u64(14),
+        ),
+        Err(ContainerError::InvalidLength())
+    ));
+    assert!(matches!(
+        container::encrypt_stream(
+            message.as_slice(),
+            Some(message.len() as u64 - 1),
+            Vec::new(),
+            &key,
+            &options,
+            &mut StdRng::seed_from_u64(14),
+        ),
+        Err(ContainerError::InvalidLength())
+    ));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_decrypt_stream_round_trip() {
+    let message_file = concat!(env!("CARGO_MANIFEST_DIR"), "/data/tests/text_01.txt");
+    let message =
+        fs::read(message_file).expect("Could not find plaintext in data/tests directory.");
+    let mut rng = StdRng::seed_from_u64(15);
+
+    for (padding, authenticate) in [
+        (PaddingStrategy::Pkcs7, true),
+        (PaddingStrategy::Random, true),
+        (PaddingStrategy::Pkcs7, false),
+    ] {
+        let key = TalosKey::Bits256(rng.random());
+        let options = EncryptOptions {
+            padding,
+            authenticate,
+            ..EncryptOptions::default()
+        };
+        let container =
+            container::encrypt_with_options(message.clone(), &key, &options, &mut rng).unwrap();
+        let decrypt_options = DecryptOptions {
+            require_authentication: authenticate,
+        };
+
+        let mut input = container.as_slice();
+        let header = Header::read(&mut input).unwrap();
+        assert_eq!(input.len(), container.len() - header.size());
+        let decrypted =
+            container::decrypt_stream(&header, input, Vec::new(), &key, &decrypt_options).unwrap();
+
+        assert_eq!(message, decrypted);
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_decrypt_stream_errors() {
+    let message = b"Streams are authenticated once the whole container is read.".to_vec();
+    let key = TalosKey::Bits32(7);
+    let container = container::encrypt(message, 7);
+    let header = Header::from_bytes(&container).unwrap();
+    let body = &container[header.size()..];
+    let decrypt = |body: &[u8], key: &TalosKey| {
+        container::decrypt_stream(&header, body, Vec::new(), key, &DecryptOptions::default())
+    };
+
+    assert!(matches!(
+        decrypt(body, &TalosKey::Bits32(8)),
+        Err(ContainerError::WrongKey())
+    ));
+
+    let mut tampered = body.to_vec();
+    tampered[3] ^= 1;
+    assert!(matches!(
+        decrypt(&tampered, &key),
+        Err(ContainerError::AuthenticationFailed())
+    ));
+
+    for len in [0, 10, body.len() - 1] {
+        assert!(matches!(
+            decrypt(&body[..len], &key),
+            Err(ContainerError::Truncated())
+        ));
+    }
+
+    let mut extended = body.to_vec();
+    extended.push(0);
+    assert!(matches!(
+        decrypt(&extended, &key),
+        Err(ContainerError::InvalidLength())
+    ));
+
+    assert!(matches!(
+        Header::read(&mut &container[..10]),
+        Err(ContainerError::Truncated())
+    ));
+    assert!(matches!(
+        Header::read(&mut &b"NOT A TALOS CONTAINER AT ALL"[..]),
+        Err(ContainerError::BadMagic())
+    ));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_length_trailer_round_trip() {
+    let message_file = concat!(env!("CARGO_MANIFEST_DIR"), "/data/tests/text_01.txt");
+    let message =
+        fs::read(message_file).expect("Could not find plaintext in data/tests directory.");
+    let mut rng = StdRng::seed_from_u64(16);
+
+    for (padding, authenticate, dim) in [
+        (PaddingStrategy::Pkcs7, true, 16),
+        (PaddingStrategy::Zeros, true, 32),
+        (PaddingStrategy::Random, false, 16),
+        (PaddingStrategy::Zeros, false, 64),
+    ] {
+        let options = EncryptOptions {
+            padding,
+            authenticate,
+            geometry: BlockGeometry::new(dim).unwrap(),
+            ..EncryptOptions::default()
+        };
+        let decrypt_options = DecryptOptions {
+            require_authentication: authenticate,
+        };
+        for len in [0, 1, 31, 32, 33, message.len()] {
+            let key = TalosKey::Bits32(rng.random());
+            let container = container::encrypt_stream(
+                &message[..len],
+                None,
+                Vec::new(),
+                &key,
+                &options,
+                &mut rng,
+            )
+            .unwrap();
+            let header = Header::from_bytes(&container).unwrap();
+            assert_ne!(header.flags & FLAG_LENGTH_TRAILER, 0);
+            assert_eq!(header.plaintext_len, 0);
+
+            let decrypted = container::decrypt_stream(
+                &header,
+                &container[header.size()..],
+                Vec::new(),
+                &key,
+                &decrypt_options,
+            )
+            .unwrap();
+            assert_eq!(&message[..len], decrypted);
+            assert_eq!(
+                container::decrypt_with_options(container, &key, &decrypt_options).unwrap(),
+                decrypted
+            );
+        }
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_length_trailer_errors() {
+    let message = b"Piped plaintexts have their length written after the ciphertext.".to_vec();
+    let key = TalosKey::Bits32(8);
+    let container = container::encrypt_stream(
+        message.as_slice(),
+        None,
+        Vec::new(),
+        &key,
+        &EncryptOptions::default(),
+        &mut StdRng::seed_from_u64(17),
+    )
+    .unwrap();
+    let header = Header::from_bytes(&container).unwrap();
+    let body = &container[header.size()..];
+    let decrypt = |body: &[u8], key: &TalosKey| {
+        container::decrypt_stream(&header, body, Vec::new(), key, &DecryptOptions::default())
+    };
+    assert_eq!(decrypt(body, &key).unwrap(), message);
+
+    assert!(matches!(
+        decrypt(body, &TalosKey::Bits32(9)),
+        Err(ContainerError::WrongKey())
+    ));
+
+    // the length trailer is covered by the authentication tag
+    let mut tampered = body.to_vec();
+    let trailer_start = body.len() - LENGTH_TRAILER_SIZE - 32;
+    tampered[trailer_start] ^= 1;
+    assert!(matches!(
+        decrypt(&tampered, &key),
+        Err(ContainerError::AuthenticationFailed())
+    ));
+
+    for len in [0, 10, LENGTH_TRAILER_SIZE + 31, body.len() - 1] {
+        assert!(matches!(
+            decrypt(&body[..len], &key),
+            Err(ContainerError::Truncated())
+        ));
+    }
+
+    let mut lying = header.clone();
+    lying.plaintext_len = 1;
+    assert!(matches!(
+        container::decrypt_stream(&lying, body, Vec::new(), &key, &DecryptOptions::default()),
+        Err(ContainerError::InvalidLength())
+    ));
+}